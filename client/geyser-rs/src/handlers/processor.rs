@@ -1,18 +1,43 @@
 use crate::utils::blocktime::{prepare_log_message, TransactionsBySlot};
-use solana_stream_sdk::GeyserSubscribeUpdate;
+use log::{info, warn};
+use solana_stream_sdk::txn::ProgramWatchConfig;
+use solana_stream_sdk::watch_sink::watch_event_json;
+use solana_stream_sdk::{
+    collect_watch_events_from_geyser, GeyserSubscribeUpdate, GeyserUpdateOneof,
+};
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 pub async fn process_updates(
     mut updates_rx: mpsc::Receiver<GeyserSubscribeUpdate>,
     transactions_by_slot: TransactionsBySlot,
+    watch_cfg: Option<Arc<ProgramWatchConfig>>,
 ) {
     while let Some(update) = updates_rx.recv().await {
-        handle_update(&update, &transactions_by_slot);
+        handle_update(&update, &transactions_by_slot, watch_cfg.as_deref());
     }
 }
 
-fn handle_update(update: &GeyserSubscribeUpdate, transactions_by_slot: &TransactionsBySlot) {
+fn handle_update(
+    update: &GeyserSubscribeUpdate,
+    transactions_by_slot: &TransactionsBySlot,
+    watch_cfg: Option<&ProgramWatchConfig>,
+) {
     // TODO: Add your trade logic here. This is the main hook for every update.
     // Match on update.update_oneof and branch per event type as needed.
     prepare_log_message(update, transactions_by_slot);
+
+    // Same detectors and events as the shreds-udp path (see `watch` in config.jsonc).
+    if let (Some(watch_cfg), Some(GeyserUpdateOneof::Transaction(tx_update))) =
+        (watch_cfg, &update.update_oneof)
+    {
+        match collect_watch_events_from_geyser(tx_update, watch_cfg) {
+            Ok(events) => {
+                for event in events {
+                    info!("🎯 {}", watch_event_json(&event));
+                }
+            }
+            Err(err) => warn!("Skipping undecodable transaction: {}", err),
+        }
+    }
 }
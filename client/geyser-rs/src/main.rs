@@ -29,6 +29,11 @@ async fn main() -> Result<(), anyhow::Error> {
     let config_content = fs::read_to_string(&settings.config_path)?;
     let config: Config = serde_jsonc::from_str(&config_content)?;
     let request = build_subscribe_request(&config);
+    let watch_cfg = config
+        .watch
+        .as_ref()
+        .map(|watch| watch.program_watch_config().map(Arc::new))
+        .transpose()?;
 
     let transactions_by_slot = create_transactions_by_slot();
     let block_time_cache = BlockTimeCache::new(&settings.rpc_endpoint);
@@ -47,7 +52,7 @@ async fn main() -> Result<(), anyhow::Error> {
         // Add your detection/trade logic in src/handlers/processor.rs.
         let transactions_by_slot = transactions_by_slot.clone();
         tokio::spawn(async move {
-            process_updates(updates_rx, transactions_by_slot).await;
+            process_updates(updates_rx, transactions_by_slot, watch_cfg).await;
         })
     };
 
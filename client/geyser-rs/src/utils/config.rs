@@ -1,4 +1,7 @@
+use anyhow::Context;
 use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_stream_sdk::txn::ProgramWatchConfig;
 use solana_stream_sdk::GeyserCommitmentLevel;
 use solana_stream_sdk::{
     GeyserAccountsFilterEnum, GeyserLamportsCmp, GeyserMemcmpData,
@@ -9,6 +12,7 @@ use solana_stream_sdk::{
     GeyserSubscribeRequestFilterSlots, GeyserSubscribeRequestFilterTransactions,
 };
 use std::collections::HashMap;
+use std::str::FromStr;
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -19,6 +23,34 @@ pub struct Config {
     pub blocks: HashMap<String, BlockFilter>,
     pub blocks_meta: HashMap<String, BlockMetaFilter>,
     pub entry: HashMap<String, EntryFilter>,
+    /// Run transaction updates through the SDK watch detectors (same events as shreds-udp).
+    #[serde(default)]
+    pub watch: Option<WatchConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchConfig {
+    #[serde(default)]
+    pub program_ids: Vec<String>,
+    #[serde(default)]
+    pub authorities: Vec<String>,
+}
+
+impl WatchConfig {
+    pub fn program_watch_config(&self) -> anyhow::Result<ProgramWatchConfig> {
+        let parse = |keys: &[String], field: &str| {
+            keys.iter()
+                .map(|key| {
+                    Pubkey::from_str(key)
+                        .with_context(|| format!("watch.{}: invalid pubkey {:?}", field, key))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        Ok(ProgramWatchConfig::new(
+            parse(&self.program_ids, "program_ids")?,
+            parse(&self.authorities, "authorities")?,
+        ))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -1,7 +1,10 @@
 use blocktime::prepare_log_message;
 use chrono::{DateTime, Utc};
 use futures::StreamExt;
-use solana_stream_sdk::{CommitmentLevel, ShredstreamClient};
+use solana_stream_sdk::{
+    decode_slot_entry, CommitmentLevel, ConnectionEvent, EntriesRequestBuilder, ReconnectPolicy,
+    ShredstreamClient, ShredstreamEvent,
+};
 use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
@@ -16,17 +19,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let endpoint = env::var("SHREDS_ENDPOINT")
         .unwrap_or_else(|_| "https://shreds-ams-9.erpc.global".to_string());
 
-    let mut client = ShredstreamClient::connect(&endpoint).await?;
+    let mut builder = ShredstreamClient::builder(&endpoint);
+    if let Ok(token) = env::var("SHREDS_X_TOKEN") {
+        builder = builder.with_x_token(token);
+    }
+    let client = builder.connect().await?;
 
     // The filter is experimental
-    let request = ShredstreamClient::create_entries_request_for_accounts(
-        vec![],
-        vec![],
-        vec![],
-        Some(CommitmentLevel::Processed),
-    );
+    let request = EntriesRequestBuilder::new()
+        .with_commitment(CommitmentLevel::Processed)
+        .build()?;
 
-    let mut stream = client.subscribe_entries(request).await?;
+    // Reconnects with backoff and re-sends the request whenever the stream drops.
+    let mut stream = client.subscribe_entries_reconnecting(request, ReconnectPolicy::default());
 
     let transactions_by_slot = Arc::new(Mutex::new(
         HashMap::<u64, Vec<(String, DateTime<Utc>)>>::new(),
@@ -45,36 +50,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let stream_handle = tokio::spawn(async move {
-        while let Some(slot_entry) = stream.next().await {
-            match slot_entry {
-                Ok(data) => {
-                    let slot = data.slot;
-                    prepare_log_message(slot, &transactions_by_slot).await;
-
-                    // You can see data with deserializing like below
-                    // let entries =
-                    //     match bincode::deserialize::<Vec<solana_entry::entry::Entry>>(&slot_entry.entries) {
-                    //         Ok(e) => e,
-                    //         Err(e) => {
-                    //             println!("Deserialization failed with err: {e}");
-                    //             continue;
-                    //         }
-                    //     };
-                    // let transactions = entries
-                    //     .iter()
-                    //     .flat_map(|e| e.transactions.iter())
-                    //     .collect::<Vec<_>>();
-                    // println!("transactions: {:?}", transactions[0]);
-                    // println!(
-                    //     "slot {}, entries: {}, transactions: {}",
-                    //     slot_entry.slot,
-                    //     entries.len(),
-                    //     entries.iter().map(|e| e.transactions.len()).sum::<usize>()
-                    // );
-                }
-                Err(_) => {
-                    break;
+        while let Some(event) = stream.next().await {
+            match event {
+                ShredstreamEvent::Entry(entry) => match decode_slot_entry(&entry) {
+                    Ok((slot, entries)) => {
+                        prepare_log_message(slot, &transactions_by_slot).await;
+                        log::debug!(
+                            "slot {}, entries: {}, transactions: {}",
+                            slot,
+                            entries.len(),
+                            entries.iter().map(|e| e.transactions.len()).sum::<usize>()
+                        );
+                    }
+                    // Undecodable payloads only affect their own slot.
+                    Err(e) => log::warn!("{}", e),
+                },
+                ShredstreamEvent::Connection(ConnectionEvent::Gap {
+                    last_slot,
+                    next_slot,
+                }) => {
+                    log::warn!("missed slots {}..{}", last_slot + 1, next_slot);
                 }
+                ShredstreamEvent::Connection(change) => log::info!("{:?}", change),
             }
         }
     });
@@ -70,18 +70,18 @@ async fn handle_ready_batch(
                 );
             }
 
-            if !(watch_cfg.program_ids.is_empty() && watch_cfg.authorities.is_empty()) {
+            if !(watch_cfg.program_ids().is_empty() && watch_cfg.authorities().is_empty()) {
                 for event in collect_watch_events(key.slot, &txs, watch_cfg.as_ref(), 0) {
                     for detail in &event.details {
                         info!(
-                            "hit slot={} sig={} mint={} label={:?} action={:?} lamports={:?} token_amount={:?}",
+                            "hit slot={} sig={} mint={} protocol={:?} action={:?} lamports={:?} token_amount={:?}",
                             event.slot,
                             event.hit.signature,
                             detail.mint,
-                            detail.label,
+                            detail.protocol,
                             detail.action,
-                            detail.sol_amount,
-                            detail.token_amount,
+                            detail.lamports(),
+                            detail.token_amount.map(|a| a.value),
                         );
                     }
                 }
@@ -4,21 +4,16 @@ use log::{error, info};
 use solana_stream_sdk::{
     shreds_udp::{
         collect_watch_events, decode_udp_datagram, deshred_shreds_to_entries, insert_shred,
-        latency_monitor_task, DeshredPolicy, ShredInsertOutcome, ShredReadyBatch, ShredSource,
-        ShredsUdpConfig, ShredsUdpState, WatchEvent, log_watch_events,
+        latency_monitor_task, log_collected_watch_events, DeshredPolicy, ShredInsertOutcome,
+        ShredReadyBatch, ShredSource, ShredsUdpConfig, ShredsUdpState,
     },
-    UdpShredReceiver,
+    txn::SlotTips,
+    ConfigWatcher, UdpShredReceiver, WatchSinks,
 };
-use std::sync::Arc;
 use tokio::signal;
 
 const EMBEDDED_CONFIG: &str = include_str!("../settings.jsonc");
-async fn handle_ready_batch(
-    ready: ShredReadyBatch,
-    state: &ShredsUdpState,
-    cfg: &ShredsUdpConfig,
-    watch_cfg: &Arc<solana_stream_sdk::txn::ProgramWatchConfig>,
-) {
+async fn handle_ready_batch(ready: ShredReadyBatch, state: &ShredsUdpState, cfg: &ShredsUdpConfig) {
     let key = ready.key;
     match deshred_shreds_to_entries(&ready.shreds) {
         Ok(entries) => {
@@ -31,19 +26,30 @@ async fn handle_ready_batch(
                 txs.len()
             );
 
-            // Default logging (honors pump_min_lamports). This is the first sink; swap or extend with
-            // custom logic below if you need additional actions.
-            log_watch_events(
-                key.slot,
-                &txs,
-                watch_cfg.as_ref(),
-                cfg.log_watch_hits,
-                cfg.pump_min_lamports,
-            );
-            // Structured hits for custom hooks; use this to attach your own side-effects.
-            let events =
-                collect_watch_events(key.slot, &txs, watch_cfg.as_ref(), cfg.pump_min_lamports);
-            maybe_custom_watch_hook(&events, cfg.pump_min_lamports);
+            // Watch list and thresholds are read per batch so config reloads apply without a restart.
+            let watch = state.watch_settings().load();
+            let watch_cfg = watch.watch_cfg.as_ref();
+
+            // Detect once per batch; the events feed both the default logging and the sinks.
+            if watch.log_watch_hits || !state.sinks().is_empty() {
+                let events =
+                    collect_watch_events(key.slot, &txs, watch_cfg, watch.pump_min_lamports);
+                // Default logging (honors pump_min_lamports). This is the first sink; swap or
+                // extend with custom logic below if you need additional actions.
+                if watch.log_watch_hits {
+                    log_collected_watch_events(key.slot, &events, watch.pump_min_lamports);
+                }
+                // Structured hits go to the sinks declared under `sinks` in settings.jsonc
+                // (stdout/file/webhook/udp). Push your own `WatchSink` onto `WatchSinks` in main().
+                state.sinks().dispatch(&events);
+            }
+            // Per-slot Jito tip totals (logged with 💰 when hit logging is on).
+            state
+                .record_slot_tips(
+                    SlotTips::from_entries(key.slot, &entries, &watch_cfg.jito_tip_accounts),
+                    watch.log_watch_hits,
+                )
+                .await;
 
             if cfg.log_entries {
                 let sigs: Vec<String> = solana_stream_sdk::txn::first_signatures(
@@ -60,6 +66,9 @@ async fn handle_ready_batch(
                 );
             }
 
+            // Feed the local ShredstreamProxy server (no-op unless `proxy` is configured).
+            state.publish_entries(key.slot, entries);
+
             state.remove_batch(&key).await;
             if matches!(ready.source, ShredSource::Data) {
                 state.mark_completed(key).await;
@@ -78,33 +87,10 @@ async fn handle_ready_batch(
     }
 }
 
-/// Optional sample hook to show where custom actions can be triggered after detection.
-/// Enable by setting `SHREDS_UDP_CUSTOM_HOOK=1`. Replace the body with your own sink.
-fn maybe_custom_watch_hook(events: &[WatchEvent], pump_min_lamports: u64) {
-    if std::env::var("SHREDS_UDP_CUSTOM_HOOK").is_err() {
-        return;
-    }
-    // This is the second “sink” point: structured hits. Use it to send alerts/txs/etc.
-    // It already respects pump_min_lamports (only logs hits at/above the threshold if amounts exist).
-    for event in events.iter() {
-        for detail in event.details.iter().filter(|d| {
-            pump_min_lamports == 0
-                || d.sol_amount
-                    .map(|l| l >= pump_min_lamports)
-                    .unwrap_or(true)
-        }) {
-            info!(
-                "[custom hook] slot={} sig={} mint={} action={:?} sol_amount={:?} token_amount={:?}",
-                event.slot,
-                event.hit.signature,
-                detail.mint,
-                detail.action,
-                detail.sol_amount,
-                detail.token_amount
-            );
-            // Place your own side-effects here (queue, RPC call, on-chain tx, etc.).
-        }
-    }
+/// Build the sinks declared under `sinks`, routing those that name `rules` through the
+/// compiled watch rules.
+fn build_sinks(cfg: &ShredsUdpConfig) -> solana_stream_sdk::Result<WatchSinks> {
+    WatchSinks::from_configs_with_rules(&cfg.sinks, &cfg.watch_rules()?)
 }
 
 fn describe_status(st: &solana_stream_sdk::shreds_udp::BatchStatus) -> String {
@@ -114,20 +100,32 @@ fn describe_status(st: &solana_stream_sdk::shreds_udp::BatchStatus) -> String {
     )
 }
 
-async fn shutdown_signal() {
+/// Wait for Ctrl+C or SIGTERM, and for SIGHUP unless a `ConfigWatcher` uses it for reloads.
+async fn shutdown_signal(hangup_reloads: bool) {
     #[cfg(unix)]
     {
         use tokio::signal::unix::{signal, SignalKind};
         let mut term = signal(SignalKind::terminate()).expect("create SIGTERM listener");
-        let mut hup = signal(SignalKind::hangup()).expect("create SIGHUP listener");
+        let mut hup = if hangup_reloads {
+            None
+        } else {
+            Some(signal(SignalKind::hangup()).expect("create SIGHUP listener"))
+        };
+        let hangup = async {
+            match hup.as_mut() {
+                Some(hup) => hup.recv().await,
+                None => std::future::pending().await,
+            }
+        };
         tokio::select! {
             _ = signal::ctrl_c() => {},
             _ = term.recv() => {},
-            _ = hup.recv() => {},
+            _ = hangup => {},
         }
     }
     #[cfg(not(unix))]
     {
+        let _ = hangup_reloads;
         signal::ctrl_c()
             .await
             .expect("failed to install CTRL+C handler");
@@ -139,19 +137,82 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     dotenv().ok();
     env_logger::init();
 
-    // Always start from the crate-local settings.jsonc (next to Cargo.toml), embedded at build time.
-    let cfg = ShredsUdpConfig::from_embedded(EMBEDDED_CONFIG);
+    // Start from the crate-local settings.jsonc (next to Cargo.toml), embedded at build time,
+    // unless SHREDS_UDP_CONFIG points at a file; that file is then watched for live reloads.
+    // Settings are validated strictly: any unknown key or bad value aborts startup.
+    let check_only = std::env::args().skip(1).any(|arg| arg == "--check-config");
+    let config_path = std::env::var("SHREDS_UDP_CONFIG").ok();
+    let source = config_path.as_deref().unwrap_or("embedded settings.jsonc");
+    let loaded = match config_path.as_deref() {
+        Some(path) => ShredsUdpConfig::try_from_file(path),
+        None => ShredsUdpConfig::try_from_embedded(EMBEDDED_CONFIG),
+    };
+    let cfg = match loaded {
+        Ok(cfg) if check_only => {
+            println!("{}: OK\n{}", source, cfg.describe());
+            return Ok(());
+        }
+        Ok(cfg) => {
+            info!("Loaded {} | {}", source, cfg.describe());
+            cfg
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let receiver = UdpShredReceiver::bind(&cfg.bind_addr, None).await?;
     let local_addr = receiver.local_addr()?;
     info!("Listening for UDP shreds on {}", local_addr);
-    info!("Ensure the sender targets this ip:port.");
+    // Keep the heartbeat handle alive until shutdown; dropping it stops the heartbeats.
+    let mut heartbeat = match &cfg.heartbeat {
+        Some(hb) => {
+            let client = hb.client(local_addr.port())?;
+            info!(
+                "Sending Shredstream heartbeats for {} to {}",
+                client.socket(),
+                hb.endpoint
+            );
+            Some(client.spawn())
+        }
+        None => {
+            info!("Ensure the sender targets this ip:port.");
+            None
+        }
+    };
 
     // Configurable flags are still easy to tweak here before starting the loop.
     let policy = DeshredPolicy {
         require_code_match: cfg.require_code_match,
     };
-    let watch_cfg = Arc::new(cfg.watch_config());
-    let state = ShredsUdpState::new(&cfg);
+    let sinks = build_sinks(&cfg)?;
+    if !sinks.is_empty() {
+        info!("Watch sinks enabled: {}", sinks.len());
+    }
+    let state = ShredsUdpState::new(&cfg).with_sinks(sinks);
+
+    let mut proxy_handle = match &cfg.proxy {
+        Some(proxy) => {
+            let listener = tokio::net::TcpListener::bind(&proxy.bind_addr).await?;
+            info!(
+                "Serving ShredstreamProxy SubscribeEntries on {}",
+                listener.local_addr()?
+            );
+            let service = proxy.service();
+            service.spawn_feed(state.subscribe_entries());
+            Some(tokio::spawn(async move {
+                if let Err(e) = service.serve_listener(listener).await {
+                    error!("ShredstreamProxy server stopped: {}", e);
+                }
+            }))
+        }
+        None => None,
+    };
+
+    let mut reload_handle = config_path.map(|path| {
+        info!("Watching {} for changes (or send SIGHUP to reload)", path);
+        ConfigWatcher::new(path, state.watch_settings().clone()).spawn()
+    });
 
     let mut latency_handle = if let (true, Some(cache), Some(txs)) = (
         cfg.enable_latency_monitor,
@@ -165,7 +226,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     let mut recv_handle = Some({
         let state = state.clone();
-        let watch_cfg = watch_cfg.clone();
         let cfg = cfg.clone();
         let mut receiver = receiver;
         tokio::spawn(async move {
@@ -212,7 +272,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                                     );
                                 }
                             }
-                            handle_ready_batch(ready, &state, &cfg, &watch_cfg).await;
+                            handle_ready_batch(ready, &state, &cfg).await;
                         }
                         ShredInsertOutcome::Deferred {
                             key,
@@ -245,10 +305,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     });
 
     tokio::select! {
-        _ = shutdown_signal() => {
+        _ = shutdown_signal(reload_handle.is_some()) => {
             info!("Shutdown signal received, stopping tasks...");
             if let Some(handle) = recv_handle.take() { handle.abort(); }
             if let Some(handle) = latency_handle.take() { handle.abort(); }
+            if let Some(handle) = reload_handle.take() { handle.abort(); }
+            if let Some(handle) = proxy_handle.take() { handle.abort(); }
+            if let Some(handle) = heartbeat.take() { handle.stop(); }
         }
         res = async {
             match (latency_handle.take(), recv_handle.take()) {
@@ -270,3 +333,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sinks_route_by_watch_rules() {
+        let cfg = ShredsUdpConfig::try_from_embedded(
+            r#"{
+                "rules": { "whale_buys": "action == \"buy\" && sol_amount >= 5 SOL" },
+                "sinks": [{ "type": "stdout", "rules": ["whale_buys"] }]
+            }"#,
+        )
+        .expect("settings with rules");
+
+        let sinks = build_sinks(&cfg).expect("sinks with rules");
+        assert_eq!(sinks.len(), 1);
+    }
+}
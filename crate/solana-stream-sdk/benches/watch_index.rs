@@ -0,0 +1,79 @@
+//! Per-transaction cost of `detect_program_hit` as the watch lists grow.
+//!
+//! `cargo bench -p solana-stream-sdk --bench watch_index` prints ns/tx for each list size,
+//! with and without the cuckoo prefilter. Every transaction misses, which is the common case
+//! on a live feed and the one the prefilter speeds up.
+//!
+//! The run fails if the cost at the largest size exceeds [`MAX_GROWTH`] times the cost at the
+//! smallest: the index must keep per-transaction cost flat (a linear scan would grow 10,000x).
+
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_stream_sdk::txn::{detect_program_hit, Prefilter, ProgramWatchConfig, WalletMatch};
+use std::{hint::black_box, time::Instant};
+
+const SIZES: [usize; 5] = [10, 100, 1_000, 10_000, 100_000];
+const TXS: usize = 2_000;
+const ROUNDS: usize = 20;
+/// Allowed ns/tx growth from the smallest to the largest size (cache misses, timing noise).
+const MAX_GROWTH: u128 = 4;
+
+fn tx(seed: usize) -> VersionedTransaction {
+    let payer = Pubkey::new_unique();
+    let accounts = (0..8)
+        .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+        .collect();
+    let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &seed.to_le_bytes(), accounts);
+    let message = v0::Message::try_compile(&payer, &[ix], &[], Hash::default())
+        .expect("compile bench message");
+    VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(message),
+    }
+}
+
+fn keys(n: usize) -> Vec<Pubkey> {
+    (0..n).map(|_| Pubkey::new_unique()).collect()
+}
+
+fn main() {
+    let txs: Vec<VersionedTransaction> = (0..TXS).map(tx).collect();
+    println!("{:>8} {:>12} {:>12}", "keys", "none ns/tx", "cuckoo ns/tx");
+    let mut rows = Vec::new();
+    for size in SIZES {
+        let mut row = Vec::new();
+        for prefilter in [Prefilter::None, Prefilter::Cuckoo] {
+            let cfg = ProgramWatchConfig::new(keys(size), keys(size))
+                .with_token_mints(keys(size))
+                .with_wallets(keys(size), WalletMatch::Signers)
+                .with_prefilter(prefilter);
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                for tx in &txs {
+                    black_box(detect_program_hit(black_box(tx), &cfg));
+                }
+            }
+            row.push((start.elapsed().as_nanos() / (TXS * ROUNDS) as u128).max(1));
+        }
+        println!("{:>8} {:>12} {:>12}", size, row[0], row[1]);
+        rows.push(row);
+    }
+
+    let (first, last) = (&rows[0], &rows[rows.len() - 1]);
+    for (column, name) in ["none", "cuckoo"].into_iter().enumerate() {
+        assert!(
+            last[column] <= first[column] * MAX_GROWTH,
+            "{name}: {} ns/tx at {} keys vs {} ns/tx at {} keys; per-transaction cost must stay flat",
+            last[column],
+            SIZES[SIZES.len() - 1],
+            first[column],
+            SIZES[0],
+        );
+    }
+}
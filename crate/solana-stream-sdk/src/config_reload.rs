@@ -0,0 +1,244 @@
+//! Live reload of the shreds-udp watch settings.
+//!
+//! [`ConfigWatcher`] polls the settings file for changes (and listens for `SIGHUP` on unix),
+//! re-parses it with [`ShredsUdpConfig::try_from_file`] and swaps the resulting
+//! [`WatchSettings`] into a [`LiveWatchSettings`] handle shared with [`ShredsUdpState`].
+//! The receiver and buffered FEC state are untouched; the next deshredded batch simply
+//! sees the new watch list and thresholds. Invalid files are rejected and the previous
+//! settings stay in effect.
+//!
+//! Only the watch-related keys are live (`watch_program_ids`, `watch_authorities`,
+//! `token_program_ids`, `jito_tip_accounts`, `watch_token_mints`, `skip_vote_sigs`,
+//! `log_watch_hits`, `pump_min_lamports`, `idls`, whose IDL files are re-read too); socket, FEC
+//! and sink settings still require a restart.
+//!
+//! [`ShredsUdpState`]: crate::shreds_udp::ShredsUdpState
+
+use crate::{shreds_udp::ShredsUdpConfig, txn::ProgramWatchConfig, Result};
+use arc_swap::ArcSwap;
+use log::{info, warn};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+pub const DEFAULT_RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The reloadable subset of [`ShredsUdpConfig`], consulted once per deshredded batch.
+#[derive(Clone)]
+pub struct WatchSettings {
+    pub watch_cfg: Arc<ProgramWatchConfig>,
+    pub pump_min_lamports: u64,
+    pub log_watch_hits: bool,
+}
+
+impl WatchSettings {
+    pub fn from_config(cfg: &ShredsUdpConfig) -> Self {
+        Self {
+            watch_cfg: Arc::new(cfg.watch_config()),
+            pump_min_lamports: cfg.pump_min_lamports,
+            log_watch_hits: cfg.log_watch_hits,
+        }
+    }
+}
+
+/// Cheaply cloneable handle to the current [`WatchSettings`].
+#[derive(Clone)]
+pub struct LiveWatchSettings {
+    inner: Arc<ArcSwap<WatchSettings>>,
+}
+
+impl LiveWatchSettings {
+    pub fn new(settings: WatchSettings) -> Self {
+        Self {
+            inner: Arc::new(ArcSwap::from_pointee(settings)),
+        }
+    }
+
+    /// Snapshot of the settings in effect right now.
+    pub fn load(&self) -> Arc<WatchSettings> {
+        self.inner.load_full()
+    }
+
+    /// Atomically replace the settings; readers holding an older snapshot keep it.
+    pub fn store(&self, settings: WatchSettings) {
+        self.inner.store(Arc::new(settings));
+    }
+}
+
+/// Watches a settings file and pushes validated changes into a [`LiveWatchSettings`].
+pub struct ConfigWatcher {
+    path: PathBuf,
+    live: LiveWatchSettings,
+    poll_interval: Duration,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: impl Into<PathBuf>, live: LiveWatchSettings) -> Self {
+        Self {
+            path: path.into(),
+            live,
+            poll_interval: DEFAULT_RELOAD_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Re-read and strictly validate the file now; on error the current settings stay.
+    pub fn reload(&self) -> Result<ShredsUdpConfig> {
+        let cfg = ShredsUdpConfig::try_from_file(&self.path)?;
+        self.live.store(WatchSettings::from_config(&cfg));
+        Ok(cfg)
+    }
+
+    /// Run the watcher on the current tokio runtime until the handle is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+
+    async fn run(self) {
+        let mut last_seen = file_fingerprint(&self.path);
+        let mut ticker = tokio::time::interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        #[cfg(unix)]
+        let mut hangup = {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::hangup()) {
+                Ok(sig) => Some(sig),
+                Err(e) => {
+                    warn!(
+                        "SIGHUP reload disabled (listener failed: {}); polling only",
+                        e
+                    );
+                    None
+                }
+            }
+        };
+
+        loop {
+            #[cfg(unix)]
+            let forced = tokio::select! {
+                _ = ticker.tick() => false,
+                Some(()) = recv_hangup(&mut hangup) => true,
+            };
+            #[cfg(not(unix))]
+            let forced = {
+                ticker.tick().await;
+                false
+            };
+
+            let fingerprint = file_fingerprint(&self.path);
+            if !forced && fingerprint == last_seen {
+                continue;
+            }
+            last_seen = fingerprint;
+
+            match self.reload() {
+                Ok(cfg) => info!(
+                    "Reloaded {} (watch_program_ids={} watch_authorities={} pump_min_lamports={} log_watch_hits={})",
+                    self.path.display(),
+                    cfg.watch_program_ids.len(),
+                    cfg.watch_authorities.len(),
+                    cfg.pump_min_lamports,
+                    cfg.log_watch_hits
+                ),
+                Err(e) => warn!("Config reload rejected, keeping previous settings: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn recv_hangup(hangup: &mut Option<tokio::signal::unix::Signal>) -> Option<()> {
+    match hangup {
+        Some(sig) => sig.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn file_fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    const PROGRAM_A: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+    const PROGRAM_B: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+
+    fn temp_settings(name: &str, body: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "shreds-udp-reload-{}-{}.jsonc",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, body).expect("write temp settings");
+        path
+    }
+
+    #[test]
+    fn reload_swaps_watch_list_and_threshold() {
+        let path = temp_settings(
+            "swap",
+            &format!(
+                r#"{{ "watch_program_ids": "{}", "pump_min_lamports": 1 }}"#,
+                PROGRAM_A
+            ),
+        );
+        let initial = ShredsUdpConfig::try_from_file(&path).expect("initial config");
+        let live = LiveWatchSettings::new(WatchSettings::from_config(&initial));
+        let watcher = ConfigWatcher::new(&path, live.clone());
+
+        fs::write(
+            &path,
+            format!(
+                r#"{{ "watch_program_ids": "{}", "pump_min_lamports": 5000000000 }}"#,
+                PROGRAM_B
+            ),
+        )
+        .expect("rewrite settings");
+        watcher.reload().expect("reload");
+
+        let current = live.load();
+        assert_eq!(
+            current.watch_cfg.program_ids(),
+            vec![Pubkey::from_str(PROGRAM_B).unwrap()]
+        );
+        assert_eq!(current.pump_min_lamports, 5_000_000_000);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn invalid_reload_keeps_previous_settings() {
+        let path = temp_settings(
+            "invalid",
+            &format!(r#"{{ "watch_program_ids": "{}" }}"#, PROGRAM_A),
+        );
+        let initial = ShredsUdpConfig::try_from_file(&path).expect("initial config");
+        let live = LiveWatchSettings::new(WatchSettings::from_config(&initial));
+        let watcher = ConfigWatcher::new(&path, live.clone());
+
+        fs::write(&path, r#"{ "watch_program_ids": "not-a-pubkey" }"#).expect("rewrite");
+        assert!(watcher.reload().is_err());
+        assert_eq!(
+            live.load().watch_cfg.program_ids(),
+            vec![Pubkey::from_str(PROGRAM_A).unwrap()]
+        );
+        let _ = fs::remove_file(&path);
+    }
+}
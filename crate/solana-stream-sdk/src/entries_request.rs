@@ -0,0 +1,611 @@
+//! Typed builder for [`SubscribeEntriesRequest`].
+//!
+//! [`EntriesRequestBuilder`] serializes to the same JSON the Node client's `subscribeEntries`
+//! takes (`SimpleEntriesRequest`): camelCase keys, filters keyed by name and `commitment` as
+//! `"Processed"`, `"Confirmed"` or `"Finalized"`. Account filters additionally accept typed
+//! `filters`:
+//!
+//! ```jsonc
+//! {
+//!   "accounts": {
+//!     "pump_curves": {
+//!       "owner": ["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"],
+//!       "filters": [{ "datasize": 150 }, { "memcmp": { "offset": 0, "base58": "..." } }]
+//!     }
+//!   },
+//!   "transactions": { "pump": { "accountInclude": ["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"] } },
+//!   "commitment": "Processed"
+//! }
+//! ```
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{bs58, pubkey::Pubkey};
+
+use crate::{
+    shredstream_proto::{
+        subscribe_request_filter_accounts_filter::Filter,
+        subscribe_request_filter_accounts_filter_lamports::Cmp,
+        subscribe_request_filter_accounts_filter_memcmp::Data, CommitmentLevel,
+        SubscribeEntriesRequest, SubscribeRequestFilterAccounts,
+        SubscribeRequestFilterAccountsFilter, SubscribeRequestFilterAccountsFilterLamports,
+        SubscribeRequestFilterAccountsFilterMemcmp, SubscribeRequestFilterSlots,
+        SubscribeRequestFilterTransactions,
+    },
+    Result, SolanaStreamError,
+};
+
+/// Longest memcmp pattern accepted, in decoded bytes.
+pub const MAX_MEMCMP_BYTES: usize = 128;
+
+/// Named filters and commitment of an entries subscription, validated by
+/// [`build`](Self::build).
+///
+/// # Example
+/// ```
+/// use solana_stream_sdk::{
+///     AccountsFilter, CommitmentLevel, EntriesFilter, EntriesRequestBuilder, TransactionsFilter,
+/// };
+///
+/// let request = EntriesRequestBuilder::new()
+///     .with_commitment(CommitmentLevel::Confirmed)
+///     .with_transactions(
+///         "pump",
+///         TransactionsFilter::new()
+///             .with_account_include(["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"]),
+///     )
+///     .with_accounts(
+///         "token_accounts",
+///         AccountsFilter::new()
+///             .with_owners(["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"])
+///             .with_filter(EntriesFilter::Datasize(165)),
+///     )
+///     .build()
+///     .unwrap();
+/// assert_eq!(request.commitment, Some(CommitmentLevel::Confirmed as i32));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntriesRequestBuilder {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub accounts: BTreeMap<String, AccountsFilter>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transactions: BTreeMap<String, TransactionsFilter>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub slots: BTreeMap<String, SlotsFilter>,
+    /// Left to the server when unset.
+    #[serde(
+        default,
+        with = "serde_commitment",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub commitment: Option<CommitmentLevel>,
+}
+
+/// Accounts filter: matches listed accounts, or accounts owned by listed programs, that pass
+/// every `filters` entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountsFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub account: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owner: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<EntriesFilter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonempty_txn_signature: Option<bool>,
+}
+
+/// Condition on account data or lamports, serialized as `{ "<kind>": <value> }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntriesFilter {
+    Memcmp(Memcmp),
+    /// Exact account data length.
+    Datasize(u64),
+    /// Only valid SPL token accounts.
+    TokenAccountState(bool),
+    Lamports(LamportsCmp),
+}
+
+/// Account data at `offset` starts with the pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memcmp {
+    pub offset: u64,
+    #[serde(flatten)]
+    pub data: MemcmpData,
+}
+
+/// Memcmp pattern, serialized as one of `bytes`, `base58` or `base64`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemcmpData {
+    Bytes(Vec<u8>),
+    Base58(String),
+    Base64(String),
+}
+
+/// Lamports comparison, serialized as `{ "gt": 1000 }` and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LamportsCmp {
+    Eq(u64),
+    Ne(u64),
+    Lt(u64),
+    Gt(u64),
+}
+
+/// Transactions filter: a transaction matches when it mentions any `account_include` key,
+/// none of `account_exclude` and all of `account_required`. Empty lists do not constrain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TransactionsFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub account_include: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub account_exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub account_required: Vec<String>,
+}
+
+/// Slots filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SlotsFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter_by_commitment: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interslot_updates: Option<bool>,
+}
+
+impl EntriesRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the JSON/JSONC request format shared with the Node client.
+    pub fn from_jsonc(raw: &str) -> Result<Self> {
+        Ok(serde_jsonc::from_str(raw)?)
+    }
+
+    /// Serialize to the JSON request format shared with the Node client.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| SolanaStreamError::Serialization(e.to_string()))
+    }
+
+    /// Add or replace the accounts filter called `name`.
+    pub fn with_accounts(mut self, name: impl Into<String>, filter: AccountsFilter) -> Self {
+        self.accounts.insert(name.into(), filter);
+        self
+    }
+
+    /// Add or replace the transactions filter called `name`.
+    pub fn with_transactions(
+        mut self,
+        name: impl Into<String>,
+        filter: TransactionsFilter,
+    ) -> Self {
+        self.transactions.insert(name.into(), filter);
+        self
+    }
+
+    /// Add or replace the slots filter called `name`.
+    pub fn with_slots(mut self, name: impl Into<String>, filter: SlotsFilter) -> Self {
+        self.slots.insert(name.into(), filter);
+        self
+    }
+
+    pub fn with_commitment(mut self, commitment: CommitmentLevel) -> Self {
+        self.commitment = Some(commitment);
+        self
+    }
+
+    /// Validation problems, each prefixed with the path of the offending field.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for (name, filter) in &self.accounts {
+            let path = format!("accounts.{}", name);
+            pubkey_issues(&mut issues, &path, "account", &filter.account);
+            pubkey_issues(&mut issues, &path, "owner", &filter.owner);
+            for (index, entry) in filter.filters.iter().enumerate() {
+                if let Some(issue) = entry.issue() {
+                    issues.push(format!("{}.filters[{}]: {}", path, index, issue));
+                }
+            }
+        }
+        for (name, filter) in &self.transactions {
+            let path = format!("transactions.{}", name);
+            pubkey_issues(
+                &mut issues,
+                &path,
+                "accountInclude",
+                &filter.account_include,
+            );
+            pubkey_issues(
+                &mut issues,
+                &path,
+                "accountExclude",
+                &filter.account_exclude,
+            );
+            pubkey_issues(
+                &mut issues,
+                &path,
+                "accountRequired",
+                &filter.account_required,
+            );
+        }
+        issues
+    }
+
+    /// Validate and convert to the gRPC request.
+    pub fn build(&self) -> Result<SubscribeEntriesRequest> {
+        let issues = self.issues();
+        if !issues.is_empty() {
+            return Err(SolanaStreamError::Configuration(format!(
+                "entries request: {} problem(s):\n  - {}",
+                issues.len(),
+                issues.join("\n  - ")
+            )));
+        }
+        Ok(SubscribeEntriesRequest {
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(name, filter)| {
+                    (
+                        name.clone(),
+                        SubscribeRequestFilterAccounts {
+                            account: filter.account.clone(),
+                            owner: filter.owner.clone(),
+                            filters: filter.filters.iter().map(EntriesFilter::to_proto).collect(),
+                            nonempty_txn_signature: filter.nonempty_txn_signature,
+                        },
+                    )
+                })
+                .collect(),
+            transactions: self
+                .transactions
+                .iter()
+                .map(|(name, filter)| {
+                    (
+                        name.clone(),
+                        SubscribeRequestFilterTransactions {
+                            account_include: filter.account_include.clone(),
+                            account_exclude: filter.account_exclude.clone(),
+                            account_required: filter.account_required.clone(),
+                        },
+                    )
+                })
+                .collect(),
+            slots: self
+                .slots
+                .iter()
+                .map(|(name, filter)| {
+                    (
+                        name.clone(),
+                        SubscribeRequestFilterSlots {
+                            filter_by_commitment: filter.filter_by_commitment,
+                            interslot_updates: filter.interslot_updates,
+                        },
+                    )
+                })
+                .collect(),
+            commitment: self.commitment.map(|commitment| commitment as i32),
+        })
+    }
+}
+
+impl AccountsFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_accounts<I, S>(mut self, accounts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.account.extend(accounts.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_owners<I, S>(mut self, owners: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.owner.extend(owners.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_filter(mut self, filter: EntriesFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn with_nonempty_txn_signature(mut self, nonempty: bool) -> Self {
+        self.nonempty_txn_signature = Some(nonempty);
+        self
+    }
+}
+
+impl EntriesFilter {
+    /// Memcmp filter matching `bytes` at `offset`.
+    pub fn memcmp(offset: u64, bytes: impl Into<Vec<u8>>) -> Self {
+        Self::Memcmp(Memcmp {
+            offset,
+            data: MemcmpData::Bytes(bytes.into()),
+        })
+    }
+
+    fn issue(&self) -> Option<String> {
+        let Self::Memcmp(memcmp) = self else {
+            return None;
+        };
+        let len = match &memcmp.data {
+            MemcmpData::Bytes(bytes) => bytes.len(),
+            MemcmpData::Base58(encoded) => match bs58::decode(encoded).into_vec() {
+                Ok(bytes) => bytes.len(),
+                Err(e) => return Some(format!("memcmp.base58: {}", e)),
+            },
+            // Upper bound of the decoded length; the server decodes it.
+            MemcmpData::Base64(encoded) => encoded.trim_end_matches('=').len() * 3 / 4,
+        };
+        if len == 0 {
+            Some("memcmp: empty pattern".to_string())
+        } else if len > MAX_MEMCMP_BYTES {
+            Some(format!(
+                "memcmp: pattern of {} bytes exceeds {}",
+                len, MAX_MEMCMP_BYTES
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn to_proto(&self) -> SubscribeRequestFilterAccountsFilter {
+        let filter = match self {
+            Self::Memcmp(memcmp) => Filter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                offset: memcmp.offset,
+                data: Some(match &memcmp.data {
+                    MemcmpData::Bytes(bytes) => Data::Bytes(bytes.clone()),
+                    MemcmpData::Base58(encoded) => Data::Base58(encoded.clone()),
+                    MemcmpData::Base64(encoded) => Data::Base64(encoded.clone()),
+                }),
+            }),
+            Self::Datasize(size) => Filter::Datasize(*size),
+            Self::TokenAccountState(state) => Filter::TokenAccountState(*state),
+            Self::Lamports(cmp) => Filter::Lamports(SubscribeRequestFilterAccountsFilterLamports {
+                cmp: Some(match *cmp {
+                    LamportsCmp::Eq(lamports) => Cmp::Eq(lamports),
+                    LamportsCmp::Ne(lamports) => Cmp::Ne(lamports),
+                    LamportsCmp::Lt(lamports) => Cmp::Lt(lamports),
+                    LamportsCmp::Gt(lamports) => Cmp::Gt(lamports),
+                }),
+            }),
+        };
+        SubscribeRequestFilterAccountsFilter {
+            filter: Some(filter),
+        }
+    }
+}
+
+impl TransactionsFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_account_include<I, S>(mut self, accounts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.account_include
+            .extend(accounts.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_account_exclude<I, S>(mut self, accounts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.account_exclude
+            .extend(accounts.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_account_required<I, S>(mut self, accounts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.account_required
+            .extend(accounts.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl SlotsFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_filter_by_commitment(mut self, enabled: bool) -> Self {
+        self.filter_by_commitment = Some(enabled);
+        self
+    }
+
+    pub fn with_interslot_updates(mut self, enabled: bool) -> Self {
+        self.interslot_updates = Some(enabled);
+        self
+    }
+}
+
+fn pubkey_issues(issues: &mut Vec<String>, path: &str, field: &str, keys: &[String]) {
+    for (index, key) in keys.iter().enumerate() {
+        if Pubkey::from_str(key).is_err() {
+            issues.push(format!(
+                "{}.{}[{}]: invalid pubkey {:?}",
+                path, field, index, key
+            ));
+        }
+    }
+}
+
+/// `CommitmentLevel` as its variant name, like the Node client's enum.
+mod serde_commitment {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::shredstream_proto::CommitmentLevel;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<CommitmentLevel>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(CommitmentLevel::Processed) => serializer.serialize_str("Processed"),
+            Some(CommitmentLevel::Confirmed) => serializer.serialize_str("Confirmed"),
+            Some(CommitmentLevel::Finalized) => serializer.serialize_str("Finalized"),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<CommitmentLevel>, D::Error> {
+        match Option::<String>::deserialize(deserializer)?.as_deref() {
+            None => Ok(None),
+            Some("Processed") => Ok(Some(CommitmentLevel::Processed)),
+            Some("Confirmed") => Ok(Some(CommitmentLevel::Confirmed)),
+            Some("Finalized") => Ok(Some(CommitmentLevel::Finalized)),
+            Some(other) => Err(D::Error::custom(format!(
+                "unknown commitment {:?} (expected Processed, Confirmed or Finalized)",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUMP: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+    #[test]
+    fn node_request_round_trips_and_builds() {
+        // As sent by the Node client, plus typed account filters.
+        let raw = r#"{
+            // JSONC comments are fine
+            "accounts": {
+                "curves": {
+                    "owner": ["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"],
+                    "filters": [
+                        { "datasize": 150 },
+                        { "memcmp": { "offset": 8, "base58": "3Mc6vR" } },
+                        { "lamports": { "gt": 1000 } },
+                        { "tokenAccountState": true }
+                    ],
+                    "nonemptyTxnSignature": true
+                }
+            },
+            "transactions": { "pump": { "accountInclude": ["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"] } },
+            "slots": { "slots": { "filterByCommitment": true } },
+            "commitment": "Confirmed"
+        }"#;
+        let builder = EntriesRequestBuilder::from_jsonc(raw).unwrap();
+        let expected = EntriesRequestBuilder::new()
+            .with_accounts(
+                "curves",
+                AccountsFilter::new()
+                    .with_owners([PUMP])
+                    .with_filter(EntriesFilter::Datasize(150))
+                    .with_filter(EntriesFilter::Memcmp(Memcmp {
+                        offset: 8,
+                        data: MemcmpData::Base58("3Mc6vR".to_string()),
+                    }))
+                    .with_filter(EntriesFilter::Lamports(LamportsCmp::Gt(1000)))
+                    .with_filter(EntriesFilter::TokenAccountState(true))
+                    .with_nonempty_txn_signature(true),
+            )
+            .with_transactions(
+                "pump",
+                TransactionsFilter::new().with_account_include([PUMP]),
+            )
+            .with_slots("slots", SlotsFilter::new().with_filter_by_commitment(true))
+            .with_commitment(CommitmentLevel::Confirmed);
+        assert_eq!(builder, expected);
+        assert_eq!(
+            EntriesRequestBuilder::from_jsonc(&builder.to_json().unwrap()).unwrap(),
+            builder
+        );
+
+        let request = builder.build().unwrap();
+        assert_eq!(request.commitment, Some(CommitmentLevel::Confirmed as i32));
+        assert_eq!(request.transactions["pump"].account_include, [PUMP]);
+        assert!(request.transactions["pump"].account_exclude.is_empty());
+        let curves = &request.accounts["curves"];
+        assert_eq!(curves.filters.len(), 4);
+        assert_eq!(
+            curves.filters[2].filter,
+            Some(Filter::Lamports(
+                SubscribeRequestFilterAccountsFilterLamports {
+                    cmp: Some(Cmp::Gt(1000))
+                }
+            ))
+        );
+        assert_eq!(request.slots["slots"].filter_by_commitment, Some(true));
+
+        // The Node client's minimal request.
+        let empty = EntriesRequestBuilder::from_jsonc(
+            r#"{ "accounts": {}, "transactions": {}, "slots": {}, "commitment": "Processed" }"#,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        assert!(empty.accounts.is_empty() && empty.transactions.is_empty());
+        assert_eq!(empty.commitment, Some(CommitmentLevel::Processed as i32));
+    }
+
+    #[test]
+    fn build_reports_each_invalid_field() {
+        let builder = EntriesRequestBuilder::new()
+            .with_accounts(
+                "a",
+                AccountsFilter::new()
+                    .with_accounts(["not-a-key"])
+                    .with_filter(EntriesFilter::memcmp(0, Vec::new()))
+                    .with_filter(EntriesFilter::memcmp(0, vec![1u8; 129])),
+            )
+            .with_transactions("t", TransactionsFilter::new().with_account_required([""]));
+        assert_eq!(
+            builder.issues(),
+            [
+                "accounts.a.account[0]: invalid pubkey \"not-a-key\"",
+                "accounts.a.filters[0]: memcmp: empty pattern",
+                "accounts.a.filters[1]: memcmp: pattern of 129 bytes exceeds 128",
+                "transactions.t.accountRequired[0]: invalid pubkey \"\"",
+            ]
+        );
+        match builder.build() {
+            Err(SolanaStreamError::Configuration(message)) => {
+                assert!(
+                    message.starts_with("entries request: 4 problem(s):"),
+                    "{message}"
+                )
+            }
+            other => panic!("expected Configuration, got {:?}", other),
+        }
+
+        let Err(SolanaStreamError::SerdeJsonc(e)) =
+            EntriesRequestBuilder::from_jsonc(r#"{ "commitment": "Rooted" }"#)
+        else {
+            panic!("expected a parse error");
+        };
+        assert!(e.to_string().contains("unknown commitment \"Rooted\""));
+    }
+}
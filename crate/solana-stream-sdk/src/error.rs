@@ -11,6 +11,9 @@ pub enum SolanaStreamError {
     #[error("Serialization error: {0}")]
     Serialization(String),
 
+    #[error("Entry decode error at slot {slot}: {message}")]
+    EntryDecode { slot: u64, message: String },
+
     #[error("Connection error: {0}")]
     Connection(String),
 
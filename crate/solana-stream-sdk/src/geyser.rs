@@ -0,0 +1,501 @@
+//! Watch detection on Geyser (Yellowstone gRPC) transaction updates.
+//!
+//! [`versioned_transaction_from_geyser`] rebuilds the [`VersionedTransaction`] that the UDP
+//! path deshreds and [`account_keys_from_geyser`] resolves its lookup-table keys, so
+//! [`collect_watch_events_from_geyser`] runs the same detectors and yields the same
+//! [`WatchEvent`]s whichever feed a transaction arrived on. The update's status meta
+//! is converted with [`tx_meta_from_geyser`] and adds what shreds cannot show: CPI-invoked
+//! instructions, executed amounts and failed transactions.
+
+use solana_sdk::{
+    hash::Hash,
+    message::{
+        compiled_instruction::CompiledInstruction,
+        legacy,
+        v0::{self, MessageAddressTableLookup},
+        MessageHeader, VersionedMessage,
+    },
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{TransactionError, VersionedTransaction},
+};
+use yellowstone_grpc_proto::prelude::{
+    Message, SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo,
+    TokenBalance as GeyserTokenBalance, TransactionStatusMeta,
+};
+
+use crate::{
+    shreds_udp::{collect_watch_event, WatchEvent},
+    txn::{InnerInstructions, ProgramWatchConfig, TokenBalance, TxMeta},
+    Result, SolanaStreamError,
+};
+
+fn invalid(message: String) -> SolanaStreamError {
+    SolanaStreamError::Serialization(format!("geyser transaction: {}", message))
+}
+
+fn pubkey(bytes: &[u8], what: &str) -> Result<Pubkey> {
+    Pubkey::try_from(bytes)
+        .map_err(|_| invalid(format!("{} is {} bytes, expected 32", what, bytes.len())))
+}
+
+fn index(value: u32, what: &str) -> Result<u8> {
+    u8::try_from(value).map_err(|_| invalid(format!("{} {} out of range", what, value)))
+}
+
+/// Convert a Geyser transaction into the [`VersionedTransaction`] that was signed.
+///
+/// Address lookups are kept as-is, so the result round-trips and sanitizes like the original;
+/// [`account_keys_from_geyser`] resolves the keys they load.
+pub fn versioned_transaction_from_geyser(
+    info: &SubscribeUpdateTransactionInfo,
+) -> Result<VersionedTransaction> {
+    let tx = info
+        .transaction
+        .as_ref()
+        .ok_or_else(|| invalid("missing transaction".to_string()))?;
+    let message = tx
+        .message
+        .as_ref()
+        .ok_or_else(|| invalid("missing message".to_string()))?;
+    let signatures = tx
+        .signatures
+        .iter()
+        .map(|bytes| {
+            Signature::try_from(bytes.as_slice())
+                .map_err(|_| invalid(format!("signature is {} bytes, expected 64", bytes.len())))
+        })
+        .collect::<Result<_>>()?;
+    Ok(VersionedTransaction {
+        signatures,
+        message: versioned_message(message)?,
+    })
+}
+
+/// Account keys of a Geyser transaction in runtime order: static keys, then the addresses
+/// loaded from lookup tables (writable, then readonly) as listed in the meta. Instruction
+/// account indexes and meta indexes such as token balances address this list. Without meta
+/// only the static keys are known, which is all the UDP path can see.
+pub fn account_keys_from_geyser(info: &SubscribeUpdateTransactionInfo) -> Result<Vec<Pubkey>> {
+    let message = info
+        .transaction
+        .as_ref()
+        .and_then(|tx| tx.message.as_ref())
+        .ok_or_else(|| invalid("missing message".to_string()))?;
+    let mut keys = message
+        .account_keys
+        .iter()
+        .map(|key| pubkey(key, "account key"))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(meta) = &info.meta {
+        for key in meta
+            .loaded_writable_addresses
+            .iter()
+            .chain(&meta.loaded_readonly_addresses)
+        {
+            keys.push(pubkey(key, "loaded address")?);
+        }
+    }
+    Ok(keys)
+}
+
+fn versioned_message(message: &Message) -> Result<VersionedMessage> {
+    let header = message
+        .header
+        .as_ref()
+        .ok_or_else(|| invalid("missing message header".to_string()))?;
+    let header = MessageHeader {
+        num_required_signatures: index(header.num_required_signatures, "num_required_signatures")?,
+        num_readonly_signed_accounts: index(
+            header.num_readonly_signed_accounts,
+            "num_readonly_signed_accounts",
+        )?,
+        num_readonly_unsigned_accounts: index(
+            header.num_readonly_unsigned_accounts,
+            "num_readonly_unsigned_accounts",
+        )?,
+    };
+    let account_keys = message
+        .account_keys
+        .iter()
+        .map(|key| pubkey(key, "account key"))
+        .collect::<Result<Vec<_>>>()?;
+    let recent_blockhash = <[u8; 32]>::try_from(message.recent_blockhash.as_slice())
+        .map(Hash::new_from_array)
+        .map_err(|_| {
+            invalid(format!(
+                "recent blockhash is {} bytes, expected 32",
+                message.recent_blockhash.len()
+            ))
+        })?;
+    let instructions = message
+        .instructions
+        .iter()
+        .map(|ix| {
+            Ok(CompiledInstruction {
+                program_id_index: index(ix.program_id_index, "program_id_index")?,
+                accounts: ix.accounts.clone(),
+                data: ix.data.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if !message.versioned {
+        return Ok(VersionedMessage::Legacy(legacy::Message {
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+        }));
+    }
+    let address_table_lookups = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| {
+            Ok(MessageAddressTableLookup {
+                account_key: pubkey(&lookup.account_key, "lookup table")?,
+                writable_indexes: lookup.writable_indexes.clone(),
+                readonly_indexes: lookup.readonly_indexes.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(VersionedMessage::V0(v0::Message {
+        header,
+        account_keys,
+        recent_blockhash,
+        instructions,
+        address_table_lookups,
+    }))
+}
+
+/// Convert Geyser status meta into a [`TxMeta`].
+///
+/// The execution error is decoded into its runtime form (e.g. `Error processing Instruction 2:
+/// custom program error: 0x1771`); token balances without an owner keep `owner: None`.
+pub fn tx_meta_from_geyser(meta: &TransactionStatusMeta) -> Result<TxMeta> {
+    let err = meta.err.as_ref().map(|err| {
+        bincode::deserialize::<TransactionError>(&err.err)
+            .map(|err| err.to_string())
+            .unwrap_or_else(|_| format!("undecodable error ({} bytes)", err.err.len()))
+    });
+    let inner_instructions = meta
+        .inner_instructions
+        .iter()
+        .map(|set| {
+            Ok(InnerInstructions {
+                index: index(set.index, "inner instruction index")?,
+                instructions: set
+                    .instructions
+                    .iter()
+                    .map(|ix| {
+                        Ok(CompiledInstruction {
+                            program_id_index: index(ix.program_id_index, "program_id_index")?,
+                            accounts: ix.accounts.clone(),
+                            data: ix.data.clone(),
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(TxMeta {
+        err,
+        fee: meta.fee,
+        pre_balances: meta.pre_balances.clone(),
+        post_balances: meta.post_balances.clone(),
+        inner_instructions,
+        pre_token_balances: token_balances(&meta.pre_token_balances)?,
+        post_token_balances: token_balances(&meta.post_token_balances)?,
+        log_messages: meta.log_messages.clone(),
+    })
+}
+
+fn token_balances(balances: &[GeyserTokenBalance]) -> Result<Vec<TokenBalance>> {
+    balances
+        .iter()
+        .map(|balance| {
+            let amount = balance.ui_token_amount.as_ref();
+            Ok(TokenBalance {
+                account_index: index(balance.account_index, "token balance account_index")?,
+                mint: balance
+                    .mint
+                    .parse()
+                    .map_err(|_| invalid(format!("token balance mint {:?}", balance.mint)))?,
+                owner: balance.owner.parse().ok(),
+                amount: amount
+                    .map(|a| a.amount.parse())
+                    .transpose()
+                    .map_err(|_| invalid("token balance amount is not a u64".to_string()))?
+                    .unwrap_or_default(),
+                decimals: amount
+                    .map(|a| index(a.decimals, "token decimals"))
+                    .transpose()?
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Run the watch detectors on a Geyser transaction update; the event slot is the update's.
+/// Uses the update's meta when present (see [`collect_watch_event`]).
+pub fn collect_watch_events_from_geyser(
+    update: &SubscribeUpdateTransaction,
+    watch_cfg: &ProgramWatchConfig,
+) -> Result<Vec<WatchEvent>> {
+    let Some(info) = &update.transaction else {
+        return Ok(Vec::new());
+    };
+    let tx = versioned_transaction_from_geyser(info)?;
+    let account_keys = account_keys_from_geyser(info)?;
+    let meta = info.meta.as_ref().map(tx_meta_from_geyser).transpose()?;
+    Ok(
+        collect_watch_event(update.slot, &tx, &account_keys, meta.as_ref(), watch_cfg)
+            .into_iter()
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{test_support::tx_with, Amount, AmountBound},
+    };
+    use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+    use yellowstone_grpc_proto::prelude::{
+        CompiledInstruction as GeyserInstruction, InnerInstruction as GeyserInnerInstruction,
+        InnerInstructions as GeyserInnerInstructions, Message as GeyserMessage,
+        MessageAddressTableLookup as GeyserLookup, MessageHeader as GeyserHeader,
+        Transaction as GeyserTransaction, TransactionError as GeyserTransactionError,
+    };
+
+    const PAYER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const PROGRAM: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const ACCOUNT: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const TABLE: Pubkey = Pubkey::new_from_array([4u8; 32]);
+    const LOADED: Pubkey = Pubkey::new_from_array([5u8; 32]);
+    const GLOBAL: Pubkey = Pubkey::new_from_array([7u8; 32]);
+
+    /// Encode `tx` the way Geyser does; `loaded` becomes one readonly lookup plus its meta.
+    fn to_geyser(tx: &VersionedTransaction, loaded: Option<Pubkey>) -> SubscribeUpdateTransaction {
+        let message = &tx.message;
+        let header = message.header();
+        let mut instructions: Vec<GeyserInstruction> = message
+            .instructions()
+            .iter()
+            .map(|ix| GeyserInstruction {
+                program_id_index: u32::from(ix.program_id_index),
+                accounts: ix.accounts.clone(),
+                data: ix.data.clone(),
+            })
+            .collect();
+        let static_keys = message.static_account_keys().len() as u8;
+        if loaded.is_some() {
+            instructions[0].accounts.push(static_keys);
+        }
+        SubscribeUpdateTransaction {
+            slot: 77,
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: tx.signatures[0].as_ref().to_vec(),
+                is_vote: false,
+                transaction: Some(GeyserTransaction {
+                    signatures: tx.signatures.iter().map(|s| s.as_ref().to_vec()).collect(),
+                    message: Some(GeyserMessage {
+                        header: Some(GeyserHeader {
+                            num_required_signatures: u32::from(header.num_required_signatures),
+                            num_readonly_signed_accounts: u32::from(
+                                header.num_readonly_signed_accounts,
+                            ),
+                            num_readonly_unsigned_accounts: u32::from(
+                                header.num_readonly_unsigned_accounts,
+                            ),
+                        }),
+                        account_keys: message
+                            .static_account_keys()
+                            .iter()
+                            .map(|key| key.to_bytes().to_vec())
+                            .collect(),
+                        recent_blockhash: message.recent_blockhash().to_bytes().to_vec(),
+                        instructions,
+                        versioned: true,
+                        address_table_lookups: loaded
+                            .map(|_| GeyserLookup {
+                                account_key: TABLE.to_bytes().to_vec(),
+                                writable_indexes: Vec::new(),
+                                readonly_indexes: vec![0],
+                            })
+                            .into_iter()
+                            .collect(),
+                    }),
+                }),
+                meta: Some(TransactionStatusMeta {
+                    loaded_readonly_addresses: loaded
+                        .map(|key| key.to_bytes().to_vec())
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                }),
+                index: 0,
+            }),
+        }
+    }
+
+    /// pump.fun `buy(amount: 1_000, max_sol_cost: 5_000_000)` of mint `ACCOUNT`.
+    fn buy_data() -> Vec<u8> {
+        [
+            crate::txn::PUMPFUN_BUY_DISC.as_slice(),
+            &1_000u64.to_le_bytes(),
+            &5_000_000u64.to_le_bytes(),
+        ]
+        .concat()
+    }
+
+    fn tx() -> VersionedTransaction {
+        tx_with(
+            &PAYER,
+            &[Instruction::new_with_bytes(
+                PROGRAM,
+                &buy_data(),
+                vec![
+                    AccountMeta::new_readonly(GLOBAL, false),
+                    AccountMeta::new_readonly(GLOBAL, false),
+                    AccountMeta::new(ACCOUNT, false),
+                ],
+            )],
+        )
+    }
+
+    #[test]
+    fn geyser_transaction_round_trips_and_detects_like_udp() {
+        let tx = tx();
+        let update = to_geyser(&tx, None);
+        let info = update.transaction.as_ref().unwrap();
+        assert_eq!(versioned_transaction_from_geyser(info).unwrap(), tx);
+
+        let cfg = ProgramWatchConfig::new(vec![PROGRAM], Vec::new());
+        let mut from_geyser = collect_watch_events_from_geyser(&update, &cfg).unwrap();
+        let outcome = from_geyser[0].outcome.take().expect("meta outcome");
+        assert!(!outcome.failed);
+        let from_udp = collect_watch_events(77, &[&tx], &cfg, 0);
+        assert_eq!(
+            serde_json::to_value(&from_geyser).unwrap(),
+            serde_json::to_value(&from_udp).unwrap()
+        );
+
+        let mut broken = update.clone();
+        let message = broken
+            .transaction
+            .as_mut()
+            .unwrap()
+            .transaction
+            .as_mut()
+            .unwrap();
+        message.message.as_mut().unwrap().account_keys[0].pop();
+        assert_eq!(
+            collect_watch_events_from_geyser(&broken, &cfg)
+                .unwrap_err()
+                .to_string(),
+            "Serialization error: geyser transaction: account key is 31 bytes, expected 32"
+        );
+    }
+
+    #[test]
+    fn loaded_addresses_are_visible_to_detectors() {
+        let update = to_geyser(&tx(), Some(LOADED));
+        let info = update.transaction.as_ref().unwrap();
+        let converted = versioned_transaction_from_geyser(info).unwrap();
+        assert!(converted.sanitize().is_ok());
+        let lookups = converted.message.address_table_lookups().unwrap();
+        assert_eq!(lookups[0].account_key, TABLE);
+        assert!(!converted.message.static_account_keys().contains(&LOADED));
+
+        let keys = account_keys_from_geyser(info).unwrap();
+        assert_eq!(
+            keys.len(),
+            converted.message.static_account_keys().len() + 1
+        );
+        assert_eq!(keys.last(), Some(&LOADED));
+
+        let cfg = ProgramWatchConfig::new(Vec::new(), vec![LOADED]);
+        assert!(crate::txn::detect_program_hit(&converted, &cfg).is_none());
+        let resolved = crate::txn::with_resolved_keys(&converted, &keys).unwrap();
+        assert!(
+            crate::txn::detect_program_hit(&resolved, &cfg)
+                .expect("loaded key hit")
+                .authority_hit
+        );
+    }
+
+    #[test]
+    fn meta_adds_cpi_hits_executed_amounts_and_failures() {
+        const ROUTER: Pubkey = Pubkey::new_from_array([6u8; 32]);
+        let tx = tx_with(
+            &PAYER,
+            &[Instruction::new_with_bytes(
+                ROUTER,
+                &[1],
+                vec![
+                    AccountMeta::new_readonly(PROGRAM, false),
+                    AccountMeta::new_readonly(GLOBAL, false),
+                    AccountMeta::new(ACCOUNT, false),
+                ],
+            )],
+        );
+        let keys = tx.message.static_account_keys();
+        let position = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap() as u8;
+        let mut update = to_geyser(&tx, None);
+        let meta = update.transaction.as_mut().unwrap().meta.as_mut().unwrap();
+        meta.inner_instructions = vec![GeyserInnerInstructions {
+            index: 0,
+            instructions: vec![GeyserInnerInstruction {
+                program_id_index: u32::from(position(&PROGRAM)),
+                accounts: vec![position(&GLOBAL), position(&GLOBAL), position(&ACCOUNT)],
+                data: buy_data(),
+                stack_height: Some(2),
+            }],
+        }];
+        meta.fee = 5_000;
+        meta.pre_balances = vec![0; keys.len()];
+        meta.post_balances = vec![0; keys.len()];
+        meta.pre_balances[0] = 10_000_000;
+        meta.post_balances[0] = 6_995_000;
+        meta.post_token_balances = vec![GeyserTokenBalance {
+            account_index: 1,
+            mint: ACCOUNT.to_string(),
+            ui_token_amount: Some(yellowstone_grpc_proto::prelude::UiTokenAmount {
+                decimals: 6,
+                amount: "1200".to_string(),
+                ..Default::default()
+            }),
+            owner: PAYER.to_string(),
+            program_id: String::new(),
+        }];
+
+        let cfg = ProgramWatchConfig::new(vec![PROGRAM], Vec::new());
+        assert!(collect_watch_events(77, &[&tx], &cfg, 0).is_empty());
+        let events = collect_watch_events_from_geyser(&update, &cfg).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].hit.program_hit);
+        let buy = &events[0].details[0];
+        assert_eq!(buy.sol_amount, Some(Amount::lamports(3_000_000)));
+        assert_eq!(buy.token_amount, Some(Amount::token(1_200)));
+
+        let error = TransactionError::InstructionError(0, InstructionError::Custom(6002));
+        let info = update.transaction.as_mut().unwrap();
+        info.meta.as_mut().unwrap().err = Some(GeyserTransactionError {
+            err: bincode::serialize(&error).unwrap(),
+        });
+        let events = collect_watch_events_from_geyser(&update, &cfg).unwrap();
+        let outcome = events[0].outcome.as_ref().unwrap();
+        assert!(outcome.failed);
+        assert_eq!(
+            outcome.error.as_deref(),
+            Some("Error processing Instruction 0: custom program error: 0x1772")
+        );
+        assert_eq!(
+            events[0].details[0].sol_amount,
+            Some(Amount::lamports(5_000_000).with_bound(AmountBound::Max))
+        );
+    }
+}
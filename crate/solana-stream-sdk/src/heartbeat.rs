@@ -0,0 +1,312 @@
+//! Heartbeats to the Shredstream service, which keep shreds flowing to a registered UDP socket.
+//!
+//! The service sends shreds to `socket` for `ttl_ms` after each heartbeat, so
+//! [`ShredstreamHeartbeatClient::spawn`] re-sends well within the returned TTL and retries
+//! failures with a [`ReconnectPolicy`] backoff. Run it next to a
+//! [`UdpShredReceiver`](crate::UdpShredReceiver) bound to the same port, or set `heartbeat`
+//! in the shreds-udp settings (see [`HeartbeatConfig`]).
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use log::{info, warn};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+use tonic::transport::Channel;
+
+use crate::{
+    shared::Socket,
+    shredstream::{ReconnectPolicy, ShredstreamClientBuilder},
+    shredstream_proto::{
+        shredstream_client::ShredstreamClient as ShredstreamServiceClient, Heartbeat,
+    },
+    Result, SolanaStreamError,
+};
+
+/// Shortest pause between heartbeats, whatever TTL the service returns.
+const MIN_HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `heartbeat` section of the shreds-udp settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeartbeatConfig {
+    /// Shredstream service URL.
+    pub endpoint: String,
+    /// Public IP that shreds are sent to; must be the address the service sees our packets from.
+    pub public_ip: String,
+    /// Port shreds are sent to; defaults to the port of `bind_addr`.
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Regions to receive shreds from.
+    pub regions: Vec<String>,
+}
+
+impl HeartbeatConfig {
+    /// Validation problems, each prefixed with its field name.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        match url::Url::parse(&self.endpoint) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(url) => issues.push(format!(
+                "endpoint: unsupported scheme {:?} (expected http or https)",
+                url.scheme()
+            )),
+            Err(e) => issues.push(format!("endpoint: {}", e)),
+        }
+        match self.public_ip.parse::<IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => {
+                issues.push(format!("public_ip: {} is not routable", ip))
+            }
+            Ok(_) => {}
+            Err(_) => issues.push(format!(
+                "public_ip: expected an IP address, got {:?}",
+                self.public_ip
+            )),
+        }
+        if self.port == Some(0) {
+            issues.push("port: must be at least 1".to_string());
+        }
+        if self.regions.is_empty() {
+            issues.push("regions: list at least one region".to_string());
+        }
+        issues
+    }
+
+    /// Client registering `public_ip` and `port`, or `bind_port` when no port is set.
+    pub fn client(&self, bind_port: u16) -> Result<ShredstreamHeartbeatClient> {
+        let ip = self.public_ip.parse::<IpAddr>().map_err(|_| {
+            SolanaStreamError::Configuration(format!(
+                "heartbeat public_ip: expected an IP address, got {:?}",
+                self.public_ip
+            ))
+        })?;
+        ShredstreamHeartbeatClient::new(
+            &self.endpoint,
+            SocketAddr::new(ip, self.port.unwrap_or(bind_port)),
+            self.regions.clone(),
+        )
+    }
+}
+
+/// Client for the Shredstream service's `SendHeartbeat`.
+pub struct ShredstreamHeartbeatClient {
+    client: ShredstreamServiceClient<Channel>,
+    socket: SocketAddr,
+    regions: Vec<String>,
+    retry: ReconnectPolicy,
+}
+
+impl ShredstreamHeartbeatClient {
+    /// Client registering `socket` for `regions`.
+    ///
+    /// The connection is made on the first heartbeat and re-established after failures, so
+    /// only a malformed `endpoint` fails here.
+    pub fn new(endpoint: &str, socket: SocketAddr, regions: Vec<String>) -> Result<Self> {
+        let channel = ShredstreamClientBuilder::new(endpoint)
+            .transport_endpoint()
+            .map_err(|e| {
+                SolanaStreamError::Configuration(format!("heartbeat endpoint {}: {}", endpoint, e))
+            })?
+            .connect_lazy();
+        Ok(Self {
+            client: ShredstreamServiceClient::new(channel),
+            socket,
+            regions,
+            retry: ReconnectPolicy::default(),
+        })
+    }
+
+    /// Backoff between failed heartbeats; attempts are never exhausted.
+    pub fn with_retry_policy(mut self, retry: ReconnectPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn socket(&self) -> SocketAddr {
+        self.socket
+    }
+
+    /// Send one heartbeat and return the TTL granted by the service.
+    pub async fn send_heartbeat(&mut self) -> Result<Duration> {
+        let response = self
+            .client
+            .send_heartbeat(Heartbeat {
+                socket: Some(Socket {
+                    ip: self.socket.ip().to_string(),
+                    port: i64::from(self.socket.port()),
+                }),
+                regions: self.regions.clone(),
+            })
+            .await?;
+        Ok(Duration::from_millis(u64::from(
+            response.into_inner().ttl_ms,
+        )))
+    }
+
+    /// Send heartbeats in the background until the returned handle is stopped or dropped.
+    ///
+    /// Each heartbeat is renewed after half the granted TTL. Failures are logged and retried
+    /// with the retry policy's backoff, capped at half the last TTL so a short outage does not
+    /// let the registration lapse.
+    pub fn spawn(mut self) -> HeartbeatHandle {
+        let ttl_ms = Arc::new(AtomicU64::new(0));
+        let task = {
+            let ttl_ms = ttl_ms.clone();
+            tokio::spawn(async move {
+                let mut failures = 0u32;
+                loop {
+                    let delay = match self.send_heartbeat().await {
+                        Ok(ttl) => {
+                            if failures > 0 {
+                                info!(
+                                    "heartbeat for {} recovered after {} failure(s)",
+                                    self.socket, failures
+                                );
+                            }
+                            failures = 0;
+                            ttl_ms.store(ttl.as_millis() as u64, Ordering::Relaxed);
+                            if ttl.is_zero() {
+                                self.retry.initial
+                            } else {
+                                ttl / 2
+                            }
+                        }
+                        Err(e) => {
+                            failures = failures.saturating_add(1);
+                            warn!(
+                                "heartbeat for {} failed ({} in a row): {}",
+                                self.socket, failures, e
+                            );
+                            let backoff = self.retry.delay(failures);
+                            match ttl_ms.load(Ordering::Relaxed) {
+                                0 => backoff,
+                                ttl => backoff.min(Duration::from_millis(ttl / 2)),
+                            }
+                        }
+                    };
+                    tokio::time::sleep(delay.max(MIN_HEARTBEAT_INTERVAL)).await;
+                }
+            })
+        };
+        HeartbeatHandle { task, ttl_ms }
+    }
+}
+
+/// Background heartbeat task started by [`ShredstreamHeartbeatClient::spawn`]; dropping it
+/// stops the heartbeats.
+pub struct HeartbeatHandle {
+    task: JoinHandle<()>,
+    ttl_ms: Arc<AtomicU64>,
+}
+
+impl HeartbeatHandle {
+    /// TTL granted by the last successful heartbeat.
+    pub fn last_ttl(&self) -> Option<Duration> {
+        match self.ttl_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for HeartbeatHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shredstream_proto::{
+        shredstream_server::{Shredstream, ShredstreamServer},
+        HeartbeatResponse,
+    };
+    use futures::stream;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Service {
+        heartbeats: Arc<Mutex<Vec<Heartbeat>>>,
+    }
+
+    #[tonic::async_trait]
+    impl Shredstream for Service {
+        async fn send_heartbeat(
+            &self,
+            request: tonic::Request<Heartbeat>,
+        ) -> std::result::Result<tonic::Response<HeartbeatResponse>, tonic::Status> {
+            self.heartbeats.lock().unwrap().push(request.into_inner());
+            Ok(tonic::Response::new(HeartbeatResponse { ttl_ms: 250 }))
+        }
+    }
+
+    #[tokio::test]
+    async fn heartbeats_register_socket_and_follow_ttl() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = stream::unfold(listener, |listener| async move {
+            Some((listener.accept().await.map(|(socket, _)| socket), listener))
+        });
+        let service = Service::default();
+        let heartbeats = service.heartbeats.clone();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(ShredstreamServer::new(service))
+                .serve_with_incoming(incoming),
+        );
+
+        let config = HeartbeatConfig {
+            endpoint: format!("http://{}", addr),
+            public_ip: "203.0.113.7".to_string(),
+            port: None,
+            regions: vec!["amsterdam".to_string()],
+        };
+        assert!(config.issues().is_empty());
+        let mut client = config.client(20_000).unwrap();
+        assert_eq!(
+            client.send_heartbeat().await.unwrap(),
+            Duration::from_millis(250)
+        );
+
+        let handle = client.spawn();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(handle.last_ttl(), Some(Duration::from_millis(250)));
+        drop(handle);
+        let sent = heartbeats.lock().unwrap().clone();
+        // The direct heartbeat, the spawned one, and its renewal after ttl / 2.
+        assert_eq!(sent.len(), 3);
+        let socket = sent[0].socket.as_ref().unwrap();
+        assert_eq!((socket.ip.as_str(), socket.port), ("203.0.113.7", 20_000));
+        assert_eq!(sent[0].regions, ["amsterdam"]);
+    }
+
+    #[test]
+    fn config_issues_name_each_field() {
+        let config = HeartbeatConfig {
+            endpoint: "ftp://example.com".to_string(),
+            public_ip: "0.0.0.0".to_string(),
+            port: Some(0),
+            regions: Vec::new(),
+        };
+        assert_eq!(
+            config.issues(),
+            [
+                "endpoint: unsupported scheme \"ftp\" (expected http or https)",
+                "public_ip: 0.0.0.0 is not routable",
+                "port: must be at least 1",
+                "regions: list at least one region",
+            ]
+        );
+    }
+}
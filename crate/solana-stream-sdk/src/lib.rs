@@ -4,10 +4,17 @@
 //! This crate provides convenient wrappers around the Shreds protobuf definitions
 //! for easier integration with Solana streaming services.
 
+pub mod config_reload;
+pub mod entries_request;
 pub mod error;
+pub mod geyser;
+pub mod heartbeat;
+pub mod proxy_server;
 pub mod shreds_udp;
 pub mod shredstream;
 pub mod txn;
+pub mod watch_rule;
+pub mod watch_sink;
 pub use yellowstone_grpc_client;
 pub use yellowstone_grpc_proto;
 
@@ -24,9 +31,37 @@ pub mod shredstream_proto {
 // Re-export error types
 pub use error::SolanaStreamError;
 // Re-export shredstream client
-pub use shredstream::ShredstreamClient;
+pub use shredstream::{
+    decode_slot_entry, ConnectionEvent, DecodedEntryStream, ReconnectPolicy,
+    ReconnectingEntryStream, ShredstreamClient, ShredstreamClientBuilder, ShredstreamEvent,
+    WatchedEntryStream,
+};
+// Re-export typed entries requests
+pub use entries_request::{
+    AccountsFilter, EntriesFilter, EntriesRequestBuilder, LamportsCmp, Memcmp, MemcmpData,
+    SlotsFilter, TransactionsFilter,
+};
+// Re-export tonic types used by ShredstreamClientBuilder
+pub use tonic::{
+    codec::CompressionEncoding,
+    transport::{Certificate, ClientTlsConfig, Identity},
+};
 // Re-export UDP receiver
 pub use shreds_udp::{deshred_shreds_to_entries, UdpDatagram, UdpShredReceiver};
+// Re-export live config reload
+pub use config_reload::{ConfigWatcher, LiveWatchSettings, WatchSettings};
+// Re-export watch sinks
+pub use watch_sink::{WatchSink, WatchSinkConfig, WatchSinkFilter, WatchSinks};
+// Re-export Geyser watch detection
+pub use geyser::{
+    account_keys_from_geyser, collect_watch_events_from_geyser, versioned_transaction_from_geyser,
+};
+// Re-export Shredstream heartbeats
+pub use heartbeat::{HeartbeatConfig, HeartbeatHandle, ShredstreamHeartbeatClient};
+// Re-export the local ShredstreamProxy server
+pub use proxy_server::{ProxyConfig, ShredstreamProxyService};
+// Re-export watch rules
+pub use watch_rule::{RuleList, WatchRule, WatchRules};
 
 // Shredstream protobuf exports
 pub use shredstream_proto::{
@@ -80,7 +115,9 @@ pub use yellowstone_grpc_proto::{
         SubscribeUpdate as GeyserSubscribeUpdate,
         SubscribeUpdateAccountInfo as GeyserSubscribeUpdateAccountInfo,
         SubscribeUpdateEntry as GeyserSubscribeUpdateEntry,
+        SubscribeUpdateTransaction as GeyserSubscribeUpdateTransaction,
         SubscribeUpdateTransactionInfo as GeyserSubscribeUpdateTransactionInfo,
+        TransactionStatusMeta as GeyserTransactionStatusMeta,
     },
     prost::Message as GeyserMessage,
 };
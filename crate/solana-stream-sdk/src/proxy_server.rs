@@ -0,0 +1,639 @@
+//! Local `ShredstreamProxy` gRPC server fed by the UDP deshred pipeline.
+//!
+//! [`ShredstreamProxyService`] serves `SubscribeEntries` from `shredstream.proto`, so one UDP
+//! feed can be shared with many clients, including an unchanged
+//! [`ShredstreamClient`](crate::ShredstreamClient). Each client gets a bounded buffer; a client
+//! that lets it fill up is disconnected with `RESOURCE_EXHAUSTED` instead of slowing the feed
+//! or the other clients down, and if the feed itself falls behind the UDP pipeline every client
+//! is disconnected with `DATA_LOSS`. Set `proxy` in the shreds-udp settings (see [`ProxyConfig`]) or
+//! wire it up yourself:
+//!
+//! ```no_run
+//! use solana_stream_sdk::{
+//!     shreds_udp::{ShredsUdpConfig, ShredsUdpState},
+//!     ShredstreamProxyService,
+//! };
+//!
+//! # async fn run(state: ShredsUdpState) -> Result<(), Box<dyn std::error::Error>> {
+//! let service = ShredstreamProxyService::new().with_client_buffer(256);
+//! service.spawn_feed(state.subscribe_entries());
+//! service.serve("0.0.0.0:9999".parse()?).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Shreds carry neither account state nor confirmations, which limits the filters honored:
+//!
+//! * A transactions filter matches on the transaction's static account keys (keys loaded from
+//!   lookup tables are not known) with `account_include` (any), `account_exclude` (none) and
+//!   `account_required` (all).
+//! * An accounts filter matches transactions mentioning one of its `account` keys or invoking
+//!   one of its `owner` programs. Data filters (memcmp, datasize, lamports, token account
+//!   state) are rejected with `INVALID_ARGUMENT`.
+//! * Only `PROCESSED` commitment can be served; other levels are rejected.
+//! * Slots filters are accepted and ignored.
+//!
+//! A transaction is sent when it matches any filter, and without filters everything is sent.
+//! Empty strings, as in the placeholder filters older clients send, are ignored, and a filter
+//! left with no keys does not constrain. Matching entries are sent
+//! whole, bincode-encoded. An entry's PoH hash chains from the entry before it, so only an
+//! unfiltered subscription receives every entry needed to verify the hashes; a filtered one
+//! gets the matching entries alone and cannot check them.
+
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use log::{error, info, warn};
+use serde::Deserialize;
+use solana_entry::entry::Entry;
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use tokio::{
+    net::TcpListener,
+    sync::{broadcast, mpsc},
+    task::JoinHandle,
+};
+use tonic::{Request, Response, Status};
+
+use crate::{
+    shreds_udp::SlotEntries,
+    shredstream_proto::{
+        self,
+        shredstream_proxy_server::{ShredstreamProxy, ShredstreamProxyServer},
+        CommitmentLevel, SubscribeEntriesRequest,
+    },
+    Result,
+};
+
+/// Entry messages buffered per client before it is disconnected as too slow.
+pub const DEFAULT_CLIENT_BUFFER: usize = 1024;
+
+/// `proxy` section of the shreds-udp settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyConfig {
+    /// Address the gRPC server listens on, e.g. `0.0.0.0:9999`.
+    pub bind_addr: String,
+    /// Entry messages buffered per client; defaults to [`DEFAULT_CLIENT_BUFFER`].
+    #[serde(default = "default_client_buffer")]
+    pub client_buffer: usize,
+    /// Clients served at once; unlimited when unset.
+    #[serde(default)]
+    pub max_clients: Option<usize>,
+}
+
+fn default_client_buffer() -> usize {
+    DEFAULT_CLIENT_BUFFER
+}
+
+impl ProxyConfig {
+    /// Validation problems, each prefixed with its field name.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.bind_addr.parse::<SocketAddr>().is_err() {
+            issues.push(format!(
+                "bind_addr: expected ip:port, got {:?}",
+                self.bind_addr
+            ));
+        }
+        if self.client_buffer == 0 {
+            issues.push("client_buffer: must be at least 1".to_string());
+        }
+        if self.max_clients == Some(0) {
+            issues.push("max_clients: must be at least 1".to_string());
+        }
+        issues
+    }
+
+    pub fn service(&self) -> ShredstreamProxyService {
+        let service = ShredstreamProxyService::new().with_client_buffer(self.client_buffer);
+        match self.max_clients {
+            Some(max) => service.with_max_clients(max),
+            None => service,
+        }
+    }
+}
+
+/// `ShredstreamProxy` implementation serving published entries to subscribed clients.
+///
+/// Clones share their clients, so one clone can be fed while another is served.
+#[derive(Clone)]
+pub struct ShredstreamProxyService {
+    clients: Arc<Mutex<Vec<Subscriber>>>,
+    next_id: Arc<AtomicU64>,
+    client_buffer: usize,
+    max_clients: Option<usize>,
+}
+
+struct Subscriber {
+    id: u64,
+    filter: EntryFilter,
+    tx: mpsc::Sender<std::result::Result<shredstream_proto::Entry, Status>>,
+    /// Set when the client is dropped by the server, so its stream ends with this error.
+    closed: Arc<OnceLock<Status>>,
+}
+
+impl Default for ShredstreamProxyService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShredstreamProxyService {
+    pub fn new() -> Self {
+        Self {
+            clients: Arc::new(Mutex::new(Vec::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            client_buffer: DEFAULT_CLIENT_BUFFER,
+            max_clients: None,
+        }
+    }
+
+    /// Entry messages buffered per client (at least 1).
+    pub fn with_client_buffer(mut self, client_buffer: usize) -> Self {
+        self.client_buffer = client_buffer.max(1);
+        self
+    }
+
+    /// Refuse subscriptions beyond `max_clients` with `RESOURCE_EXHAUSTED`.
+    pub fn with_max_clients(mut self, max_clients: usize) -> Self {
+        self.max_clients = Some(max_clients);
+        self
+    }
+
+    /// Clients currently subscribed.
+    pub fn clients(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    /// Send one batch to every client whose filters it matches.
+    ///
+    /// Never blocks: a client whose buffer is full is disconnected.
+    pub fn publish(&self, batch: &SlotEntries) {
+        let mut clients = self.clients.lock().unwrap();
+        if clients.is_empty() {
+            return;
+        }
+        // Clients without filters share one encoding of the whole batch.
+        let mut whole: Option<Vec<u8>> = None;
+        clients.retain(|client| {
+            if client.tx.is_closed() {
+                info!("proxy client {} disconnected", client.id);
+                return false;
+            }
+            let encoded = match client.filter.select(&batch.entries) {
+                Selection::All => match &whole {
+                    Some(encoded) => Ok(encoded.clone()),
+                    None => bincode::serialize(&batch.entries)
+                        .inspect(|encoded| whole = Some(encoded.clone())),
+                },
+                Selection::Some(entries) => bincode::serialize(&entries),
+                Selection::None => return true,
+            };
+            let entries = match encoded {
+                Ok(entries) => entries,
+                Err(e) => {
+                    error!("proxy failed to encode slot {}: {}", batch.slot, e);
+                    return true;
+                }
+            };
+            let message = shredstream_proto::Entry {
+                slot: batch.slot,
+                entries,
+            };
+            match client.tx.try_send(Ok(message)) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!(
+                        "proxy client {} fell {} messages behind; disconnecting",
+                        client.id, self.client_buffer
+                    );
+                    let _ = client.closed.set(Status::resource_exhausted(format!(
+                        "client fell {} messages behind and was disconnected",
+                        self.client_buffer
+                    )));
+                    false
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    info!("proxy client {} disconnected", client.id);
+                    false
+                }
+            }
+        });
+    }
+
+    /// Disconnect every client, ending each stream with `status`.
+    pub fn disconnect_all(&self, status: Status) {
+        for client in self.clients.lock().unwrap().drain(..) {
+            info!(
+                "proxy client {} disconnected: {}",
+                client.id,
+                status.message()
+            );
+            let _ = client.closed.set(status.clone());
+        }
+    }
+
+    /// Publish every batch from `entries`, e.g.
+    /// [`ShredsUdpState::subscribe_entries`](crate::shreds_udp::ShredsUdpState::subscribe_entries),
+    /// until its sender is dropped.
+    ///
+    /// If the feed falls behind `entries`, the skipped batches are lost for every client, so
+    /// all of them are disconnected with `DATA_LOSS` and can resubscribe.
+    pub fn spawn_feed(&self, mut entries: broadcast::Receiver<Arc<SlotEntries>>) -> JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match entries.recv().await {
+                    Ok(batch) => service.publish(&batch),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "proxy feed fell behind; skipped {} batches, disconnecting clients",
+                            skipped
+                        );
+                        service.disconnect_all(Status::data_loss(format!(
+                            "proxy feed fell behind and skipped {} batches",
+                            skipped
+                        )));
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Serve `SubscribeEntries` on `addr` until the server fails.
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        self.serve_listener(TcpListener::bind(addr).await?).await
+    }
+
+    /// Serve `SubscribeEntries` on an already bound listener.
+    pub async fn serve_listener(self, listener: TcpListener) -> Result<()> {
+        let incoming = stream::unfold(listener, |listener| async move {
+            Some((listener.accept().await.map(|(socket, _)| socket), listener))
+        });
+        tonic::transport::Server::builder()
+            .add_service(ShredstreamProxyServer::new(self))
+            .serve_with_incoming(incoming)
+            .await?;
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl ShredstreamProxy for ShredstreamProxyService {
+    type SubscribeEntriesStream =
+        BoxStream<'static, std::result::Result<shredstream_proto::Entry, Status>>;
+
+    async fn subscribe_entries(
+        &self,
+        request: Request<SubscribeEntriesRequest>,
+    ) -> std::result::Result<Response<Self::SubscribeEntriesStream>, Status> {
+        let remote = request.remote_addr();
+        let filter = EntryFilter::compile(&request.into_inner())?;
+        let (tx, rx) = mpsc::channel(self.client_buffer);
+        let closed = Arc::new(OnceLock::new());
+        let id = {
+            let mut clients = self.clients.lock().unwrap();
+            if let Some(max) = self.max_clients.filter(|max| clients.len() >= *max) {
+                return Err(Status::resource_exhausted(format!(
+                    "proxy serves at most {} clients",
+                    max
+                )));
+            }
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            clients.push(Subscriber {
+                id,
+                filter,
+                tx,
+                closed: closed.clone(),
+            });
+            id
+        };
+        info!("proxy client {} subscribed from {:?}", id, remote);
+
+        let stream = stream::unfold(Some((rx, closed)), |state| async move {
+            let (mut rx, closed) = state?;
+            match rx.recv().await {
+                Some(item) => Some((item, Some((rx, closed)))),
+                None => closed.get().map(|status| (Err(status.clone()), None)),
+            }
+        });
+        Ok(Response::new(stream.boxed()))
+    }
+}
+
+/// Which entries of a batch a client receives.
+enum Selection<'a> {
+    All,
+    Some(Vec<&'a Entry>),
+    None,
+}
+
+/// Compiled filters of one subscription; empty matches everything.
+#[derive(Default)]
+struct EntryFilter {
+    accounts: Vec<AccountsMatch>,
+    transactions: Vec<TransactionsMatch>,
+}
+
+struct AccountsMatch {
+    accounts: HashSet<Pubkey>,
+    owners: HashSet<Pubkey>,
+}
+
+struct TransactionsMatch {
+    include: HashSet<Pubkey>,
+    exclude: HashSet<Pubkey>,
+    required: HashSet<Pubkey>,
+}
+
+impl EntryFilter {
+    fn compile(request: &SubscribeEntriesRequest) -> std::result::Result<Self, Status> {
+        if let Some(level) = request.commitment {
+            if CommitmentLevel::try_from(level) != Ok(CommitmentLevel::Processed) {
+                return Err(Status::invalid_argument(
+                    "commitment: only PROCESSED is available from shreds",
+                ));
+            }
+        }
+        let mut filter = Self::default();
+        for (name, accounts) in &request.accounts {
+            if !accounts.filters.is_empty() {
+                return Err(Status::invalid_argument(format!(
+                    "accounts.{}.filters: data filters need account state, which shreds do not carry",
+                    name
+                )));
+            }
+            let matcher = AccountsMatch {
+                accounts: parse_keys(&accounts.account, name, "accounts", "account")?,
+                owners: parse_keys(&accounts.owner, name, "accounts", "owner")?,
+            };
+            if !matcher.accounts.is_empty() || !matcher.owners.is_empty() {
+                filter.accounts.push(matcher);
+            }
+        }
+        for (name, transactions) in &request.transactions {
+            let matcher = TransactionsMatch {
+                include: parse_keys(
+                    &transactions.account_include,
+                    name,
+                    "transactions",
+                    "account_include",
+                )?,
+                exclude: parse_keys(
+                    &transactions.account_exclude,
+                    name,
+                    "transactions",
+                    "account_exclude",
+                )?,
+                required: parse_keys(
+                    &transactions.account_required,
+                    name,
+                    "transactions",
+                    "account_required",
+                )?,
+            };
+            if !matcher.include.is_empty()
+                || !matcher.exclude.is_empty()
+                || !matcher.required.is_empty()
+            {
+                filter.transactions.push(matcher);
+            }
+        }
+        Ok(filter)
+    }
+
+    fn select<'a>(&self, entries: &'a [Entry]) -> Selection<'a> {
+        if self.accounts.is_empty() && self.transactions.is_empty() {
+            return Selection::All;
+        }
+        let selected: Vec<&Entry> = entries
+            .iter()
+            .filter(|entry| entry.transactions.iter().any(|tx| self.matches(tx)))
+            .collect();
+        if selected.is_empty() {
+            Selection::None
+        } else if selected.len() == entries.len() {
+            Selection::All
+        } else {
+            Selection::Some(selected)
+        }
+    }
+
+    fn matches(&self, tx: &VersionedTransaction) -> bool {
+        let keys = tx.message.static_account_keys();
+        self.accounts.iter().any(|matcher| {
+            keys.iter().any(|key| matcher.accounts.contains(key))
+                || tx
+                    .message
+                    .instructions()
+                    .iter()
+                    .any(|ix| matcher.owners.contains(ix.program_id(keys)))
+        }) || self.transactions.iter().any(|matcher| {
+            (matcher.include.is_empty() || keys.iter().any(|key| matcher.include.contains(key)))
+                && !keys.iter().any(|key| matcher.exclude.contains(key))
+                && matcher.required.iter().all(|key| keys.contains(key))
+        })
+    }
+}
+
+fn parse_keys(
+    keys: &[String],
+    name: &str,
+    kind: &str,
+    field: &str,
+) -> std::result::Result<HashSet<Pubkey>, Status> {
+    keys.iter()
+        .filter(|key| !key.is_empty())
+        .map(|key| {
+            Pubkey::from_str(key).map_err(|_| {
+                Status::invalid_argument(format!(
+                    "{}.{}.{}: invalid pubkey {:?}",
+                    kind, name, field, key
+                ))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AccountsFilter, EntriesFilter, EntriesRequestBuilder, ShredstreamClient, TransactionsFilter,
+    };
+    use solana_sdk::{
+        hash::Hash, instruction::Instruction, message::Message, signature::Keypair, signer::Signer,
+        transaction::Transaction,
+    };
+
+    fn entry(program: Pubkey) -> Entry {
+        let payer = Keypair::new();
+        let message = Message::new(
+            &[Instruction::new_with_bytes(program, &[1], Vec::new())],
+            Some(&payer.pubkey()),
+        );
+        let tx = Transaction::new(&[&payer], message, Hash::default());
+        Entry {
+            num_hashes: 1,
+            hash: Hash::new_unique(),
+            transactions: vec![VersionedTransaction::from(tx)],
+        }
+    }
+
+    async fn serve(service: ShredstreamProxyService) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(service.serve_listener(listener));
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn clients_receive_filtered_entries_over_grpc() {
+        let pump = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let service = ShredstreamProxyService::new();
+        let endpoint = serve(service.clone()).await;
+
+        let mut all = ShredstreamClient::connect(&endpoint).await.unwrap();
+        let mut all = all
+            .subscribe_decoded_entries(ShredstreamClient::create_empty_entries_request())
+            .await
+            .unwrap();
+        let mut filtered = ShredstreamClient::connect(&endpoint).await.unwrap();
+        let request = EntriesRequestBuilder::new()
+            .with_transactions(
+                "pump",
+                TransactionsFilter::new().with_account_include([pump.to_string()]),
+            )
+            .build()
+            .unwrap();
+        let mut filtered = filtered.subscribe_decoded_entries(request).await.unwrap();
+        while service.clients() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        let batch = SlotEntries {
+            slot: 42,
+            entries: vec![entry(other), entry(pump)],
+        };
+        service.publish(&batch);
+        service.publish(&SlotEntries {
+            slot: 43,
+            entries: vec![entry(other)],
+        });
+
+        assert_eq!(
+            all.next().await.unwrap().unwrap(),
+            (42, batch.entries.clone())
+        );
+        assert_eq!(all.next().await.unwrap().unwrap().0, 43);
+        // Slot 43 has nothing for the pump filter.
+        let (slot, entries) = filtered.next().await.unwrap().unwrap();
+        assert_eq!((slot, entries), (42, vec![batch.entries[1].clone()]));
+    }
+
+    #[tokio::test]
+    async fn slow_clients_are_disconnected() {
+        let service = ShredstreamProxyService::new().with_client_buffer(2);
+        let endpoint = serve(service.clone()).await;
+        let mut client = ShredstreamClient::connect(&endpoint).await.unwrap();
+        let mut stream = client
+            .subscribe_entries(ShredstreamClient::create_empty_entries_request())
+            .await
+            .unwrap();
+        while service.clients() < 1 {
+            tokio::task::yield_now().await;
+        }
+
+        for slot in 1..=3 {
+            service.publish(&SlotEntries {
+                slot,
+                entries: vec![entry(Pubkey::new_unique())],
+            });
+        }
+        assert_eq!(service.clients(), 0);
+        assert_eq!(stream.next().await.unwrap().unwrap().slot, 1);
+        assert_eq!(stream.next().await.unwrap().unwrap().slot, 2);
+        let status = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+    }
+
+    #[tokio::test]
+    async fn lagging_feed_disconnects_clients() {
+        let service = ShredstreamProxyService::new();
+        let endpoint = serve(service.clone()).await;
+        let mut client = ShredstreamClient::connect(&endpoint).await.unwrap();
+        let mut stream = client
+            .subscribe_entries(ShredstreamClient::create_empty_entries_request())
+            .await
+            .unwrap();
+        while service.clients() < 1 {
+            tokio::task::yield_now().await;
+        }
+
+        let (tx, rx) = broadcast::channel(1);
+        for slot in 1..=3 {
+            tx.send(Arc::new(SlotEntries {
+                slot,
+                entries: vec![entry(Pubkey::new_unique())],
+            }))
+            .unwrap();
+        }
+        service.spawn_feed(rx);
+        let status = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::DataLoss);
+        assert_eq!(service.clients(), 0);
+    }
+
+    #[test]
+    fn unsupported_filters_are_rejected() {
+        let confirmed = EntriesRequestBuilder::new()
+            .with_commitment(CommitmentLevel::Confirmed)
+            .build()
+            .unwrap();
+        let status = EntryFilter::compile(&confirmed).err().unwrap();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let datasize = EntriesRequestBuilder::new()
+            .with_accounts(
+                "a",
+                AccountsFilter::new().with_filter(EntriesFilter::Datasize(165)),
+            )
+            .build()
+            .unwrap();
+        let status = EntryFilter::compile(&datasize).err().unwrap();
+        assert!(status.message().starts_with("accounts.a.filters:"));
+
+        // Placeholder filters of older clients leave only the account filter.
+        let account = Pubkey::new_unique();
+        let mut legacy = ShredstreamClient::create_entries_request_for_account(
+            account.to_string(),
+            Some(CommitmentLevel::Processed),
+        );
+        assert!(legacy.transactions.is_empty() && legacy.slots.is_empty());
+        legacy.transactions.insert(
+            String::new(),
+            shredstream_proto::SubscribeRequestFilterTransactions {
+                account_include: vec![String::new()],
+                account_exclude: vec![String::new()],
+                account_required: vec![String::new()],
+            },
+        );
+        let filter = EntryFilter::compile(&legacy).unwrap();
+        assert_eq!((filter.accounts.len(), filter.transactions.len()), (1, 0));
+        let entries = [entry(account), entry(Pubkey::new_unique())];
+        assert!(matches!(filter.select(&entries), Selection::Some(ref e) if e.len() == 1));
+    }
+}
@@ -1,15 +1,23 @@
 use crate::{
+    config_reload::{LiveWatchSettings, WatchSettings},
+    heartbeat::HeartbeatConfig,
+    proxy_server::ProxyConfig,
     txn::{
-        default_token_program_ids, detect_program_hit, first_signatures, parse_pubkeys, MintDetail,
-        ProgramHit, ProgramWatchConfig,
+        default_jito_tip_accounts, default_token_program_ids, detect_program_hit, first_signatures,
+        fmt_pubkeys, load_wallets, parse_pubkeys, tip_lamports, with_resolved_keys, AmountBound,
+        ComputeBudget, IdlDetailer, IdlProgramConfig, InstructionEvent, JitoTipTracker, MintDetail,
+        Prefilter, ProgramHit, ProgramWatchConfig, Protocol, SlotTips, TxMeta, TxOutcome,
+        WalletMatch, WatchAction,
     },
+    watch_rule::{RuleList, WatchRules},
+    watch_sink::{WatchSinkConfig, WatchSinks},
     Result, SolanaStreamError,
 };
 use chrono::{DateTime, LocalResult, TimeZone, Utc};
 use dashmap::DashMap;
 use futures::future::join_all;
 use log::{error, info, warn};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use solana_ledger::shred::{
     Shred, Shredder, MAX_CODE_SHREDS_PER_SLOT, MAX_DATA_SHREDS_PER_SLOT, SIZE_OF_NONCE,
 };
@@ -20,11 +28,15 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     env, fs,
     path::{Path, PathBuf},
+    str::FromStr,
     sync::atomic::{AtomicU64, Ordering},
     sync::Arc,
     time::{Duration, Instant},
 };
-use tokio::{net::UdpSocket, sync::Mutex};
+use tokio::{
+    net::UdpSocket,
+    sync::{broadcast, Mutex},
+};
 
 const COMMON_HEADER_LEN: usize = 83;
 const CODING_HEADER_LEN: usize = 6;
@@ -35,6 +47,9 @@ const MERKLE_DATA_SHRED_PAYLOAD_WITH_NONCE: usize = MERKLE_DATA_SHRED_PAYLOAD_SI
 // Maximum UDP payload we expect to receive (legacy/repair shreds with nonce).
 const MAX_UDP_PAYLOAD_SIZE: usize = PACKET_DATA_SIZE;
 
+const SLOT_TIPS_CHANNEL_CAPACITY: usize = 256;
+const ENTRIES_CHANNEL_CAPACITY: usize = 1024;
+
 pub const DEFAULT_BIND_ADDR: &str = "0.0.0.0:10001";
 pub const DEFAULT_RPC_ENDPOINT: &str = "https://api.mainnet-beta.solana.com";
 pub const DEFAULT_WATCH_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"; // pump.fun program (sample)
@@ -111,15 +126,20 @@ pub fn deshred_shreds_to_entries(
     let data = Shredder::deshred(payloads)
         .map_err(|e| SolanaStreamError::Serialization(format!("deshred failed: {e}")))?;
 
-    wincode::deserialize::<Vec<solana_entry::entry::Entry>>(&data)
+    decode_entries(&data).map_err(SolanaStreamError::Serialization)
+}
+
+/// Decode serialized `Vec<Entry>` bytes: wincode (Agave 3.x) first, then bincode.
+pub(crate) fn decode_entries(
+    data: &[u8],
+) -> std::result::Result<Vec<solana_entry::entry::Entry>, String> {
+    wincode::deserialize::<Vec<solana_entry::entry::Entry>>(data)
         .or_else(|wincode_err| {
-            bincode::deserialize::<Vec<solana_entry::entry::Entry>>(&data)
+            bincode::deserialize::<Vec<solana_entry::entry::Entry>>(data)
                 .map_err(|bincode_err| (wincode_err, bincode_err))
         })
         .map_err(|(wincode_err, bincode_err)| {
-            SolanaStreamError::Serialization(format!(
-                "entry decode failed: wincode={wincode_err}; bincode={bincode_err}"
-            ))
+            format!("entry decode failed: wincode={wincode_err}; bincode={bincode_err}")
         })
 }
 
@@ -139,6 +159,17 @@ pub struct ShredsUdpConfig {
     pub watch_program_ids: Vec<Pubkey>,
     pub watch_authorities: Vec<Pubkey>,
     pub token_program_ids: Vec<Pubkey>,
+    /// Jito tip accounts; empty falls back to the mainnet list.
+    pub jito_tip_accounts: Vec<Pubkey>,
+    /// Mints whose token transfers/burns/authority changes are watched (empty = off).
+    pub watch_token_mints: Vec<Pubkey>,
+    /// Tracked wallets (signer/fee-payer matching), merged with `watch_wallets_file`.
+    pub watch_wallets: Vec<Pubkey>,
+    /// Wallet list file, re-read on each reload (see [`load_wallets`]).
+    pub watch_wallets_file: Option<PathBuf>,
+    pub wallet_match: WalletMatch,
+    /// Probabilistic prefilter in front of the watch-list hash sets.
+    pub watch_prefilter: Prefilter,
     pub completed_ttl: Duration,
     pub enable_latency_monitor: bool,
     pub strict_fec: bool,
@@ -149,6 +180,16 @@ pub struct ShredsUdpConfig {
     pub evict_cooldown: Duration,
     pub warn_once_per_fec: bool,
     pub pump_min_lamports: u64,
+    pub sinks: Vec<WatchSinkConfig>,
+    /// Named filter expressions that sinks reference by name (see [`WatchRules`]).
+    pub rules: BTreeMap<String, String>,
+    /// Key lists referenced from rules as `@name`.
+    pub lists: BTreeMap<String, RuleList>,
+    pub idls: Vec<IdlProgramConfig>,
+    /// Register `bind_addr` with the Shredstream service while running (off when `None`).
+    pub heartbeat: Option<HeartbeatConfig>,
+    /// Serve deshredded entries over gRPC as a local `ShredstreamProxy` (off when `None`).
+    pub proxy: Option<ProxyConfig>,
 }
 
 #[derive(Clone)]
@@ -163,6 +204,18 @@ pub struct ShredsUdpState {
     suppressed_ttl: Duration,
     warnings: Arc<Mutex<HashMap<FecKey, Instant>>>,
     metrics: Arc<ShredMetrics>,
+    sinks: WatchSinks,
+    watch: LiveWatchSettings,
+    jito_tips: Arc<Mutex<JitoTipTracker>>,
+    slot_tips: broadcast::Sender<SlotTips>,
+    entries: broadcast::Sender<Arc<SlotEntries>>,
+}
+
+/// Entries of one deshredded batch, as published by [`ShredsUdpState::subscribe_entries`].
+#[derive(Debug, Clone)]
+pub struct SlotEntries {
+    pub slot: u64,
+    pub entries: Vec<solana_entry::entry::Entry>,
 }
 
 #[derive(Default)]
@@ -250,6 +303,12 @@ impl Default for ShredsUdpConfig {
             watch_program_ids: Vec::new(),
             watch_authorities: Vec::new(),
             token_program_ids: default_token_program_ids(),
+            jito_tip_accounts: default_jito_tip_accounts(),
+            watch_token_mints: Vec::new(),
+            watch_wallets: Vec::new(),
+            watch_wallets_file: None,
+            wallet_match: WalletMatch::default(),
+            watch_prefilter: Prefilter::default(),
             completed_ttl: DEFAULT_COMPLETED_TTL,
             enable_latency_monitor: false,
             strict_fec: true,
@@ -260,6 +319,12 @@ impl Default for ShredsUdpConfig {
             evict_cooldown: DEFAULT_EVICT_COOLDOWN,
             warn_once_per_fec: true,
             pump_min_lamports: 0,
+            sinks: Vec::new(),
+            rules: BTreeMap::new(),
+            lists: BTreeMap::new(),
+            idls: Vec::new(),
+            heartbeat: None,
+            proxy: None,
         }
     }
 }
@@ -327,6 +392,24 @@ impl ShredsUdpConfig {
         if let Some(v) = file.token_program_ids {
             self.token_program_ids = parse_pubkeys(Some(v.as_str()), &[]);
         }
+        if let Some(v) = file.jito_tip_accounts {
+            self.jito_tip_accounts = parse_pubkeys(Some(v.as_str()), &[]);
+        }
+        if let Some(v) = file.watch_token_mints {
+            self.watch_token_mints = parse_pubkeys(Some(v.as_str()), &[]);
+        }
+        if let Some(v) = file.watch_wallets {
+            self.watch_wallets = parse_pubkeys(Some(v.as_str()), &[]);
+        }
+        if let Some(v) = file.watch_wallets_file {
+            self.watch_wallets_file = Some(v);
+        }
+        if let Some(v) = file.wallet_match {
+            self.wallet_match = v;
+        }
+        if let Some(v) = file.watch_prefilter {
+            self.watch_prefilter = v;
+        }
         if let Some(ms) = file.completed_ttl_ms {
             self.completed_ttl = Duration::from_millis(ms);
         }
@@ -357,6 +440,24 @@ impl ShredsUdpConfig {
         if let Some(v) = file.pump_min_lamports {
             self.pump_min_lamports = v;
         }
+        if let Some(v) = file.sinks {
+            self.sinks = v;
+        }
+        if let Some(v) = file.rules {
+            self.rules = v;
+        }
+        if let Some(v) = file.lists {
+            self.lists = v;
+        }
+        if let Some(v) = file.idls {
+            self.idls = v;
+        }
+        if let Some(v) = file.heartbeat {
+            self.heartbeat = Some(v);
+        }
+        if let Some(v) = file.proxy {
+            self.proxy = Some(v);
+        }
         self
     }
 
@@ -428,6 +529,155 @@ impl ShredsUdpConfig {
         cfg
     }
 
+    /// Strictly parse a JSONC/TOML settings string without applying env overrides.
+    ///
+    /// Unlike [`ShredsUdpConfig::from_embedded`], nothing is silently dropped: unknown fields,
+    /// values of the wrong type, unparseable pubkeys and out-of-range values are all collected
+    /// and returned together as one [`SolanaStreamError::Configuration`].
+    pub fn try_from_str(raw: &str) -> Result<Self> {
+        Self::try_build(raw, "settings", false)
+    }
+
+    /// Strict counterpart of [`ShredsUdpConfig::from_embedded`]; env overrides are validated too.
+    pub fn try_from_embedded(raw: &str) -> Result<Self> {
+        Self::try_build(raw, "embedded settings", true)
+    }
+
+    /// Strictly load settings from `path` and apply env overrides.
+    pub fn try_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = fs::read_to_string(path)
+            .map_err(|e| SolanaStreamError::Configuration(format!("{}: {}", path.display(), e)))?;
+        Self::try_build(&raw, &path.display().to_string(), true)
+    }
+
+    /// Strict counterpart of [`ShredsUdpConfig::from_env`]: a missing `SHREDS_UDP_CONFIG`
+    /// target or no settings file on the search path is an error rather than a fallback.
+    pub fn try_from_env() -> Result<Self> {
+        let cwd = env::current_dir().ok();
+        let exe_dir = env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(|p| p.to_path_buf()));
+        let env_config = env::var("SHREDS_UDP_CONFIG").ok();
+        if let Some(raw) = env_config.as_deref() {
+            if !Path::new(raw).exists() {
+                return Err(SolanaStreamError::Configuration(format!(
+                    "SHREDS_UDP_CONFIG={} not found",
+                    raw
+                )));
+            }
+        }
+        let (config_path, searched_paths) =
+            resolve_config_path(env_config.as_deref(), cwd.as_deref(), exe_dir.as_deref());
+        match config_path {
+            Some(path) => Self::try_from_file(path),
+            None => Err(SolanaStreamError::Configuration(format!(
+                "no settings file found (searched {})",
+                searched_paths
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    /// Check value ranges of an already-built config (e.g. one assembled in code).
+    pub fn validate(&self) -> Result<()> {
+        config_error("config", self.range_issues())
+    }
+
+    fn try_build(raw: &str, source: &str, with_env: bool) -> Result<Self> {
+        let mut issues = Vec::new();
+        let file = parse_config_strict(raw, &mut issues)
+            .map_err(|e| SolanaStreamError::Configuration(format!("{}: {}", source, e)))?;
+        let mut cfg = Self::defaults().apply_file(file);
+        if with_env {
+            issues.extend(env_issues());
+            cfg = apply_env_overrides(cfg);
+        }
+        issues.extend(cfg.range_issues());
+        config_error(source, issues)?;
+        Ok(cfg)
+    }
+
+    fn range_issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if !is_host_port(&self.bind_addr) {
+            issues.push(format!(
+                "bind_addr: expected ip:port, got {:?}",
+                self.bind_addr
+            ));
+        }
+        match url::Url::parse(&self.rpc_endpoint) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(url) => issues.push(format!(
+                "rpc_endpoint: unsupported scheme {:?} (expected http or https)",
+                url.scheme()
+            )),
+            Err(e) => issues.push(format!("rpc_endpoint: {}", e)),
+        }
+        for (key, value) in [
+            ("strict_num_data", self.strict_num_data),
+            ("strict_num_coding", self.strict_num_coding),
+        ] {
+            if value == 0 {
+                issues.push(format!("{}: must be at least 1", key));
+            }
+        }
+        let fec_total = usize::from(self.strict_num_data) + usize::from(self.strict_num_coding);
+        if fec_total > MAX_SHREDS_PER_FEC_SET {
+            issues.push(format!(
+                "strict_num_data + strict_num_coding: {} exceeds {} shreds per FEC set",
+                fec_total, MAX_SHREDS_PER_FEC_SET
+            ));
+        }
+        if self.slot_window_max_future == 0 {
+            issues.push("slot_window_max_future: must be at least 1".to_string());
+        }
+        if self.completed_ttl.is_zero() {
+            issues.push("completed_ttl_ms: must be greater than 0".to_string());
+        }
+        let rules = match self.watch_rules() {
+            Ok(rules) => Some(rules),
+            Err(e) => {
+                issues.push(format!("rules: {}", e));
+                None
+            }
+        };
+        for (index, sink) in self.sinks.iter().enumerate() {
+            for issue in sink.issues() {
+                issues.push(format!("sinks[{}]: {}", index, issue));
+            }
+            if let Some(rules) = &rules {
+                if let Err(e) = sink.filter.clone().with_rules(rules) {
+                    issues.push(format!("sinks[{}]: {}", index, e));
+                }
+            }
+        }
+        if let Some(path) = &self.watch_wallets_file {
+            if let Err(e) = load_wallets(path) {
+                issues.push(format!("watch_wallets_file: {}", e));
+            }
+        }
+        for (index, idl) in self.idls.iter().enumerate() {
+            if let Err(e) = IdlDetailer::from_config(idl) {
+                issues.push(format!("idls[{}]: {}", index, e));
+            }
+        }
+        if let Some(heartbeat) = &self.heartbeat {
+            for issue in heartbeat.issues() {
+                issues.push(format!("heartbeat.{}", issue));
+            }
+        }
+        if let Some(proxy) = &self.proxy {
+            for issue in proxy.issues() {
+                issues.push(format!("proxy.{}", issue));
+            }
+        }
+        issues
+    }
+
     pub fn watch_config(&self) -> ProgramWatchConfig {
         ProgramWatchConfig::new(
             if self.watch_program_ids.is_empty() {
@@ -447,6 +697,11 @@ impl ShredsUdpConfig {
             self.token_program_ids.clone()
         })
         .with_skip_vote_txs(self.skip_vote_sigs)
+        .with_jito_tip_accounts(self.jito_tip_accounts_or_default())
+        .with_token_mints(self.watch_token_mints.clone())
+        .with_wallets(self.wallets(), self.wallet_match)
+        .with_idl_detailers(self.idl_detailers())
+        .with_prefilter(self.watch_prefilter)
     }
 
     /// Build a watch config without populating pump.fun defaults when the lists are empty.
@@ -458,11 +713,55 @@ impl ShredsUdpConfig {
                 self.token_program_ids.clone()
             })
             .with_skip_vote_txs(self.skip_vote_sigs)
+            .with_jito_tip_accounts(self.jito_tip_accounts_or_default())
+            .with_token_mints(self.watch_token_mints.clone())
+            .with_wallets(self.wallets(), self.wallet_match)
+            .with_idl_detailers(self.idl_detailers())
+            .with_prefilter(self.watch_prefilter)
+    }
+
+    /// `watch_wallets` plus the wallets file; a file that fails to load is logged and skipped.
+    pub fn wallets(&self) -> Vec<Pubkey> {
+        let mut wallets = self.watch_wallets.clone();
+        if let Some(path) = &self.watch_wallets_file {
+            match load_wallets(path) {
+                Ok(loaded) => wallets.extend(loaded),
+                Err(e) => warn!("Skipping wallets file: {}", e),
+            }
+        }
+        wallets
+    }
+
+    fn jito_tip_accounts_or_default(&self) -> Vec<Pubkey> {
+        if self.jito_tip_accounts.is_empty() {
+            default_jito_tip_accounts()
+        } else {
+            self.jito_tip_accounts.clone()
+        }
+    }
+
+    /// Compile `rules` against `lists`; the first invalid list or expression is an error.
+    pub fn watch_rules(&self) -> Result<WatchRules> {
+        WatchRules::compile(&self.rules, &self.lists)
+    }
+
+    /// Load every configured IDL; files that fail to load are logged and skipped.
+    pub fn idl_detailers(&self) -> Vec<Arc<IdlDetailer>> {
+        self.idls
+            .iter()
+            .filter_map(|cfg| match IdlDetailer::from_config(cfg) {
+                Ok(detailer) => Some(Arc::new(detailer)),
+                Err(e) => {
+                    warn!("Skipping IDL: {}", e);
+                    None
+                }
+            })
+            .collect()
     }
 
     pub fn describe(&self) -> String {
         format!(
-            "bind_addr={} rpc={} slot_window_root={:?} max_future={} strict_fec={} num_data={} num_coding={} require_code_match={} log_raw={} log_shreds={} log_entries={} log_deshred_attempts={} evict_cooldown_ms={} completed_ttl_ms={} warn_once_per_fec={} pump_min_lamports={}",
+            "bind_addr={} rpc={} slot_window_root={:?} max_future={} strict_fec={} num_data={} num_coding={} require_code_match={} log_raw={} log_shreds={} log_entries={} log_deshred_attempts={} evict_cooldown_ms={} completed_ttl_ms={} warn_once_per_fec={} pump_min_lamports={} sinks={} rules={} idls={} heartbeat={} proxy={}",
             self.bind_addr,
             self.rpc_endpoint,
             self.slot_window_root,
@@ -479,6 +778,15 @@ impl ShredsUdpConfig {
             self.completed_ttl.as_millis(),
             self.warn_once_per_fec,
             self.pump_min_lamports,
+            self.sinks.len(),
+            self.rules.len(),
+            self.idls.len(),
+            self.heartbeat
+                .as_ref()
+                .map_or("off", |heartbeat| heartbeat.endpoint.as_str()),
+            self.proxy
+                .as_ref()
+                .map_or("off", |proxy| proxy.bind_addr.as_str()),
         )
     }
 }
@@ -500,6 +808,69 @@ impl ShredsUdpState {
             suppressed_ttl: cfg.evict_cooldown,
             warnings: Arc::new(Mutex::new(HashMap::new())),
             metrics: Arc::new(ShredMetrics::default()),
+            sinks: WatchSinks::new(),
+            watch: LiveWatchSettings::new(WatchSettings::from_config(cfg)),
+            jito_tips: Arc::new(Mutex::new(JitoTipTracker::default())),
+            slot_tips: broadcast::channel(SLOT_TIPS_CHANNEL_CAPACITY).0,
+            entries: broadcast::channel(ENTRIES_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Attach the sinks that receive structured watch events after each deshred.
+    pub fn with_sinks(mut self, sinks: WatchSinks) -> Self {
+        self.sinks = sinks;
+        self
+    }
+
+    pub fn sinks(&self) -> &WatchSinks {
+        &self.sinks
+    }
+
+    /// Share a reloadable watch handle, e.g. one driven by a `ConfigWatcher`.
+    pub fn with_watch_settings(mut self, watch: LiveWatchSettings) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Watch config and thresholds currently in effect; swapped in place on reload.
+    pub fn watch_settings(&self) -> &LiveWatchSettings {
+        &self.watch
+    }
+
+    /// Per-slot Jito tip totals, sent once a slot has settled (see [`JitoTipTracker`]).
+    pub fn subscribe_slot_tips(&self) -> broadcast::Receiver<SlotTips> {
+        self.slot_tips.subscribe()
+    }
+
+    /// Fold one deshredded batch into the tip tracker and publish the slots it settles.
+    pub async fn record_slot_tips(&self, tips: SlotTips, log_tips: bool) {
+        let settled = self.jito_tips.lock().await.observe(tips);
+        for slot in settled {
+            if log_tips && slot.tip_lamports > 0 {
+                info!(
+                    "💰 jito slot={} tip_lamports={} txs={} bundles={}",
+                    slot.slot,
+                    slot.tip_lamports,
+                    slot.tipped_transactions,
+                    slot.bundles.len()
+                );
+            }
+            // No subscribers is fine.
+            let _ = self.slot_tips.send(slot);
+        }
+    }
+
+    /// Entries of every successfully deshredded batch, in arrival order. A receiver that
+    /// falls more than 1024 batches behind skips ahead (see [`broadcast::error::RecvError`]).
+    pub fn subscribe_entries(&self) -> broadcast::Receiver<Arc<SlotEntries>> {
+        self.entries.subscribe()
+    }
+
+    /// Publish one deshredded batch to [`subscribe_entries`](Self::subscribe_entries).
+    pub fn publish_entries(&self, slot: u64, entries: Vec<solana_entry::entry::Entry>) {
+        if self.entries.receiver_count() > 0 {
+            // Receivers may drop between the check and the send; that is fine.
+            let _ = self.entries.send(Arc::new(SlotEntries { slot, entries }));
         }
     }
 
@@ -544,13 +915,46 @@ pub async fn run_shreds_udp(
     let mut receiver = UdpShredReceiver::bind(&cfg.bind_addr, None).await?;
     let local_addr = receiver.local_addr()?;
     info!("Listening for UDP shreds on {}", local_addr);
-    info!("Ensure the sender targets this ip:port.");
+    // Kept alive for the whole run; dropping it stops the heartbeats.
+    let _heartbeat = match &cfg.heartbeat {
+        Some(heartbeat) => {
+            let client = heartbeat.client(local_addr.port())?;
+            info!(
+                "Sending Shredstream heartbeats for {} to {}",
+                client.socket(),
+                heartbeat.endpoint
+            );
+            Some(client.spawn())
+        }
+        None => {
+            info!("Ensure the sender targets this ip:port.");
+            None
+        }
+    };
 
-    let watch_cfg = Arc::new(cfg.watch_config());
     let policy = DeshredPolicy {
         require_code_match: cfg.require_code_match,
     };
-    let state = ShredsUdpState::new(&cfg);
+    let state = ShredsUdpState::new(&cfg).with_sinks(WatchSinks::from_configs_with_rules(
+        &cfg.sinks,
+        &cfg.watch_rules()?,
+    )?);
+
+    if let Some(proxy) = &cfg.proxy {
+        // Bind before spawning so a taken port fails startup instead of a background task.
+        let listener = tokio::net::TcpListener::bind(&proxy.bind_addr).await?;
+        info!(
+            "Serving ShredstreamProxy SubscribeEntries on {}",
+            listener.local_addr()?
+        );
+        let service = proxy.service();
+        service.spawn_feed(state.subscribe_entries());
+        tokio::spawn(async move {
+            if let Err(e) = service.serve_listener(listener).await {
+                error!("ShredstreamProxy server stopped: {}", e);
+            }
+        });
+    }
 
     let latency_handle = if cfg.enable_latency_monitor {
         if let (Some(cache), Some(txs)) = (state.block_time_cache(), state.transactions_by_slot()) {
@@ -565,20 +969,11 @@ pub async fn run_shreds_udp(
     };
 
     let receive_handle = {
-        let watch_cfg = watch_cfg.clone();
         let cfg = cfg.clone();
         let state = state.clone();
         tokio::spawn(async move {
             loop {
-                if let Err(e) = handle_pumpfun_watcher(
-                    &mut receiver,
-                    &state,
-                    &cfg,
-                    policy,
-                    watch_cfg.clone(),
-                )
-                .await
-                {
+                if let Err(e) = handle_pumpfun_watcher(&mut receiver, &state, &cfg, policy).await {
                     error!("UDP handling error: {:?}", e);
                 }
             }
@@ -716,11 +1111,30 @@ pub enum ShredInsertOutcome {
     Skipped,
 }
 
-#[derive(Debug)]
+/// One watched transaction with its per-mint details.
+///
+/// Serializes to a flat, stable JSON object: `slot`, `signature` (base58), `program_hit`,
+/// `authority_hit`, `mints` and `details`; keys and signatures are base58 strings, actions
+/// and units are snake_case strings and unknown values are `null`. IDL-decoded instructions
+/// appear under `instructions` when any were decoded, and the execution result under
+/// `outcome` when the feed carries transaction meta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchEvent {
     pub slot: u64,
+    #[serde(flatten)]
     pub hit: ProgramHit,
     pub details: Vec<MintDetail>,
+    /// Compute unit limit/price and derived priority fee of the transaction.
+    #[serde(default)]
+    pub compute_budget: ComputeBudget,
+    /// Lamports sent to Jito tip accounts by this transaction.
+    #[serde(default)]
+    pub jito_tip_lamports: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instructions: Vec<InstructionEvent>,
+    /// Failure and balance changes; only known on feeds with meta (Geyser, RPC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<TxOutcome>,
 }
 
 #[derive(Clone)]
@@ -850,6 +1264,78 @@ fn env_parse_u16(name: &str) -> Option<u16> {
     env::var(name).ok().and_then(|v| v.parse::<u16>().ok())
 }
 
+// Reed-Solomon over GF(2^8) caps a FEC set at 256 shreds (data + coding).
+const MAX_SHREDS_PER_FEC_SET: usize = 256;
+
+/// Every key accepted in the settings file; keep in sync with `ShredsUdpConfigFile`.
+const CONFIG_FILE_KEYS: &[&str] = &[
+    "bind_addr",
+    "rpc_endpoint",
+    "log_raw",
+    "log_shreds",
+    "log_entries",
+    "log_deshred_attempts",
+    "log_deshred_errors",
+    "require_code_match",
+    "skip_vote_sigs",
+    "log_watch_hits",
+    "log_deferred",
+    "watch_program_ids",
+    "watch_authorities",
+    "token_program_ids",
+    "jito_tip_accounts",
+    "watch_token_mints",
+    "watch_wallets",
+    "watch_wallets_file",
+    "wallet_match",
+    "watch_prefilter",
+    "completed_ttl_ms",
+    "enable_latency_monitor",
+    "strict_fec",
+    "strict_num_data",
+    "strict_num_coding",
+    "slot_window_root",
+    "slot_window_max_future",
+    "evict_cooldown_ms",
+    "warn_once_per_fec",
+    "pump_min_lamports",
+    "sinks",
+    "rules",
+    "lists",
+    "idls",
+    "heartbeat",
+    "proxy",
+];
+
+const ENV_BOOL_VARS: &[&str] = &[
+    "SHREDS_UDP_LOG_RAW",
+    "SHREDS_UDP_LOG_SHREDS",
+    "SHREDS_UDP_LOG_ENTRIES",
+    "SHREDS_UDP_LOG_DESHRED_ATTEMPTS",
+    "SHREDS_UDP_LOG_DESHRED_ERRORS",
+    "SHREDS_UDP_REQUIRE_CODE_MATCH",
+    "SHREDS_UDP_SKIP_VOTE_SIGS",
+    "SHREDS_UDP_LOG_WATCH_HITS",
+    "SHREDS_UDP_LOG_DEFER",
+    "SHREDS_UDP_STRICT_FEC",
+    "SHREDS_UDP_WARN_ONCE",
+    "SHREDS_UDP_ENABLE_LATENCY",
+];
+const ENV_U64_VARS: &[&str] = &[
+    "SHREDS_UDP_ROOT_SLOT",
+    "SHREDS_UDP_MAX_FUTURE",
+    "SHREDS_UDP_EVICT_COOLDOWN_MS",
+    "SHREDS_UDP_PUMP_MIN_LAMPORTS",
+    "SHREDS_UDP_COMPLETED_TTL_MS",
+];
+const ENV_U16_VARS: &[&str] = &["SHREDS_UDP_STRICT_NUM_DATA", "SHREDS_UDP_STRICT_NUM_CODING"];
+const ENV_PUBKEY_VARS: &[&str] = &[
+    "SHREDS_UDP_WATCH_PROGRAM_IDS",
+    "SHREDS_UDP_PUMPFUN_PROGRAM_IDS",
+    "SHREDS_UDP_WATCH_AUTHORITIES",
+    "SHREDS_UDP_PUMPFUN_AUTHORITIES",
+];
+
 #[derive(Debug, Deserialize, Default)]
 struct ShredsUdpConfigFile {
     bind_addr: Option<String>,
@@ -866,6 +1352,12 @@ struct ShredsUdpConfigFile {
     watch_program_ids: Option<String>,
     watch_authorities: Option<String>,
     token_program_ids: Option<String>,
+    jito_tip_accounts: Option<String>,
+    watch_token_mints: Option<String>,
+    watch_wallets: Option<String>,
+    watch_wallets_file: Option<PathBuf>,
+    wallet_match: Option<WalletMatch>,
+    watch_prefilter: Option<Prefilter>,
     completed_ttl_ms: Option<u64>,
     enable_latency_monitor: Option<bool>,
     strict_fec: Option<bool>,
@@ -876,6 +1368,12 @@ struct ShredsUdpConfigFile {
     evict_cooldown_ms: Option<u64>,
     warn_once_per_fec: Option<bool>,
     pump_min_lamports: Option<u64>,
+    sinks: Option<Vec<WatchSinkConfig>>,
+    rules: Option<BTreeMap<String, String>>,
+    lists: Option<BTreeMap<String, RuleList>>,
+    idls: Option<Vec<IdlProgramConfig>>,
+    heartbeat: Option<HeartbeatConfig>,
+    proxy: Option<ProxyConfig>,
 }
 
 fn load_config_file(path: &Path) -> Option<ShredsUdpConfigFile> {
@@ -889,22 +1387,149 @@ fn load_config_str(raw: &str) -> Option<ShredsUdpConfigFile> {
         .ok()
 }
 
-fn parse_watch_list(primary_var: &str, legacy_var: &str, defaults: &[&str]) -> Vec<Pubkey> {
-    if let Ok(raw) = env::var(primary_var) {
-        let parsed = parse_pubkeys(Some(raw.as_str()), &[]);
-        if !parsed.is_empty() {
-            return parsed;
+/// Parse a settings file, recording unknown fields, mistyped values and bad pubkeys in `issues`.
+///
+/// Each key is deserialized on its own so one bad value does not hide the others. Only a
+/// document that is not JSONC/TOML at all (or not a table) is returned as a hard error.
+fn parse_config_strict(
+    raw: &str,
+    issues: &mut Vec<String>,
+) -> std::result::Result<ShredsUdpConfigFile, String> {
+    let value: serde_json::Value = serde_jsonc::from_str(raw).or_else(|jsonc_err| {
+        toml::from_str(raw).map_err(|_| format!("not valid JSONC or TOML: {}", jsonc_err))
+    })?;
+    let serde_json::Value::Object(map) = value else {
+        return Err("top level must be an object".to_string());
+    };
+
+    let mut accepted = serde_json::Map::new();
+    for (key, value) in map {
+        if !CONFIG_FILE_KEYS.contains(&key.as_str()) {
+            issues.push(format!("unknown field `{}`", key));
+            continue;
+        }
+        if key == "sinks" {
+            for (index, sink) in value.as_array().into_iter().flatten().enumerate() {
+                for field in WatchSinkConfig::unknown_fields(sink) {
+                    issues.push(format!("sinks[{}]: unknown field `{}`", index, field));
+                }
+            }
+        }
+        let single =
+            serde_json::Value::Object([(key.clone(), value.clone())].into_iter().collect());
+        match serde_json::from_value::<ShredsUdpConfigFile>(single) {
+            Ok(_) => {
+                accepted.insert(key, value);
+            }
+            Err(e) => issues.push(format!("{}: {}", key, e)),
+        }
+    }
+    let file: ShredsUdpConfigFile =
+        serde_json::from_value(serde_json::Value::Object(accepted)).map_err(|e| e.to_string())?;
+
+    invalid_pubkeys(
+        "watch_program_ids",
+        file.watch_program_ids.as_deref(),
+        issues,
+    );
+    invalid_pubkeys(
+        "watch_authorities",
+        file.watch_authorities.as_deref(),
+        issues,
+    );
+    invalid_pubkeys(
+        "token_program_ids",
+        file.token_program_ids.as_deref(),
+        issues,
+    );
+    invalid_pubkeys(
+        "jito_tip_accounts",
+        file.jito_tip_accounts.as_deref(),
+        issues,
+    );
+    invalid_pubkeys(
+        "watch_token_mints",
+        file.watch_token_mints.as_deref(),
+        issues,
+    );
+    invalid_pubkeys("watch_wallets", file.watch_wallets.as_deref(), issues);
+    Ok(file)
+}
+
+/// Collect every entry in a comma-separated pubkey list that does not parse as base58.
+fn invalid_pubkeys(key: &str, raw: Option<&str>, invalid: &mut Vec<String>) {
+    for part in raw.unwrap_or_default().split(',') {
+        let trimmed = part.trim();
+        if !trimmed.is_empty() && Pubkey::from_str(trimmed).is_err() {
+            invalid.push(format!("{}: invalid pubkey {:?}", key, trimmed));
         }
     }
+}
 
-    if let Ok(raw) = env::var(legacy_var) {
-        let parsed = parse_pubkeys(Some(raw.as_str()), defaults);
-        if !parsed.is_empty() {
-            return parsed;
+/// Env overrides that are set but would be ignored by [`apply_env_overrides`].
+fn env_issues() -> Vec<String> {
+    let mut issues = Vec::new();
+    for name in ENV_BOOL_VARS {
+        if let Ok(raw) = env::var(name) {
+            if env_bool_opt(name).is_none() {
+                issues.push(format!(
+                    "{}: expected true/false/1/0/yes/no/on/off, got {:?}",
+                    name, raw
+                ));
+            }
         }
     }
+    for name in ENV_U64_VARS {
+        if let Ok(raw) = env::var(name) {
+            if raw.parse::<u64>().is_err() {
+                issues.push(format!(
+                    "{}: expected an unsigned integer, got {:?}",
+                    name, raw
+                ));
+            }
+        }
+    }
+    for name in ENV_U16_VARS {
+        if let Ok(raw) = env::var(name) {
+            if raw.parse::<u16>().is_err() {
+                issues.push(format!(
+                    "{}: expected an integer 0-65535, got {:?}",
+                    name, raw
+                ));
+            }
+        }
+    }
+    for name in ENV_PUBKEY_VARS {
+        if let Ok(raw) = env::var(name) {
+            invalid_pubkeys(name, Some(raw.as_str()), &mut issues);
+        }
+    }
+    issues
+}
 
-    parse_pubkeys(None, defaults)
+fn config_error(source: &str, issues: Vec<String>) -> Result<()> {
+    if issues.is_empty() {
+        return Ok(());
+    }
+    Err(SolanaStreamError::Configuration(format!(
+        "{}: {} problem(s):\n  - {}",
+        source,
+        issues.len(),
+        issues.join("\n  - ")
+    )))
+}
+
+/// `ip:port` or `host:port` with a numeric port; hostnames are resolved at bind time.
+fn is_host_port(raw: &str) -> bool {
+    if raw.parse::<std::net::SocketAddr>().is_ok() {
+        return true;
+    }
+    match raw.rsplit_once(':') {
+        Some((host, port)) => {
+            !host.is_empty() && !host.contains(':') && port.parse::<u16>().is_ok()
+        }
+        None => false,
+    }
 }
 
 fn apply_env_overrides(mut cfg: ShredsUdpConfig) -> ShredsUdpConfig {
@@ -917,38 +1542,6 @@ fn apply_env_overrides(mut cfg: ShredsUdpConfig) -> ShredsUdpConfig {
     let skip_vote_sigs = env_bool_opt("SHREDS_UDP_SKIP_VOTE_SIGS");
     let log_watch_hits = env_bool_opt("SHREDS_UDP_LOG_WATCH_HITS");
     let log_deferred = env_bool_opt("SHREDS_UDP_LOG_DEFER");
-    let watch_program_ids = {
-        let primary = parse_watch_list(
-            "SHREDS_UDP_WATCH_PROGRAM_IDS",
-            "SHREDS_UDP_PUMPFUN_PROGRAM_IDS",
-            &[],
-        );
-        if primary.is_empty() {
-            parse_watch_list(
-                "SHREDS_UDP_WATCH_PROGRAM_IDS",
-                "SHREDS_UDP_PUMPFUN_PROGRAM_IDS",
-                &[DEFAULT_WATCH_PROGRAM_ID],
-            )
-        } else {
-            primary
-        }
-    };
-    let watch_authorities = {
-        let primary = parse_watch_list(
-            "SHREDS_UDP_WATCH_AUTHORITIES",
-            "SHREDS_UDP_PUMPFUN_AUTHORITIES",
-            &[],
-        );
-        if primary.is_empty() {
-            parse_watch_list(
-                "SHREDS_UDP_WATCH_AUTHORITIES",
-                "SHREDS_UDP_PUMPFUN_AUTHORITIES",
-                &[DEFAULT_WATCH_AUTHORITY],
-            )
-        } else {
-            primary
-        }
-    };
     let strict_fec = env_bool_opt("SHREDS_UDP_STRICT_FEC").unwrap_or(cfg.strict_fec);
     let strict_num_data = env_parse_u16("SHREDS_UDP_STRICT_NUM_DATA").unwrap_or(cfg.strict_num_data);
     let strict_num_coding =
@@ -995,8 +1588,6 @@ fn apply_env_overrides(mut cfg: ShredsUdpConfig) -> ShredsUdpConfig {
         if !list.is_empty() {
             cfg.watch_program_ids = list;
         }
-    } else {
-        cfg.watch_program_ids = watch_program_ids;
     }
     if let Some(list) = ShredsUdpConfig::env_override_pubkeys(
         "SHREDS_UDP_WATCH_AUTHORITIES",
@@ -1005,10 +1596,7 @@ fn apply_env_overrides(mut cfg: ShredsUdpConfig) -> ShredsUdpConfig {
         if !list.is_empty() {
             cfg.watch_authorities = list;
         }
-    } else {
-        cfg.watch_authorities = watch_authorities;
     }
-    cfg.token_program_ids = default_token_program_ids();
     if let Some(ms) = env_parse_u64("SHREDS_UDP_COMPLETED_TTL_MS") {
         cfg.completed_ttl = Duration::from_millis(ms);
     }
@@ -1121,7 +1709,6 @@ pub async fn handle_pumpfun_watcher(
     state: &ShredsUdpState,
     cfg: &ShredsUdpConfig,
     policy: DeshredPolicy,
-    watch_cfg: Arc<ProgramWatchConfig>,
 ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let datagram = receiver.recv_raw().await?;
     let payload_len = datagram.payload.len();
@@ -1164,7 +1751,7 @@ pub async fn handle_pumpfun_watcher(
                         );
                     }
                 }
-                process_ready_batch(ready, state, cfg, watch_cfg.clone()).await;
+                process_ready_batch(ready, state, cfg).await;
             }
             ShredInsertOutcome::Deferred {
                 key,
@@ -1367,7 +1954,6 @@ async fn process_ready_batch(
     ready: ShredReadyBatch,
     state: &ShredsUdpState,
     cfg: &ShredsUdpConfig,
-    watch_cfg: Arc<ProgramWatchConfig>,
 ) {
     let metrics = state.metrics();
     let ShredReadyBatch {
@@ -1399,13 +1985,23 @@ async fn process_ready_batch(
                 txs.len()
             );
 
-            log_watch_events(
-                key.slot,
-                &txs,
-                watch_cfg.as_ref(),
-                cfg.log_watch_hits,
-                cfg.pump_min_lamports,
-            );
+            // Snapshot once per batch so a concurrent reload never splits a batch.
+            let watch = state.watch_settings().load();
+            let watch_cfg = watch.watch_cfg.as_ref();
+            if watch.log_watch_hits || !state.sinks().is_empty() {
+                let events =
+                    collect_watch_events(key.slot, &txs, watch_cfg, watch.pump_min_lamports);
+                if watch.log_watch_hits {
+                    log_collected_watch_events(key.slot, &events, watch.pump_min_lamports);
+                }
+                state.sinks().dispatch(&events);
+            }
+            state
+                .record_slot_tips(
+                    SlotTips::from_entries(key.slot, &entries, &watch_cfg.jito_tip_accounts),
+                    watch.log_watch_hits,
+                )
+                .await;
 
             if cfg.log_entries {
                 let sigs: Vec<String> = first_signatures(
@@ -1422,6 +2018,8 @@ async fn process_ready_batch(
                 );
             }
 
+            state.publish_entries(key.slot, entries);
+
             for segment_key in &segment_keys {
                 state.remove_batch(segment_key).await;
             }
@@ -1769,10 +2367,12 @@ fn summarize_coding_headers(code_shreds: &HashMap<u32, Shred>) -> CodingHeaderSu
     summary
 }
 
-fn detail_action_priority(action: Option<&str>) -> u8 {
+fn detail_action_priority(action: Option<WatchAction>) -> u8 {
     match action {
-        Some("buy") | Some("sell") => 2,
-        Some("create") => 1,
+        Some(WatchAction::Buy) | Some(WatchAction::Sell) => 2,
+        Some(WatchAction::Create) | Some(WatchAction::CreatePool) => 1,
+        // Authority changes outrank the mint-to/transfer seen in the same transaction.
+        Some(WatchAction::SetAuthority) => 1,
         _ => 0,
     }
 }
@@ -1780,35 +2380,35 @@ fn detail_action_priority(action: Option<&str>) -> u8 {
 fn merge_mint_detail(current: &mut MintDetail, incoming: &MintDetail) {
     let current_priority = detail_action_priority(current.action);
     let incoming_priority = detail_action_priority(incoming.action);
-    let current_is_create = matches!(current.action, Some("create"));
-    let incoming_is_trade = matches!(incoming.action, Some("buy") | Some("sell"));
-    let incoming_is_create = matches!(incoming.action, Some("create"));
+    let current_is_create = current.action == Some(WatchAction::Create);
+    let incoming_is_trade = incoming.action.is_some_and(|a| a.is_trade());
+    let incoming_is_create = incoming.action == Some(WatchAction::Create);
 
+    if current.pool.is_none() {
+        current.pool = incoming.pool.clone();
+    }
+    if current.swap.is_none() {
+        current.swap = incoming.swap.clone();
+    }
+    if current.route.is_none() {
+        current.route = incoming.route.clone();
+    }
     if incoming_is_create {
         // Prefer create over later trade classification; keep existing amounts.
-        current.action = Some("create");
-        if let Some(label) = incoming.label {
-            current.label = Some(label);
+        current.action = Some(WatchAction::Create);
+        if incoming.protocol.is_some() {
+            current.protocol = incoming.protocol.clone();
         }
-        // Keep amounts/names if already present; otherwise let the generic merge below fill them.
+        // Keep amounts/metadata if already present; otherwise fill them from the create.
         if current.sol_amount.is_none() {
             current.sol_amount = incoming.sol_amount;
         }
         if current.token_amount.is_none() {
             current.token_amount = incoming.token_amount;
         }
-        if current.name.is_none() {
-            current.name = incoming.name.clone();
-        }
-        if current.symbol.is_none() {
-            current.symbol = incoming.symbol.clone();
-        }
-        if current.uri.is_none() {
-            current.uri = incoming.uri.clone();
-        }
+        current.metadata.fill_missing(&incoming.metadata);
         return;
     } else if current_is_create && incoming_is_trade {
-
         // Keep create, but backfill amounts from the trade.
         if current.sol_amount.is_none() {
             current.sol_amount = incoming.sol_amount;
@@ -1823,10 +2423,10 @@ fn merge_mint_detail(current: &mut MintDetail, incoming: &MintDetail) {
                 current.action = Some(action);
             }
         }
-        if let Some(label) = incoming.label {
-            if current.label.is_none() || incoming_priority > current_priority {
-                current.label = Some(label);
-            }
+        if incoming.protocol.is_some()
+            && (current.protocol.is_none() || incoming_priority > current_priority)
+        {
+            current.protocol = incoming.protocol.clone();
         }
         if let Some(sol) = incoming.sol_amount {
             if current.sol_amount.is_none() || incoming_priority >= current_priority {
@@ -1840,30 +2440,30 @@ fn merge_mint_detail(current: &mut MintDetail, incoming: &MintDetail) {
         }
     }
 
-    if current.name.is_none() {
-        current.name = incoming.name.clone();
-    }
-    if current.symbol.is_none() {
-        current.symbol = incoming.symbol.clone();
-    }
-    if current.uri.is_none() {
-        current.uri = incoming.uri.clone();
-    }
+    current.metadata.fill_missing(&incoming.metadata);
 }
 
 fn filter_pump_details(details: &mut Vec<MintDetail>, pump_min_lamports: u64) {
-    details.retain(|d| matches!(d.action, Some("buy") | Some("sell") | Some("create")));
+    details.retain(|d| {
+        matches!(
+            d.action,
+            Some(WatchAction::Buy)
+                | Some(WatchAction::Sell)
+                | Some(WatchAction::Create)
+                | Some(WatchAction::CreatePool)
+        )
+    });
     if pump_min_lamports == 0 {
         return;
     }
     details.retain(|d| match d.action {
-        Some("buy") | Some("sell") => d
-            .sol_amount
+        Some(WatchAction::Buy) | Some(WatchAction::Sell) => d
+            .lamports()
             .map(|amt| amt >= pump_min_lamports)
             .unwrap_or(false),
         // If create already carries the initial buy amounts (create/buy), apply the threshold too.
-        Some("create") => d
-            .sol_amount
+        Some(WatchAction::Create) | Some(WatchAction::CreatePool) => d
+            .lamports()
             .map(|amt| amt >= pump_min_lamports)
             .unwrap_or(true),
         _ => false,
@@ -1877,58 +2477,70 @@ pub fn collect_watch_events(
     pump_min_lamports: u64,
 ) -> Vec<WatchEvent> {
     let _ = pump_min_lamports;
-    let mut events = Vec::new();
-    for tx in txs {
-        if let Some(hit) = detect_program_hit(tx, watch_cfg) {
-            let mut detail_map: BTreeMap<Pubkey, MintDetail> = hit
-                .mints
-                .iter()
-                .map(|m| {
-                    let action = match m.label {
-                        Some("pump:create") => Some("create"),
-                        Some("pump:buy") => Some("buy"),
-                        Some("pump:buy_exact") => Some("buy"),
-                        Some("pump:sell") => Some("sell"),
-                        Some("pump:trade") => Some("trade"),
-                        _ => None,
-                    };
-                    (
-                        m.mint,
-                        MintDetail {
-                            mint: m.mint,
-                            label: m.label,
-                            action,
-                            token_amount: None,
-                            sol_amount: None,
-                            name: None,
-                            symbol: None,
-                            uri: None,
-                        },
-                    )
-                })
-                .collect();
-            for d in &watch_cfg.detailers {
-                for det in d.detail(tx, watch_cfg, &hit.mints) {
-                    detail_map
-                        .entry(det.mint)
-                        .and_modify(|curr| merge_mint_detail(curr, &det))
-                        .or_insert(det);
-                }
-            }
-            if detail_map.is_empty() {
-                continue;
-            }
-            let mut details: Vec<MintDetail> = detail_map.values().cloned().collect();
-            details.sort_by(|a, b| a.mint.cmp(&b.mint));
-            details.dedup_by(|a, b| a.mint == b.mint);
-            events.push(WatchEvent {
-                slot,
-                hit,
-                details,
-            });
+    txs.iter()
+        .filter_map(|tx| {
+            collect_watch_event(slot, tx, tx.message.static_account_keys(), None, watch_cfg)
+        })
+        .collect()
+}
+
+/// Detect and detail one transaction.
+///
+/// `account_keys` are the transaction's keys in runtime order: its static keys, then any
+/// addresses loaded from lookup tables (see
+/// [`account_keys_from_geyser`](crate::geyser::account_keys_from_geyser)). With `meta`,
+/// CPI-invoked instructions are detected too, buy/sell details carry the amounts the fee payer
+/// actually moved (see [`TxOutcome::apply_executed_amounts`]) and the event's `outcome` marks
+/// failed transactions.
+pub fn collect_watch_event(
+    slot: u64,
+    tx: &VersionedTransaction,
+    account_keys: &[Pubkey],
+    meta: Option<&TxMeta>,
+    watch_cfg: &ProgramWatchConfig,
+) -> Option<WatchEvent> {
+    let resolved = with_resolved_keys(tx, account_keys);
+    let resolved = resolved.as_ref().unwrap_or(tx);
+    let flattened = meta.and_then(|meta| meta.with_inner_instructions(resolved));
+    let executed = flattened.as_ref().unwrap_or(resolved);
+    let hit = detect_program_hit(executed, watch_cfg)?;
+    let mut detail_map: BTreeMap<Pubkey, MintDetail> = hit
+        .mints
+        .iter()
+        .map(|m| (m.mint, MintDetail::from_info(m)))
+        .collect();
+    for d in &watch_cfg.detailers {
+        for det in d.detail_hit(executed, watch_cfg, &hit, meta) {
+            detail_map
+                .entry(det.mint)
+                .and_modify(|curr| merge_mint_detail(curr, &det))
+                .or_insert(det);
         }
     }
-    events
+    let instructions: Vec<InstructionEvent> = watch_cfg
+        .instruction_decoders
+        .iter()
+        .flat_map(|d| d.decode_hit(executed, &hit))
+        .collect();
+    if detail_map.is_empty() && instructions.is_empty() {
+        return None;
+    }
+    let mut details: Vec<MintDetail> = detail_map.values().cloned().collect();
+    details.sort_by(|a, b| a.mint.cmp(&b.mint));
+    details.dedup_by(|a, b| a.mint == b.mint);
+    let outcome = meta.map(|meta| meta.outcome(executed, account_keys));
+    if let (Some(outcome), Some(fee_payer)) = (&outcome, hit.signers.first()) {
+        outcome.apply_executed_amounts(&mut details, fee_payer, &watch_cfg.jito_tip_accounts);
+    }
+    Some(WatchEvent {
+        slot,
+        hit,
+        details,
+        compute_budget: ComputeBudget::from_transaction(tx),
+        jito_tip_lamports: tip_lamports(resolved, &watch_cfg.jito_tip_accounts),
+        instructions,
+        outcome,
+    })
 }
 
 pub fn log_watch_events(
@@ -1941,31 +2553,63 @@ pub fn log_watch_events(
     if !log_watch_hits {
         return;
     }
+    log_collected_watch_events(
+        slot,
+        &collect_watch_events(slot, txs, watch_cfg, pump_min_lamports),
+        pump_min_lamports,
+    );
+}
+
+/// Log events already produced by [`collect_watch_events`], so a batch that also feeds the
+/// sinks is only detected once.
+pub fn log_collected_watch_events(slot: u64, events: &[WatchEvent], pump_min_lamports: u64) {
     fn mint_priority(detail: &MintDetail) -> u8 {
-        if let Some(action) = detail.action {
-            if action == "create" {
-                return 0;
-            }
-            if action == "trade" {
-                return 1;
-            }
-        }
-        if let Some(label) = detail.label {
-            if label.starts_with("pump:") {
-                return 2;
-            }
+        match detail.action {
+            Some(WatchAction::Create) | Some(WatchAction::CreatePool) => return 0,
+            Some(WatchAction::Trade) => return 1,
+            _ => {}
+        }
+        if matches!(
+            detail.protocol,
+            Some(Protocol::Pump)
+                | Some(Protocol::PumpSwap)
+                | Some(Protocol::RaydiumAmm)
+                | Some(Protocol::RaydiumCpmm)
+                | Some(Protocol::RaydiumClmm)
+                | Some(Protocol::MeteoraDlmm)
+                | Some(Protocol::MeteoraDammV1)
+                | Some(Protocol::MeteoraDammV2)
+                | Some(Protocol::OrcaWhirlpool)
+                | Some(Protocol::Jupiter)
+        ) {
+            return 2;
         }
         10
     }
 
-    for event in collect_watch_events(slot, txs, watch_cfg, pump_min_lamports) {
+    for event in events {
         let prefix = match (event.hit.program_hit, event.hit.authority_hit) {
             (true, true) => "🎯🐣",
             (true, false) => "🎯",
             (false, true) => "🐣",
+            _ if !event.hit.wallets.is_empty() => "👛",
             _ => "👀",
         };
-        let mut details = event.details;
+        for ix in &event.instructions {
+            info!(
+                "📜 {}\n  slot: {}\n  sig: {}\n  program: {} ({})\n  ix: {}\n  args: {}",
+                prefix,
+                slot,
+                event.hit.signature,
+                ix.protocol,
+                ix.program_id,
+                ix.instruction,
+                ix.error
+                    .as_deref()
+                    .map_or_else(|| ix.args.to_string(), |e| format!("<{}>", e))
+            );
+        }
+        let mut details = event.details.clone();
         filter_pump_details(&mut details, pump_min_lamports);
         details.sort_by(|a, b| {
             mint_priority(a)
@@ -1977,8 +2621,12 @@ pub fn log_watch_events(
             continue;
         }
         if let Some(primary) = details.first() {
-            let is_create = primary.action == Some("create") || primary.label == Some("pump:create");
-            let base_kind = primary.action.or(primary.label).unwrap_or("unknown");
+            let is_create = primary.action == Some(WatchAction::Create);
+            let base_kind = primary
+                .action
+                .map(|a| a.as_str())
+                .or(primary.protocol.as_ref().map(|p| p.as_str()))
+                .unwrap_or("unknown");
             let kind =
                 if is_create && (primary.sol_amount.is_some() || primary.token_amount.is_some()) {
                     "create/buy"
@@ -1988,26 +2636,12 @@ pub fn log_watch_events(
                     base_kind
                 };
             let missing_amounts = primary.sol_amount.is_none() && primary.token_amount.is_none();
-            // Pump.fun instruction data includes SOL limits (max for buy/create, min for sell).
-            // Exact-SOL buys use the precise input amount.
-            enum SolLimit {
-                Max,
-                Min,
-            }
-            let is_pump = primary
-                .label
-                .map(|l| l.starts_with("pump:"))
-                .unwrap_or(false);
-            let is_buy_exact = primary.label == Some("pump:buy_exact");
-            let sol_limit = if is_pump && primary.sol_amount.is_some() {
-                match primary.action {
-                    Some("buy") if !is_buy_exact => Some(SolLimit::Max),
-                    Some("create") => Some(SolLimit::Max),
-                    Some("sell") => Some(SolLimit::Min),
-                    _ => None,
-                }
-            } else {
-                None
+            // Pump.fun instruction data includes SOL limits (max for buy/create, min for sell);
+            // exact-SOL buys carry the precise input amount.
+            let limit_suffix = |bound: AmountBound| match bound {
+                AmountBound::Max => " (max)",
+                AmountBound::Min => " (min)",
+                AmountBound::Exact => "",
             };
             let icon = if missing_amounts {
                 "❓"
@@ -2015,36 +2649,45 @@ pub fn log_watch_events(
                 "🐣"
             } else {
                 match primary.action {
-                    Some("buy") => "🟢",
-                    Some("sell") => "🔻",
+                    Some(WatchAction::CreatePool) => "🏊",
+                    Some(WatchAction::Buy) => "🟢",
+                    Some(WatchAction::Sell) => "🔻",
                     _ => "🪙",
                 }
             };
             let lamports_display = primary
                 .sol_amount
-                .map(|l| match sol_limit {
-                    Some(SolLimit::Max) => format!("{} (max)", l),
-                    Some(SolLimit::Min) => format!("{} (min)", l),
-                    None => l.to_string(),
-                })
+                .map(|a| format!("{}{}", a.value, limit_suffix(a.bound)))
                 .unwrap_or_else(|| "-".to_string());
             let sol_display = primary
                 .sol_amount
-                .map(|lamports| {
-                    let base = format!("{:.9}", lamports as f64 / 1_000_000_000_f64);
-                    match sol_limit {
-                        Some(SolLimit::Max) => format!("{} (max)", base),
-                        Some(SolLimit::Min) => format!("{} (min)", base),
-                        None => base,
-                    }
+                .and_then(|a| {
+                    a.as_sol()
+                        .map(|sol| format!("{:.9}{}", sol, limit_suffix(a.bound)))
                 })
                 .unwrap_or_else(|| "-".to_string());
             let token_amount_display = primary
                 .token_amount
-                .map(|t| t.to_string())
+                .map(|t| t.value.to_string())
                 .unwrap_or_else(|| "-".to_string());
+            let metadata = &primary.metadata;
+            let metadata_display = if metadata.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "\n  name: {} ({})\n  uri: {}",
+                    metadata.name.as_deref().unwrap_or("-"),
+                    metadata.symbol.as_deref().unwrap_or("-"),
+                    metadata.uri.as_deref().unwrap_or("-")
+                )
+            };
+            let wallet_display = if event.hit.wallets.is_empty() {
+                String::new()
+            } else {
+                format!("\n  wallet: {}", fmt_pubkeys(&event.hit.wallets).join(","))
+            };
             info!(
-                "{} {}\n  slot: {}\n  sig: {}\n  mint: {}\n  kind: {}\n  lamports: {}\n  sol: {}\n  token_amount: {}",
+                "{} {}\n  slot: {}\n  sig: {}\n  mint: {}\n  kind: {}\n  lamports: {}\n  sol: {}\n  token_amount: {}{}{}",
                 icon,
                 prefix,
                 slot,
@@ -2053,7 +2696,9 @@ pub fn log_watch_events(
                 kind,
                 lamports_display,
                 sol_display,
-                token_amount_display
+                token_amount_display,
+                metadata_display,
+                wallet_display
             );
         } else {
             let mint = event
@@ -2469,37 +3114,32 @@ mod tests {
 
     fn make_detail(
         mint: Pubkey,
-        action: Option<&'static str>,
-        label: Option<&'static str>,
+        action: Option<WatchAction>,
         sol_amount: Option<u64>,
     ) -> MintDetail {
         MintDetail {
-            mint,
-            label,
+            protocol: Some(Protocol::Pump),
             action,
-            sol_amount,
-            token_amount: None,
-            name: None,
-            symbol: None,
-            uri: None,
+            sol_amount: sol_amount.map(crate::txn::Amount::lamports),
+            ..MintDetail::new(mint)
         }
     }
 
     #[test]
     fn merge_keeps_create_and_backfills_buy_amounts() {
         let mint = Pubkey::new_from_array([1u8; 32]);
-        let mut current = make_detail(mint, Some("create"), Some("pump:create"), None);
+        let mut current = make_detail(mint, Some(WatchAction::Create), None);
         let incoming = MintDetail {
-            token_amount: Some(42),
-            ..make_detail(mint, Some("buy"), Some("pump:buy"), Some(200))
+            token_amount: Some(crate::txn::Amount::token(42)),
+            ..make_detail(mint, Some(WatchAction::Buy), Some(200))
         };
 
         merge_mint_detail(&mut current, &incoming);
 
-        assert_eq!(current.action, Some("create"));
-        assert_eq!(current.label, Some("pump:create"));
-        assert_eq!(current.sol_amount, Some(200));
-        assert_eq!(current.token_amount, Some(42));
+        assert_eq!(current.action, Some(WatchAction::Create));
+        assert_eq!(current.protocol, Some(Protocol::Pump));
+        assert_eq!(current.lamports(), Some(200));
+        assert_eq!(current.token_amount.map(|a| a.value), Some(42));
     }
 
     #[test]
@@ -2510,20 +3150,181 @@ mod tests {
         let mint_trade = Pubkey::new_from_array([5u8; 32]);
 
         let mut details = vec![
-            make_detail(mint_buy_small, Some("buy"), Some("pump:buy"), Some(50)),
-            make_detail(mint_buy_large, Some("buy"), Some("pump:buy"), Some(200)),
-            make_detail(mint_create, Some("create"), Some("pump:create"), None),
-            make_detail(mint_trade, Some("trade"), Some("pump:trade"), None),
+            make_detail(mint_buy_small, Some(WatchAction::Buy), Some(50)),
+            make_detail(mint_buy_large, Some(WatchAction::Buy), Some(200)),
+            make_detail(mint_create, Some(WatchAction::Create), None),
+            make_detail(mint_trade, Some(WatchAction::Trade), None),
         ];
 
         filter_pump_details(&mut details, 100);
 
-        assert!(details
-            .iter()
-            .all(|d| matches!(d.action, Some("buy") | Some("sell") | Some("create"))));
+        assert!(details.iter().all(|d| matches!(
+            d.action,
+            Some(WatchAction::Buy) | Some(WatchAction::Sell) | Some(WatchAction::Create)
+        )));
         assert!(details.iter().any(|d| d.mint == mint_create));
         assert!(details.iter().any(|d| d.mint == mint_buy_large));
         assert!(!details.iter().any(|d| d.mint == mint_buy_small));
         assert!(!details.iter().any(|d| d.mint == mint_trade));
     }
+
+    #[test]
+    fn watch_event_json_is_flat_and_stable() {
+        let mint = Pubkey::new_from_array([7u8; 32]);
+        let event = WatchEvent {
+            slot: 42,
+            hit: ProgramHit {
+                signature: solana_sdk::signature::Signature::default(),
+                program_hit: true,
+                authority_hit: false,
+                mint_hit: false,
+                signers: Vec::new(),
+                wallets: Vec::new(),
+                mints: Vec::new(),
+                token_instructions: None,
+            },
+            details: vec![MintDetail {
+                sol_amount: Some(crate::txn::Amount::lamports(5).with_bound(AmountBound::Max)),
+                ..make_detail(mint, Some(WatchAction::Buy), None)
+            }],
+            compute_budget: ComputeBudget::default(),
+            jito_tip_lamports: 0,
+            instructions: Vec::new(),
+            outcome: None,
+        };
+
+        let json = serde_json::to_value(&event).expect("serialize");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "slot": 42,
+                "signature": solana_sdk::signature::Signature::default().to_string(),
+                "program_hit": true,
+                "authority_hit": false,
+                "mint_hit": false,
+                "signers": [],
+                "mints": [],
+                "details": [{
+                    "mint": mint.to_string(),
+                    "protocol": "pump",
+                    "action": "buy",
+                    "sol_amount": { "value": 5, "unit": "lamports", "bound": "max" },
+                    "token_amount": null,
+                    "metadata": { "name": null, "symbol": null, "uri": null }
+                }],
+                "compute_budget": {
+                    "unit_limit": null,
+                    "unit_price": null,
+                    "heap_frame_bytes": null,
+                    "priority_fee_lamports": 0
+                },
+                "jito_tip_lamports": 0
+            })
+        );
+        let back: WatchEvent = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(back.details, event.details);
+    }
+
+    #[test]
+    fn strict_config_reports_every_problem() {
+        let raw = r#"{
+            // typo'd key, wrong type, bad pubkey and out-of-range values together
+            "watch_program_id": "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P",
+            "log_raw": "yes",
+            "watch_authorities": "TSLvdd1pWpHVjahSpsvCXUbgwsL3JAcvokwaKt1eokM, not-a-key",
+            "strict_num_data": 0,
+            "bind_addr": "10001",
+            "rules": { "big": "sol_amount >= 5 SOLL" },
+            "idls": [{ "path": "/nonexistent/idl.json" }],
+            "heartbeat": { "endpoint": "http://127.0.0.1:1", "public_ip": "0.0.0.0", "regions": ["ny"] },
+            "proxy": { "bind_addr": "0.0.0.0:9999", "client_buffer": 0 },
+            "sinks": [{ "type": "stdout" }, { "type": "webhook", "url": "http://x", "min_lamport": 5 }]
+        }"#;
+
+        let Err(SolanaStreamError::Configuration(msg)) = ShredsUdpConfig::try_from_str(raw) else {
+            panic!("expected a configuration error");
+        };
+        assert!(msg.contains("10 problem(s)"), "{msg}");
+        assert!(msg.contains("unknown field `watch_program_id`"), "{msg}");
+        assert!(msg.contains("log_raw: invalid type"), "{msg}");
+        assert!(
+            msg.contains(r#"watch_authorities: invalid pubkey "not-a-key""#),
+            "{msg}"
+        );
+        assert!(msg.contains("strict_num_data: must be at least 1"), "{msg}");
+        assert!(msg.contains("bind_addr: expected ip:port"), "{msg}");
+        assert!(
+            msg.contains("rules: Configuration error: rule big: unexpected `SOLL` at column 17"),
+            "{msg}"
+        );
+        assert!(
+            msg.contains("idls[0]: Configuration error: /nonexistent/idl.json"),
+            "{msg}"
+        );
+        assert!(
+            msg.contains("heartbeat.public_ip: 0.0.0.0 is not routable"),
+            "{msg}"
+        );
+        assert!(
+            msg.contains("proxy.client_buffer: must be at least 1"),
+            "{msg}"
+        );
+        assert!(
+            msg.contains("sinks[1]: unknown field `min_lamport`"),
+            "{msg}"
+        );
+    }
+
+    #[test]
+    fn env_overrides_keep_file_watch_lists_when_unset() {
+        // Assumes the SHREDS_UDP_WATCH_* / SHREDS_UDP_PUMPFUN_* vars are unset in the test env.
+        let program = Pubkey::new_from_array([1u8; 32]);
+        let authority = Pubkey::new_from_array([2u8; 32]);
+        let token_2022 = default_token_program_ids()[1];
+        let raw = format!(
+            r#"{{
+                "watch_program_ids": "{program}",
+                "watch_authorities": "{authority}",
+                "token_program_ids": "{token_2022}"
+            }}"#
+        );
+
+        let cfg = ShredsUdpConfig::try_from_embedded(&raw).expect("valid settings");
+        assert_eq!(cfg.watch_program_ids, vec![program]);
+        assert_eq!(cfg.watch_authorities, vec![authority]);
+        assert_eq!(cfg.token_program_ids, vec![token_2022]);
+
+        let watch = cfg.watch_config();
+        assert_eq!(watch.program_ids(), &[program]);
+        assert_eq!(watch.token_program_ids, vec![token_2022]);
+    }
+
+    #[test]
+    fn strict_config_accepts_valid_jsonc_and_toml() {
+        let jsonc = r#"{
+            "bind_addr": "0.0.0.0:10001",
+            "watch_program_ids": "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P",
+            "slot_window_root": null,
+            "pump_min_lamports": 1000000000,
+            "sinks": [{ "type": "stdout", "actions": ["create"], "rules": ["whales"] }],
+            "rules": { "whales": "action == \"buy\" && signer in @whales" },
+            "lists": { "whales": ["TSLvdd1pWpHVjahSpsvCXUbgwsL3JAcvokwaKt1eokM"] }
+        }"#;
+        let cfg = ShredsUdpConfig::try_from_str(jsonc).expect("valid jsonc");
+        assert_eq!(cfg.watch_program_ids.len(), 1);
+        assert_eq!(cfg.pump_min_lamports, 1_000_000_000);
+        assert_eq!(cfg.sinks.len(), 1);
+        assert_eq!(cfg.watch_rules().expect("rules").len(), 1);
+        let mut unknown = cfg.clone();
+        unknown.sinks[0].filter.rules = vec!["sharks".to_string()];
+        assert!(unknown
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains(r#"sinks[0]: Configuration error: sink rules: unknown rule "sharks""#));
+
+        let toml = "bind_addr = \"127.0.0.1:9000\"\nstrict_num_coding = 16\n";
+        let cfg = ShredsUdpConfig::try_from_str(toml).expect("valid toml");
+        assert_eq!(cfg.strict_num_coding, 16);
+    }
 }
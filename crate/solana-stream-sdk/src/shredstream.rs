@@ -1,21 +1,67 @@
 //! Shredstream client wrapper
 
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::RandomState, HashMap, VecDeque},
+    hash::BuildHasher,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
 
-use tonic::transport::Channel;
+use futures::{
+    stream::{self, BoxStream},
+    Stream, StreamExt,
+};
+use solana_entry::entry::Entry;
+use tonic::{
+    codec::CompressionEncoding,
+    metadata::{AsciiMetadataKey, AsciiMetadataValue},
+    service::{interceptor::InterceptedService, Interceptor},
+    transport::{Channel, ClientTlsConfig, Endpoint},
+};
 
 use crate::{
+    shreds_udp::{collect_watch_events, decode_entries, WatchEvent},
     shredstream_proto::{
         shredstream_proxy_client::ShredstreamProxyClient, CommitmentLevel, SubscribeEntriesRequest,
-        SubscribeRequestFilterAccounts, SubscribeRequestFilterSlots,
-        SubscribeRequestFilterTransactions,
+        SubscribeRequestFilterAccounts,
     },
+    txn::ProgramWatchConfig,
     Result, SolanaStreamError,
 };
 
+/// Decoded entry batches as `(slot, entries)`.
+pub type DecodedEntryStream = BoxStream<'static, Result<(u64, Vec<Entry>)>>;
+
+/// Decoded entry batches with the watch events of their transactions.
+pub type WatchedEntryStream = BoxStream<'static, Result<(u64, Vec<Entry>, Vec<WatchEvent>)>>;
+
+/// Decode one Shredstream entry message into its slot and entries.
+///
+/// Payloads are tried as wincode (Agave 3.x) and then bincode, like
+/// [`deshred_shreds_to_entries`](crate::deshred_shreds_to_entries); failures are reported as
+/// [`SolanaStreamError::EntryDecode`].
+pub fn decode_slot_entry(entry: &crate::shredstream_proto::Entry) -> Result<(u64, Vec<Entry>)> {
+    decode_entries(&entry.entries)
+        .map(|entries| (entry.slot, entries))
+        .map_err(|message| SolanaStreamError::EntryDecode {
+            slot: entry.slot,
+            message,
+        })
+}
+
+/// Generated proxy client with the builder's headers attached to every request.
+type ProxyClient = ShredstreamProxyClient<InterceptedService<Channel, HeaderInterceptor>>;
+
 /// A convenient wrapper around the Shredstream client
 pub struct ShredstreamClient {
-    client: ShredstreamProxyClient<Channel>,
+    client: ProxyClient,
+    /// Connection options, reused when a reconnecting stream opens a new connection.
+    builder: ShredstreamClientBuilder,
 }
 
 impl ShredstreamClient {
@@ -35,11 +81,12 @@ impl ShredstreamClient {
     /// }
     /// ```
     pub async fn connect(endpoint: impl AsRef<str>) -> Result<Self> {
-        let client = ShredstreamProxyClient::connect(endpoint.as_ref().to_string())
-            .await
-            .map_err(SolanaStreamError::Transport)?;
+        Self::builder(endpoint).connect().await
+    }
 
-        Ok(Self { client })
+    /// Start configuring a connection to `endpoint`; see [`ShredstreamClientBuilder`].
+    pub fn builder(endpoint: impl AsRef<str>) -> ShredstreamClientBuilder {
+        ShredstreamClientBuilder::new(endpoint)
     }
 
     /// Subscribe to entries with the given filters
@@ -59,8 +106,110 @@ impl ShredstreamClient {
         Ok(response.into_inner())
     }
 
+    /// Subscribe to entries and decode each message into `(slot, entries)`.
+    ///
+    /// A payload that fails to decode yields [`SolanaStreamError::EntryDecode`] for its slot and
+    /// the stream carries on; a gRPC error yields [`SolanaStreamError::Status`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use solana_stream_sdk::ShredstreamClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut client = ShredstreamClient::connect("https://shreds-ams.erpc.global").await?;
+    ///     let request = ShredstreamClient::create_empty_entries_request();
+    ///     let mut stream = client.subscribe_decoded_entries(request).await?;
+    ///     while let Some(batch) = stream.next().await {
+    ///         let (slot, entries) = batch?;
+    ///         println!("slot {}: {} entries", slot, entries.len());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn subscribe_decoded_entries(
+        &mut self,
+        request: SubscribeEntriesRequest,
+    ) -> Result<DecodedEntryStream> {
+        let stream = self.subscribe_entries(request).await?;
+        Ok(stream.map(|entry| decode_slot_entry(&entry?)).boxed())
+    }
+
+    /// [`subscribe_decoded_entries`](Self::subscribe_decoded_entries) plus the watch events of
+    /// each batch's transactions, as the UDP pipeline would report them.
+    pub async fn subscribe_decoded_entries_with_watch(
+        &mut self,
+        request: SubscribeEntriesRequest,
+        watch_cfg: Arc<ProgramWatchConfig>,
+    ) -> Result<WatchedEntryStream> {
+        let stream = self.subscribe_decoded_entries(request).await?;
+        Ok(stream
+            .map(move |batch| {
+                let (slot, entries) = batch?;
+                let txs: Vec<_> = entries.iter().flat_map(|e| e.transactions.iter()).collect();
+                let events = collect_watch_events(slot, &txs, &watch_cfg, 0);
+                Ok((slot, entries, events))
+            })
+            .boxed())
+    }
+
+    /// Subscribe to entries and keep the subscription alive.
+    ///
+    /// When the stream fails or ends, the client reconnects to the same endpoint with `policy`'s
+    /// backoff and re-sends `request`. Entries and connection changes arrive on one stream as
+    /// [`ShredstreamEvent`]s; it ends only after [`ReconnectPolicy::max_attempts`] consecutive
+    /// failures. The first subscription reuses this client's connection.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use solana_stream_sdk::{ReconnectPolicy, ShredstreamClient, ShredstreamEvent};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let client = ShredstreamClient::connect("https://shreds-ams.erpc.global").await?;
+    ///     let request = ShredstreamClient::create_empty_entries_request();
+    ///     let mut stream = client.subscribe_entries_reconnecting(request, ReconnectPolicy::default());
+    ///     while let Some(event) = stream.next().await {
+    ///         match event {
+    ///             ShredstreamEvent::Entry(entry) => println!("slot {}", entry.slot),
+    ///             ShredstreamEvent::Connection(change) => println!("{:?}", change),
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn subscribe_entries_reconnecting(
+        &self,
+        request: SubscribeEntriesRequest,
+        policy: ReconnectPolicy,
+    ) -> ReconnectingEntryStream {
+        let last_slot = Arc::new(AtomicU64::new(0));
+        let state = Reconnect {
+            builder: self.builder.clone(),
+            client: Some(self.client.clone()),
+            request,
+            policy,
+            stream: None,
+            failures: 0,
+            retry_in: None,
+            check_gap: false,
+            done: false,
+            pending: VecDeque::new(),
+            last_slot: last_slot.clone(),
+        };
+        ReconnectingEntryStream {
+            inner: stream::unfold(state, Reconnect::next).boxed(),
+            last_slot,
+        }
+    }
+
     /// Create a simple entries subscription request with single account filter
     ///
+    /// The request holds only that accounts filter, named `"accounts"`; use
+    /// [`EntriesRequestBuilder`](crate::EntriesRequestBuilder) for named, validated filters.
+    ///
     /// # Arguments
     /// * `account` - The account address to filter for
     /// * `commitment` - The commitment level (optional, defaults to Processed)
@@ -86,7 +235,7 @@ impl ShredstreamClient {
     ) -> SubscribeEntriesRequest {
         let mut accounts = HashMap::new();
         accounts.insert(
-            "".to_owned(),
+            "accounts".to_owned(),
             SubscribeRequestFilterAccounts {
                 account: vec![account.as_ref().to_owned()],
                 owner: vec![],
@@ -95,34 +244,18 @@ impl ShredstreamClient {
             },
         );
 
-        let mut transactions = HashMap::new();
-        transactions.insert(
-            "".to_owned(),
-            SubscribeRequestFilterTransactions {
-                account_include: vec!["".to_owned()],
-                account_exclude: vec!["".to_owned()],
-                account_required: vec!["".to_owned()],
-            },
-        );
-
-        let mut slots = HashMap::new();
-        slots.insert(
-            "".to_owned(),
-            SubscribeRequestFilterSlots {
-                filter_by_commitment: Some(true),
-                interslot_updates: Some(false),
-            },
-        );
-
         SubscribeEntriesRequest {
             accounts,
-            transactions,
-            slots,
+            transactions: HashMap::new(),
+            slots: HashMap::new(),
             commitment: Some(commitment.unwrap_or(CommitmentLevel::Processed) as i32),
         }
     }
 
     /// Create entries subscription request with multiple accounts, owners, and filters
+    ///
+    /// Like [`create_entries_request_for_account`](Self::create_entries_request_for_account),
+    /// the request holds only the accounts filter, named `"accounts"`.
     pub fn create_entries_request_for_accounts(
         accounts: Vec<String>,
         owners: Vec<String>,
@@ -131,7 +264,7 @@ impl ShredstreamClient {
     ) -> SubscribeEntriesRequest {
         let mut account_filters = HashMap::new();
         account_filters.insert(
-            "".to_owned(),
+            "accounts".to_owned(),
             SubscribeRequestFilterAccounts {
                 account: accounts,
                 owner: owners,
@@ -140,29 +273,10 @@ impl ShredstreamClient {
             },
         );
 
-        let mut transactions = HashMap::new();
-        transactions.insert(
-            "".to_owned(),
-            SubscribeRequestFilterTransactions {
-                account_include: vec!["".to_owned()],
-                account_exclude: vec!["".to_owned()],
-                account_required: vec!["".to_owned()],
-            },
-        );
-
-        let mut slots = HashMap::new();
-        slots.insert(
-            "".to_owned(),
-            SubscribeRequestFilterSlots {
-                filter_by_commitment: Some(true),
-                interslot_updates: Some(false),
-            },
-        );
-
         SubscribeEntriesRequest {
             accounts: account_filters,
-            transactions,
-            slots,
+            transactions: HashMap::new(),
+            slots: HashMap::new(),
             commitment: Some(commitment.unwrap_or(CommitmentLevel::Processed) as i32),
         }
     }
@@ -177,3 +291,697 @@ impl ShredstreamClient {
         }
     }
 }
+
+/// Connection options for a [`ShredstreamClient`].
+///
+/// Anything left unset keeps tonic's default. `https` endpoints use the native and webpki
+/// roots unless [`with_tls_config`](Self::with_tls_config) is given. Headers are sent with
+/// every request, including the re-subscriptions of
+/// [`subscribe_entries_reconnecting`](ShredstreamClient::subscribe_entries_reconnecting).
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use solana_stream_sdk::{CompressionEncoding, ShredstreamClient};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = ShredstreamClient::builder("https://shreds-ams.erpc.global")
+///         .with_x_token(std::env::var("X_TOKEN")?)
+///         .with_connect_timeout(Duration::from_secs(5))
+///         .with_http2_keep_alive_interval(Duration::from_secs(10))
+///         .with_keep_alive_timeout(Duration::from_secs(5))
+///         .with_max_decoding_message_size(64 * 1024 * 1024)
+///         .with_accept_compressed(CompressionEncoding::Zstd)
+///         .connect()
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShredstreamClientBuilder {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    tls_config: Option<ClientTlsConfig>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    http2_keep_alive_interval: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    keep_alive_while_idle: Option<bool>,
+    max_decoding_message_size: Option<usize>,
+    max_encoding_message_size: Option<usize>,
+    send_compressed: Option<CompressionEncoding>,
+    accept_compressed: Vec<CompressionEncoding>,
+}
+
+impl ShredstreamClientBuilder {
+    pub fn new(endpoint: impl AsRef<str>) -> Self {
+        Self {
+            endpoint: endpoint.as_ref().to_string(),
+            headers: Vec::new(),
+            tls_config: None,
+            connect_timeout: None,
+            timeout: None,
+            http2_keep_alive_interval: None,
+            keep_alive_timeout: None,
+            keep_alive_while_idle: None,
+            max_decoding_message_size: None,
+            max_encoding_message_size: None,
+            send_compressed: None,
+            accept_compressed: Vec::new(),
+        }
+    }
+
+    /// Send `token` in the `x-token` header, as ERPC and Yellowstone endpoints expect.
+    pub fn with_x_token(self, token: impl Into<String>) -> Self {
+        self.with_header("x-token", token)
+    }
+
+    /// Send a header with every request, e.g. `authorization: Bearer <token>`. Names and
+    /// values must be ASCII; they are checked on connect.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// TLS settings, e.g. custom roots via [`ClientTlsConfig::ca_certificate`] or a client
+    /// identity.
+    pub fn with_tls_config(mut self, tls_config: ClientTlsConfig) -> Self {
+        self.tls_config = Some(tls_config);
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Deadline for each request to be answered; an open subscription is not cut off by it.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Interval of HTTP/2 keepalive pings.
+    pub fn with_http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// How long to wait for a keepalive ping to be acknowledged before closing the connection.
+    pub fn with_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.keep_alive_timeout = Some(timeout);
+        self
+    }
+
+    /// Also ping while no stream is open.
+    pub fn with_keep_alive_while_idle(mut self, enabled: bool) -> Self {
+        self.keep_alive_while_idle = Some(enabled);
+        self
+    }
+
+    /// Largest message accepted from the server (tonic's default is 4 MiB).
+    pub fn with_max_decoding_message_size(mut self, limit: usize) -> Self {
+        self.max_decoding_message_size = Some(limit);
+        self
+    }
+
+    /// Largest message sent to the server.
+    pub fn with_max_encoding_message_size(mut self, limit: usize) -> Self {
+        self.max_encoding_message_size = Some(limit);
+        self
+    }
+
+    /// Compress requests with `encoding`; the server must accept it.
+    pub fn with_send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+        self.send_compressed = Some(encoding);
+        self
+    }
+
+    /// Let the server compress responses with `encoding`; may be given more than once.
+    pub fn with_accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+        self.accept_compressed.push(encoding);
+        self
+    }
+
+    /// Connect and return the client.
+    pub async fn connect(self) -> Result<ShredstreamClient> {
+        let client = self.connect_proxy().await?;
+        Ok(ShredstreamClient {
+            client,
+            builder: self,
+        })
+    }
+
+    /// Transport settings of the endpoint, without connecting.
+    pub(crate) fn transport_endpoint(&self) -> Result<Endpoint> {
+        let mut endpoint = Endpoint::from_shared(self.endpoint.clone())?;
+        let tls_config = match &self.tls_config {
+            Some(tls_config) => Some(tls_config.clone()),
+            None if endpoint.uri().scheme_str() == Some("https") => {
+                Some(ClientTlsConfig::new().with_enabled_roots())
+            }
+            None => None,
+        };
+        if let Some(tls_config) = tls_config {
+            endpoint = endpoint.tls_config(tls_config)?;
+        }
+        if let Some(timeout) = self.connect_timeout {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            endpoint = endpoint.timeout(timeout);
+        }
+        if let Some(interval) = self.http2_keep_alive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.keep_alive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+        if let Some(enabled) = self.keep_alive_while_idle {
+            endpoint = endpoint.keep_alive_while_idle(enabled);
+        }
+        Ok(endpoint)
+    }
+
+    async fn connect_proxy(&self) -> Result<ProxyClient> {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let key = name.parse::<AsciiMetadataKey>().map_err(|_| {
+                    SolanaStreamError::Configuration(format!("invalid header name {:?}", name))
+                })?;
+                let value = value.parse::<AsciiMetadataValue>().map_err(|_| {
+                    SolanaStreamError::Configuration(format!("invalid value for header {}", name))
+                })?;
+                Ok((key, value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let channel = self.transport_endpoint()?.connect().await?;
+        let mut client = ShredstreamProxyClient::with_interceptor(
+            channel,
+            HeaderInterceptor {
+                headers: Arc::new(headers),
+            },
+        );
+        if let Some(limit) = self.max_decoding_message_size {
+            client = client.max_decoding_message_size(limit);
+        }
+        if let Some(limit) = self.max_encoding_message_size {
+            client = client.max_encoding_message_size(limit);
+        }
+        if let Some(encoding) = self.send_compressed {
+            client = client.send_compressed(encoding);
+        }
+        for encoding in &self.accept_compressed {
+            client = client.accept_compressed(*encoding);
+        }
+        Ok(client)
+    }
+}
+
+/// Adds the configured headers to each outgoing request.
+#[derive(Clone)]
+struct HeaderInterceptor {
+    headers: Arc<Vec<(AsciiMetadataKey, AsciiMetadataValue)>>,
+}
+
+impl Interceptor for HeaderInterceptor {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> std::result::Result<tonic::Request<()>, tonic::Status> {
+        for (key, value) in self.headers.iter() {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// Backoff between reconnect attempts of
+/// [`ShredstreamClient::subscribe_entries_reconnecting`].
+///
+/// The n-th consecutive failure waits `initial * multiplier^(n-1)`, capped at `max`, then
+/// spread by up to `jitter` (a fraction, e.g. `0.2` for ±20%) so clients restarting together do
+/// not reconnect in lockstep. Receiving an entry resets the count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReconnectPolicy {
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+    /// Consecutive failures before giving up; `None` retries forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub fn with_initial(mut self, initial: Duration) -> Self {
+        self.initial = initial;
+        self
+    }
+
+    pub fn with_max(mut self, max: Duration) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: Option<u32>) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Delay before retrying after `failures` consecutive failures (1-based), never more than
+    /// [`Self::with_max`].
+    pub fn delay(&self, failures: u32) -> Duration {
+        let exponent = failures.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = (self.initial.as_secs_f64() * self.multiplier.max(1.0).powi(exponent))
+            .min(self.max.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // Uniform in [-1, 1), seeded per call by std's random hasher keys.
+        let unit = RandomState::new().hash_one(failures) as f64 / u64::MAX as f64 * 2.0 - 1.0;
+        Duration::try_from_secs_f64((base * (1.0 + jitter * unit)).max(0.0))
+            .unwrap_or(self.max)
+            .min(self.max)
+    }
+}
+
+/// Connection changes reported by a [`ReconnectingEntryStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// Subscribed; `last_slot` is the last slot received before, if any.
+    Connected {
+        attempt: u32,
+        last_slot: Option<u64>,
+    },
+    /// Connecting or streaming failed after `attempt` consecutive failures. The next attempt
+    /// follows after `retry_in`; `None` means the attempts are exhausted and the stream ends.
+    Disconnected {
+        error: String,
+        attempt: u32,
+        retry_in: Option<Duration>,
+    },
+    /// The first entry after a reconnect skipped the slots between `last_slot` and
+    /// `next_slot`.
+    Gap { last_slot: u64, next_slot: u64 },
+}
+
+/// Items of a [`ReconnectingEntryStream`].
+#[derive(Debug, Clone)]
+pub enum ShredstreamEvent {
+    Entry(crate::shredstream_proto::Entry),
+    Connection(ConnectionEvent),
+}
+
+/// Entry subscription that reconnects on failure; see
+/// [`ShredstreamClient::subscribe_entries_reconnecting`].
+pub struct ReconnectingEntryStream {
+    inner: BoxStream<'static, ShredstreamEvent>,
+    last_slot: Arc<AtomicU64>,
+}
+
+impl ReconnectingEntryStream {
+    /// Highest slot received so far, across reconnects.
+    pub fn last_slot(&self) -> Option<u64> {
+        Some(self.last_slot.load(Ordering::Relaxed)).filter(|slot| *slot > 0)
+    }
+}
+
+impl Stream for ReconnectingEntryStream {
+    type Item = ShredstreamEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+struct Reconnect {
+    builder: ShredstreamClientBuilder,
+    /// Connection of the originating client, used for the first attempt.
+    client: Option<ProxyClient>,
+    request: SubscribeEntriesRequest,
+    policy: ReconnectPolicy,
+    stream: Option<tonic::Streaming<crate::shredstream_proto::Entry>>,
+    failures: u32,
+    retry_in: Option<Duration>,
+    check_gap: bool,
+    done: bool,
+    pending: VecDeque<ShredstreamEvent>,
+    last_slot: Arc<AtomicU64>,
+}
+
+impl Reconnect {
+    async fn next(mut self) -> Option<(ShredstreamEvent, Self)> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some((event, self));
+            }
+            if self.done {
+                return None;
+            }
+            let Some(stream) = self.stream.as_mut() else {
+                self.connect().await;
+                continue;
+            };
+            match stream.next().await {
+                Some(Ok(entry)) => {
+                    self.failures = 0;
+                    let last_slot = self.last_slot.fetch_max(entry.slot, Ordering::Relaxed);
+                    if std::mem::take(&mut self.check_gap)
+                        && last_slot > 0
+                        && entry.slot > last_slot + 1
+                    {
+                        self.pending.push_back(ShredstreamEvent::Connection(
+                            ConnectionEvent::Gap {
+                                last_slot,
+                                next_slot: entry.slot,
+                            },
+                        ));
+                    }
+                    self.pending.push_back(ShredstreamEvent::Entry(entry));
+                }
+                Some(Err(status)) => self.fail(SolanaStreamError::Status(status).to_string()),
+                None => self.fail("stream ended".to_string()),
+            }
+        }
+    }
+
+    async fn connect(&mut self) {
+        if let Some(delay) = self.retry_in.take() {
+            tokio::time::sleep(delay).await;
+        }
+        let client = match self.client.take() {
+            Some(client) => Ok(client),
+            None => self
+                .builder
+                .connect_proxy()
+                .await
+                .map_err(|e| e.to_string()),
+        };
+        let subscribed = match client {
+            Ok(mut client) => client
+                .subscribe_entries(self.request.clone())
+                .await
+                .map(|response| response.into_inner())
+                .map_err(|status| SolanaStreamError::Status(status).to_string()),
+            Err(error) => Err(error),
+        };
+        match subscribed {
+            Ok(stream) => {
+                self.stream = Some(stream);
+                self.check_gap = true;
+                let last_slot = self.last_slot.load(Ordering::Relaxed);
+                self.pending
+                    .push_back(ShredstreamEvent::Connection(ConnectionEvent::Connected {
+                        attempt: self.failures + 1,
+                        last_slot: Some(last_slot).filter(|slot| *slot > 0),
+                    }));
+            }
+            Err(error) => self.fail(error),
+        }
+    }
+
+    fn fail(&mut self, error: String) {
+        self.stream = None;
+        self.failures = self.failures.saturating_add(1);
+        let exhausted = self
+            .policy
+            .max_attempts
+            .is_some_and(|max| self.failures >= max);
+        self.retry_in = (!exhausted).then(|| self.policy.delay(self.failures));
+        self.done = exhausted;
+        self.pending.push_back(ShredstreamEvent::Connection(
+            ConnectionEvent::Disconnected {
+                error,
+                attempt: self.failures,
+                retry_in: self.retry_in,
+            },
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shredstream_proto::shredstream_proxy_server::ShredstreamProxyServer;
+    use solana_sdk::{hash::Hash, transaction::VersionedTransaction};
+
+    #[test]
+    fn decode_slot_entry_accepts_bincode_and_reports_slot_on_failure() {
+        let entries = vec![Entry {
+            num_hashes: 1,
+            hash: Hash::new_from_array([7u8; 32]),
+            transactions: vec![VersionedTransaction::default()],
+        }];
+        let message = crate::shredstream_proto::Entry {
+            slot: 9,
+            entries: bincode::serialize(&entries).unwrap(),
+        };
+        assert_eq!(decode_slot_entry(&message).unwrap(), (9, entries));
+
+        let broken = crate::shredstream_proto::Entry {
+            slot: 10,
+            entries: vec![1, 2, 3],
+        };
+        match decode_slot_entry(&broken) {
+            Err(SolanaStreamError::EntryDecode { slot, message }) => {
+                assert_eq!(slot, 10);
+                assert!(message.starts_with("entry decode failed: wincode="));
+            }
+            other => panic!(
+                "expected EntryDecode, got {:?}",
+                other.map(|(slot, _)| slot)
+            ),
+        }
+    }
+
+    #[test]
+    fn reconnect_delay_grows_caps_and_jitters() {
+        let policy = ReconnectPolicy::default()
+            .with_initial(Duration::from_millis(100))
+            .with_max(Duration::from_secs(1))
+            .with_jitter(0.0);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(30), Duration::from_secs(1));
+
+        let jittered = policy.with_jitter(0.5);
+        for failures in 1..20 {
+            let delay = jittered.delay(failures).as_secs_f64();
+            let base = policy.delay(failures).as_secs_f64();
+            assert!(delay >= base * 0.5 - 1e-9 && delay <= base * 1.5 + 1e-9);
+            assert!(delay <= 1.0);
+        }
+
+        let unbounded = ReconnectPolicy::default()
+            .with_initial(Duration::from_secs(u64::MAX / 2))
+            .with_max(Duration::MAX)
+            .with_multiplier(10.0)
+            .with_jitter(1.0);
+        for failures in [1, 2, 64, u32::MAX] {
+            assert!(unbounded.delay(failures) <= Duration::MAX);
+        }
+    }
+
+    /// Serves one entry per subscription from `slots`, then fails the stream.
+    struct FlakyProxy {
+        slots: std::sync::Mutex<Vec<u64>>,
+    }
+
+    #[tonic::async_trait]
+    impl crate::shredstream_proto::shredstream_proxy_server::ShredstreamProxy for FlakyProxy {
+        type SubscribeEntriesStream =
+            BoxStream<'static, std::result::Result<crate::shredstream_proto::Entry, tonic::Status>>;
+
+        async fn subscribe_entries(
+            &self,
+            _request: tonic::Request<SubscribeEntriesRequest>,
+        ) -> std::result::Result<tonic::Response<Self::SubscribeEntriesStream>, tonic::Status>
+        {
+            let mut slots = self.slots.lock().unwrap();
+            if slots.is_empty() {
+                return Err(tonic::Status::unavailable("proxy down"));
+            }
+            let slot = slots.remove(0);
+            let items = vec![
+                Ok(crate::shredstream_proto::Entry {
+                    slot,
+                    entries: Vec::new(),
+                }),
+                Err(tonic::Status::unavailable("proxy restarting")),
+            ];
+            Ok(tonic::Response::new(stream::iter(items).boxed()))
+        }
+    }
+
+    /// Serve `proxy` on a local port and return its `http://` endpoint.
+    async fn serve<S>(proxy: ShredstreamProxyServer<S>) -> String
+    where
+        S: crate::shredstream_proto::shredstream_proxy_server::ShredstreamProxy,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = stream::unfold(listener, |listener| async move {
+            Some((listener.accept().await.map(|(socket, _)| socket), listener))
+        });
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(proxy)
+                .serve_with_incoming(incoming),
+        );
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn reconnecting_stream_resubscribes_and_reports_gaps() {
+        let endpoint = serve(ShredstreamProxyServer::new(FlakyProxy {
+            slots: std::sync::Mutex::new(vec![10, 15]),
+        }))
+        .await;
+
+        let client = ShredstreamClient::connect(endpoint).await.unwrap();
+        let policy = ReconnectPolicy::default()
+            .with_initial(Duration::from_millis(10))
+            .with_max_attempts(Some(3));
+        let mut stream = client.subscribe_entries_reconnecting(
+            ShredstreamClient::create_empty_entries_request(),
+            policy,
+        );
+        let mut seen = Vec::new();
+        while let Some(event) = stream.next().await {
+            seen.push(match event {
+                ShredstreamEvent::Entry(entry) => format!("entry {}", entry.slot),
+                ShredstreamEvent::Connection(ConnectionEvent::Connected { attempt, last_slot }) => {
+                    format!("connected {} {:?}", attempt, last_slot)
+                }
+                ShredstreamEvent::Connection(ConnectionEvent::Disconnected {
+                    attempt,
+                    retry_in,
+                    ..
+                }) => format!("disconnected {} {}", attempt, retry_in.is_some()),
+                ShredstreamEvent::Connection(ConnectionEvent::Gap {
+                    last_slot,
+                    next_slot,
+                }) => format!("gap {}..{}", last_slot, next_slot),
+            });
+        }
+        assert_eq!(
+            seen,
+            [
+                "connected 1 None",
+                "entry 10",
+                "disconnected 1 true",
+                "connected 2 Some(10)",
+                "gap 10..15",
+                "entry 15",
+                "disconnected 1 true",
+                // No slots left: the proxy fails each subscription until attempts run out.
+                "disconnected 2 true",
+                "disconnected 3 false",
+            ]
+        );
+        assert_eq!(stream.last_slot(), Some(15));
+    }
+
+    /// Serves one large entry to subscribers sending `x-token: secret`.
+    struct AuthProxy;
+
+    #[tonic::async_trait]
+    impl crate::shredstream_proto::shredstream_proxy_server::ShredstreamProxy for AuthProxy {
+        type SubscribeEntriesStream =
+            BoxStream<'static, std::result::Result<crate::shredstream_proto::Entry, tonic::Status>>;
+
+        async fn subscribe_entries(
+            &self,
+            request: tonic::Request<SubscribeEntriesRequest>,
+        ) -> std::result::Result<tonic::Response<Self::SubscribeEntriesStream>, tonic::Status>
+        {
+            if request
+                .metadata()
+                .get("x-token")
+                .and_then(|v| v.to_str().ok())
+                != Some("secret")
+            {
+                return Err(tonic::Status::unauthenticated("missing x-token"));
+            }
+            let entry = crate::shredstream_proto::Entry {
+                slot: 7,
+                entries: vec![0u8; 5 * 1024 * 1024],
+            };
+            Ok(tonic::Response::new(stream::iter([Ok(entry)]).boxed()))
+        }
+    }
+
+    #[tokio::test]
+    async fn builder_sends_headers_and_applies_limits_and_compression() {
+        let endpoint = serve(
+            ShredstreamProxyServer::new(AuthProxy)
+                .send_compressed(CompressionEncoding::Gzip)
+                .accept_compressed(CompressionEncoding::Gzip),
+        )
+        .await;
+        let request = ShredstreamClient::create_empty_entries_request();
+
+        let mut anonymous = ShredstreamClient::connect(&endpoint).await.unwrap();
+        match anonymous.subscribe_entries(request.clone()).await {
+            Err(SolanaStreamError::Status(status)) => {
+                assert_eq!(status.code(), tonic::Code::Unauthenticated)
+            }
+            other => panic!("expected Unauthenticated, got {:?}", other.map(|_| ())),
+        }
+
+        let builder = ShredstreamClient::builder(&endpoint)
+            .with_x_token("secret")
+            .with_connect_timeout(Duration::from_secs(5))
+            .with_http2_keep_alive_interval(Duration::from_secs(10))
+            .with_send_compressed(CompressionEncoding::Gzip)
+            .with_accept_compressed(CompressionEncoding::Gzip);
+        // The 5 MiB entry exceeds tonic's default 4 MiB decoding limit.
+        let mut limited = builder.clone().connect().await.unwrap();
+        let mut stream = limited.subscribe_entries(request.clone()).await.unwrap();
+        let status = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+
+        let mut client = builder
+            .with_max_decoding_message_size(8 * 1024 * 1024)
+            .connect()
+            .await
+            .unwrap();
+        let mut stream = client.subscribe_entries(request).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().slot, 7);
+
+        match ShredstreamClient::builder(&endpoint)
+            .with_header("bad header", "x")
+            .connect()
+            .await
+        {
+            Err(SolanaStreamError::Configuration(message)) => {
+                assert_eq!(message, "invalid header name \"bad header\"")
+            }
+            other => panic!("expected Configuration, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Shared decoding helpers for the AMM venue detailers (Raydium, Meteora, ...).
+//!
+//! A venue maps each of its programs to an [`AmmProgram`] that turns one compiled instruction
+//! into an [`AmmCall`]; [`AmmMintFinder`] and [`AmmDetailer`] are the finder/detailer around it.
+
+use super::{
+    insert_mint, is_system_id, read_u64_le, side_amount, token_account_mints, Amount, AmountBound,
+    MintDetail, MintDetailer, MintFinder, MintInfo, PoolDetail, ProgramWatchConfig, Protocol,
+    SwapDetail, WatchAction, ASSOCIATED_TOKEN_PROGRAM,
+};
+use solana_sdk::{
+    message::compiled_instruction::CompiledInstruction, pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use std::collections::BTreeMap;
+
+/// One program of a venue: knows its mainnet id, protocol label and instruction layouts.
+///
+/// Public only so it can bound [`AmmMintFinder`] and [`AmmDetailer`]; this module is private,
+/// so the trait stays sealed.
+pub trait AmmProgram: Copy + Send + Sync + 'static {
+    /// Every program of the venue.
+    const PROGRAMS: &'static [Self];
+    fn program_id(self) -> Pubkey;
+    fn protocol(self) -> Protocol;
+    fn decode(self, ix: &InstructionView<'_>) -> Option<AmmCall>;
+}
+
+/// A decoded venue instruction.
+pub enum AmmCall {
+    Pool {
+        action: WatchAction,
+        pool: PoolDetail,
+    },
+    Swap(SwapDetail),
+}
+
+impl AmmCall {
+    fn to_mint_detail(&self, protocol: Protocol) -> Option<MintDetail> {
+        match self {
+            Self::Pool { action, pool } => Some(pool.to_mint_detail(protocol, *action)),
+            Self::Swap(swap) => swap.to_mint_detail(protocol),
+        }
+    }
+}
+
+/// Account/data accessors for one compiled instruction.
+pub struct InstructionView<'a> {
+    keys: &'a [Pubkey],
+    ix: &'a CompiledInstruction,
+    token_accounts: &'a BTreeMap<Pubkey, Pubkey>,
+}
+
+impl<'a> InstructionView<'a> {
+    pub(super) fn new(
+        keys: &'a [Pubkey],
+        ix: &'a CompiledInstruction,
+        token_accounts: &'a BTreeMap<Pubkey, Pubkey>,
+    ) -> Self {
+        Self {
+            keys,
+            ix,
+            token_accounts,
+        }
+    }
+
+    pub(super) fn data(&self) -> &[u8] {
+        &self.ix.data
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.ix.accounts.len()
+    }
+
+    pub(super) fn get(&self, index: usize) -> Option<Pubkey> {
+        self.ix
+            .accounts
+            .get(index)
+            .and_then(|i| self.keys.get(*i as usize))
+            .copied()
+    }
+
+    /// Mint of the token account at `index`, when it was created in this transaction.
+    pub(super) fn mint_of(&self, index: usize) -> Option<Pubkey> {
+        self.get(index)
+            .and_then(|account| self.token_accounts.get(&account))
+            .copied()
+    }
+
+    /// Which of `mints` (`(mint index, token program index)` pairs) the token account at
+    /// `index` holds: created in this transaction, or the associated token account of the
+    /// `owner` account.
+    pub(super) fn held_mint(
+        &self,
+        index: usize,
+        owner: usize,
+        mints: [(usize, usize); 2],
+    ) -> Option<Pubkey> {
+        let account = self.get(index)?;
+        let candidates = mints.map(|(mint, program)| (self.get(mint), self.get(program)));
+        if let Some(mint) = self.token_accounts.get(&account) {
+            return candidates
+                .iter()
+                .any(|(candidate, _)| *candidate == Some(*mint))
+                .then_some(*mint);
+        }
+        let owner = self.get(owner)?;
+        candidates.into_iter().find_map(|(mint, program)| {
+            let (mint, program) = (mint?, program?);
+            let (ata, _) = Pubkey::find_program_address(
+                &[owner.as_ref(), program.as_ref(), mint.as_ref()],
+                &ASSOCIATED_TOKEN_PROGRAM,
+            );
+            (ata == account).then_some(mint)
+        })
+    }
+
+    /// Amount at `offset`, in the unit of `mint` (base units when the mint is unknown).
+    pub(super) fn amount(
+        &self,
+        mint: Option<Pubkey>,
+        offset: usize,
+        bound: AmountBound,
+    ) -> Option<Amount> {
+        let value = read_u64_le(self.data(), offset)?;
+        Some(match mint {
+            Some(mint) => side_amount(&mint, value, bound),
+            None => Amount::token(value).with_bound(bound),
+        })
+    }
+
+    /// Pool instruction with `(base_offset, quote_offset, bound)` amounts; both mints are
+    /// required.
+    pub(super) fn pool(
+        &self,
+        action: WatchAction,
+        (pool, base_mint, quote_mint): (Option<Pubkey>, Option<Pubkey>, Option<Pubkey>),
+        amounts: Option<(usize, usize, AmountBound)>,
+    ) -> Option<AmmCall> {
+        let (base_mint, quote_mint) = (base_mint?, quote_mint?);
+        if is_system_id(&base_mint) || is_system_id(&quote_mint) {
+            return None;
+        }
+        let mut pool = PoolDetail::new(pool?, base_mint, quote_mint);
+        if let Some((base_offset, quote_offset, bound)) = amounts {
+            pool.base_amount = self.amount(Some(base_mint), base_offset, bound);
+            pool.quote_amount = self.amount(Some(quote_mint), quote_offset, bound);
+        }
+        Some(AmmCall::Pool { action, pool })
+    }
+
+    /// Swap with `(offset, bound)` for the input and output amounts.
+    pub(super) fn swap(
+        &self,
+        (pool, input_mint, output_mint): (Option<Pubkey>, Option<Pubkey>, Option<Pubkey>),
+        (in_offset, in_bound): (usize, AmountBound),
+        (out_offset, out_bound): (usize, AmountBound),
+    ) -> Option<AmmCall> {
+        let mut swap = SwapDetail::new(pool?, input_mint, output_mint);
+        swap.amount_in = self.amount(input_mint, in_offset, in_bound);
+        swap.amount_out = self.amount(output_mint, out_offset, out_bound);
+        Some(AmmCall::Swap(swap))
+    }
+}
+
+pub(super) fn anchor_disc(data: &[u8]) -> Option<[u8; 8]> {
+    data.get(..8)?.try_into().ok()
+}
+
+/// Token-centric details for every instruction of `programs` in `tx`, in order.
+pub(super) fn mint_details<P: AmmProgram>(
+    programs: &[(Pubkey, P)],
+    tx: &VersionedTransaction,
+) -> Vec<MintDetail> {
+    let keys = tx.message.static_account_keys();
+    let mut token_accounts = None;
+    let mut out = Vec::new();
+    for ix in tx.message.instructions() {
+        let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+            continue;
+        };
+        let Some((_, program)) = programs.iter().find(|(id, _)| id == program_id) else {
+            continue;
+        };
+        let view = InstructionView::new(
+            keys,
+            ix,
+            token_accounts.get_or_insert_with(|| token_account_mints(tx)),
+        );
+        if let Some(detail) = program
+            .decode(&view)
+            .and_then(|call| call.to_mint_detail(program.protocol()))
+        {
+            out.push(detail);
+        }
+    }
+    out
+}
+
+/// Finder output: one [`MintInfo`] per mint.
+pub(super) fn mint_infos(details: Vec<MintDetail>) -> Vec<MintInfo> {
+    let mut mints = BTreeMap::new();
+    for detail in details {
+        insert_mint(
+            &mut mints,
+            MintInfo {
+                mint: detail.mint,
+                protocol: detail.protocol,
+                action: detail.action,
+            },
+        );
+    }
+    mints.into_values().collect()
+}
+
+/// Precedence among the details of one mint within a transaction.
+fn rank(action: Option<WatchAction>) -> u8 {
+    match action {
+        Some(WatchAction::CreatePool) => 2,
+        Some(WatchAction::OpenPosition | WatchAction::ClosePosition) => 1,
+        _ => 0,
+    }
+}
+
+/// Detailer output: one detail per mint, the last instruction winning unless it is outranked:
+/// a pool creation or position open/close beats the swaps/deposits bundled with it, taking
+/// their amounts when it carries none.
+pub(super) fn dedup_details(details: Vec<MintDetail>) -> Vec<MintDetail> {
+    let mut out: BTreeMap<Pubkey, MintDetail> = BTreeMap::new();
+    for detail in details {
+        let mint = detail.mint;
+        let kept = match out.remove(&mint) {
+            Some(existing) if rank(existing.action) > rank(detail.action) => {
+                backfill(existing, &detail)
+            }
+            Some(existing) if rank(detail.action) > rank(existing.action) => {
+                backfill(detail, &existing)
+            }
+            _ => detail,
+        };
+        out.insert(mint, kept);
+    }
+    out.into_values().collect()
+}
+
+fn backfill(mut kept: MintDetail, bundled: &MintDetail) -> MintDetail {
+    if bundled.pool.is_some() {
+        kept.sol_amount = kept.sol_amount.or(bundled.sol_amount);
+        kept.token_amount = kept.token_amount.or(bundled.token_amount);
+    }
+    kept
+}
+
+/// Program ids known to be mainnet programs of the venue; others need `with_program`.
+fn known_programs<P: AmmProgram>(program_ids: Vec<Pubkey>) -> Vec<(Pubkey, P)> {
+    program_ids
+        .into_iter()
+        .filter_map(|id| {
+            P::PROGRAMS
+                .iter()
+                .find(|p| p.program_id() == id)
+                .map(|p| (id, *p))
+        })
+        .collect()
+}
+
+fn all_programs<P: AmmProgram>() -> Vec<(Pubkey, P)> {
+    P::PROGRAMS.iter().map(|p| (p.program_id(), *p)).collect()
+}
+
+/// Venue finder: reports the token side of each pool or swap touched.
+pub struct AmmMintFinder<P> {
+    programs: Vec<(Pubkey, P)>,
+}
+
+impl<P: AmmProgram> AmmMintFinder<P> {
+    /// Keeps the ids that are mainnet programs of the venue.
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self {
+            programs: known_programs(program_ids),
+        }
+    }
+
+    /// Decode `program_id` (e.g. a devnet deployment) as `program`.
+    pub fn with_program(mut self, program_id: Pubkey, program: P) -> Self {
+        self.programs.push((program_id, program));
+        self
+    }
+}
+
+impl<P: AmmProgram> Default for AmmMintFinder<P> {
+    fn default() -> Self {
+        Self {
+            programs: all_programs(),
+        }
+    }
+}
+
+impl<P: AmmProgram> MintFinder for AmmMintFinder<P> {
+    fn find_mints(&self, tx: &VersionedTransaction, _cfg: &ProgramWatchConfig) -> Vec<MintInfo> {
+        mint_infos(mint_details(&self.programs, tx))
+    }
+}
+
+/// Venue detailer: pool, mints and amount limits for every instruction of the venue.
+pub struct AmmDetailer<P> {
+    programs: Vec<(Pubkey, P)>,
+}
+
+impl<P: AmmProgram> AmmDetailer<P> {
+    /// Keeps the ids that are mainnet programs of the venue.
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self {
+            programs: known_programs(program_ids),
+        }
+    }
+
+    /// Decode `program_id` (e.g. a devnet deployment) as `program`.
+    pub fn with_program(mut self, program_id: Pubkey, program: P) -> Self {
+        self.programs.push((program_id, program));
+        self
+    }
+}
+
+impl<P: AmmProgram> Default for AmmDetailer<P> {
+    fn default() -> Self {
+        Self {
+            programs: all_programs(),
+        }
+    }
+}
+
+impl<P: AmmProgram> MintDetailer for AmmDetailer<P> {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        _cfg: &ProgramWatchConfig,
+        _mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        dedup_details(mint_details(&self.programs, tx))
+    }
+}
@@ -0,0 +1,322 @@
+//! Compute budget instructions and priority fees.
+//!
+//! [`ComputeBudget::from_transaction`] reads the ComputeBudget program's top-level
+//! instructions; [`FeeTracker`] keeps a rolling window of compute unit prices per slot and per
+//! invoked program so senders can price from live data.
+
+use super::is_vote_transaction;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+const COMPUTE_BUDGET: Pubkey = Pubkey::from_str_const(COMPUTE_BUDGET_PROGRAM_ID);
+const SYSTEM_PROGRAM: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+
+const REQUEST_HEAP_FRAME: u8 = 1;
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Runtime default per non-builtin instruction when no limit is requested.
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+/// Runtime default per builtin program instruction when no limit is requested.
+pub const BUILTIN_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 3_000;
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Natively implemented programs, which get [`BUILTIN_INSTRUCTION_COMPUTE_UNIT_LIMIT`] by
+/// default. Builtins already migrated to on-chain programs (Config, Stake) are left out.
+const BUILTIN_PROGRAMS: [Pubkey; 8] = [
+    COMPUTE_BUDGET,
+    SYSTEM_PROGRAM,
+    Pubkey::from_str_const("Vote111111111111111111111111111111111111111"),
+    Pubkey::from_str_const("AddressLookupTab1e1111111111111111111111111"),
+    Pubkey::from_str_const("BPFLoader1111111111111111111111111111111111"),
+    Pubkey::from_str_const("BPFLoader2111111111111111111111111111111111"),
+    Pubkey::from_str_const("BPFLoaderUpgradeab1e11111111111111111111111"),
+    Pubkey::from_str_const("LoaderV411111111111111111111111111111111111"),
+];
+
+/// Compute budget requested by a transaction.
+///
+/// `priority_fee_lamports` is `ceil(unit_price * limit / 1e6)`, using the requested limit or
+/// the runtime default (3k per builtin instruction, including ComputeBudget's own, and 200k per
+/// other instruction, capped at 1.4M). The base signature fee is not included.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ComputeBudget {
+    /// `SetComputeUnitLimit`, if present.
+    pub unit_limit: Option<u32>,
+    /// `SetComputeUnitPrice` in micro-lamports per compute unit, if present.
+    pub unit_price: Option<u64>,
+    /// `RequestHeapFrame` in bytes, if present (the default heap is 32 KiB).
+    pub heap_frame_bytes: Option<u32>,
+    pub priority_fee_lamports: u64,
+}
+
+impl ComputeBudget {
+    pub fn from_transaction(tx: &VersionedTransaction) -> Self {
+        let keys = tx.message.static_account_keys();
+        let mut budget = Self::default();
+        let mut default_limit: u32 = 0;
+        for ix in tx.message.instructions() {
+            let program = keys.get(ix.program_id_index as usize);
+            default_limit = default_limit.saturating_add(
+                if program.is_some_and(|id| BUILTIN_PROGRAMS.contains(id)) {
+                    BUILTIN_INSTRUCTION_COMPUTE_UNIT_LIMIT
+                } else {
+                    DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
+                },
+            );
+            if program != Some(&COMPUTE_BUDGET) {
+                continue;
+            }
+            let u32_arg = || {
+                ix.data
+                    .get(1..5)
+                    .and_then(|b| b.try_into().ok())
+                    .map(u32::from_le_bytes)
+            };
+            match ix.data.first() {
+                Some(&REQUEST_HEAP_FRAME) => budget.heap_frame_bytes = u32_arg(),
+                Some(&SET_COMPUTE_UNIT_LIMIT) => budget.unit_limit = u32_arg(),
+                Some(&SET_COMPUTE_UNIT_PRICE) => {
+                    budget.unit_price = ix
+                        .data
+                        .get(1..9)
+                        .and_then(|b| b.try_into().ok())
+                        .map(u64::from_le_bytes)
+                }
+                _ => {}
+            }
+        }
+        let limit = budget
+            .unit_limit
+            .unwrap_or(default_limit)
+            .min(MAX_COMPUTE_UNIT_LIMIT);
+        let fee = u128::from(budget.unit_price.unwrap_or(0)) * u128::from(limit);
+        budget.priority_fee_lamports =
+            u64::try_from(fee.div_ceil(MICRO_LAMPORTS_PER_LAMPORT)).unwrap_or(u64::MAX);
+        budget
+    }
+
+    /// True when the transaction asked for a non-default heap frame.
+    pub fn requests_heap_frame(&self) -> bool {
+        self.heap_frame_bytes.is_some()
+    }
+}
+
+#[derive(Default)]
+struct SlotFees {
+    prices: Vec<u64>,
+    by_program: HashMap<Pubkey, Vec<u64>>,
+}
+
+/// Rolling compute unit price percentiles over the last `window_slots` slots, overall and per
+/// invoked program.
+///
+/// Only transactions that set a compute unit price are recorded; votes are skipped. Wrap it in
+/// a mutex to share between the ingest task and senders.
+pub struct FeeTracker {
+    window_slots: u64,
+    slots: BTreeMap<u64, SlotFees>,
+}
+
+impl FeeTracker {
+    pub fn new(window_slots: u64) -> Self {
+        Self {
+            window_slots: window_slots.max(1),
+            slots: BTreeMap::new(),
+        }
+    }
+
+    /// Record `tx` seen in `slot` and return its compute budget.
+    pub fn record(&mut self, slot: u64, tx: &VersionedTransaction) -> ComputeBudget {
+        let budget = ComputeBudget::from_transaction(tx);
+        if is_vote_transaction(tx) {
+            return budget;
+        }
+        let keys = tx.message.static_account_keys();
+        let programs: BTreeSet<Pubkey> = tx
+            .message
+            .instructions()
+            .iter()
+            .filter_map(|ix| keys.get(ix.program_id_index as usize).copied())
+            .filter(|id| *id != COMPUTE_BUDGET)
+            .collect();
+        self.record_budget(slot, programs, &budget);
+        budget
+    }
+
+    /// Record an already decoded budget for a transaction invoking `programs`.
+    pub fn record_budget(
+        &mut self,
+        slot: u64,
+        programs: impl IntoIterator<Item = Pubkey>,
+        budget: &ComputeBudget,
+    ) {
+        let Some(price) = budget.unit_price else {
+            return;
+        };
+        if self
+            .slots
+            .last_key_value()
+            .is_some_and(|(latest, _)| latest.saturating_sub(slot) >= self.window_slots)
+        {
+            return;
+        }
+        let fees = self.slots.entry(slot).or_default();
+        fees.prices.push(price);
+        for program in programs {
+            fees.by_program.entry(program).or_default().push(price);
+        }
+        if let Some((&latest, _)) = self.slots.last_key_value() {
+            let oldest = latest.saturating_sub(self.window_slots - 1);
+            self.slots = self.slots.split_off(&oldest);
+        }
+    }
+
+    /// Newest slot with recorded fees.
+    pub fn latest_slot(&self) -> Option<u64> {
+        self.slots.keys().next_back().copied()
+    }
+
+    /// Compute unit price at `percentile` (0-100, nearest rank) within one slot.
+    pub fn slot_percentile(&self, slot: u64, percentile: u8) -> Option<u64> {
+        nearest_rank(self.slots.get(&slot)?.prices.clone(), percentile)
+    }
+
+    /// Compute unit price at `percentile` across the window.
+    pub fn percentile(&self, percentile: u8) -> Option<u64> {
+        let prices = self
+            .slots
+            .values()
+            .flat_map(|fees| fees.prices.iter().copied())
+            .collect();
+        nearest_rank(prices, percentile)
+    }
+
+    /// Compute unit price at `percentile` across the window, for transactions invoking
+    /// `program`.
+    pub fn program_percentile(&self, program: &Pubkey, percentile: u8) -> Option<u64> {
+        let prices = self
+            .slots
+            .values()
+            .filter_map(|fees| fees.by_program.get(program))
+            .flatten()
+            .copied()
+            .collect();
+        nearest_rank(prices, percentile)
+    }
+}
+
+fn nearest_rank(mut values: Vec<u64>, percentile: u8) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let rank = (usize::from(percentile.min(100)) * values.len()).div_ceil(100);
+    values.get(rank.saturating_sub(1)).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txn::test_support::tx_with;
+    use solana_sdk::instruction::Instruction;
+
+    const PAYER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const PROGRAM: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const OTHER: Pubkey = Pubkey::new_from_array([3u8; 32]);
+
+    fn budget_ix(tag: u8, arg: &[u8]) -> Instruction {
+        let mut data = vec![tag];
+        data.extend_from_slice(arg);
+        Instruction::new_with_bytes(COMPUTE_BUDGET, &data, Vec::new())
+    }
+
+    fn priced_tx(program: Pubkey, price: u64) -> VersionedTransaction {
+        tx_with(
+            &PAYER,
+            &[
+                budget_ix(SET_COMPUTE_UNIT_PRICE, &price.to_le_bytes()),
+                Instruction::new_with_bytes(program, &[], Vec::new()),
+            ],
+        )
+    }
+
+    #[test]
+    fn compute_budget_reads_limit_price_and_heap() {
+        let tx = tx_with(
+            &PAYER,
+            &[
+                budget_ix(SET_COMPUTE_UNIT_LIMIT, &150_000u32.to_le_bytes()),
+                budget_ix(SET_COMPUTE_UNIT_PRICE, &1_000_001u64.to_le_bytes()),
+                budget_ix(REQUEST_HEAP_FRAME, &(256 * 1024u32).to_le_bytes()),
+                Instruction::new_with_bytes(PROGRAM, &[], Vec::new()),
+            ],
+        );
+        let budget = ComputeBudget::from_transaction(&tx);
+        assert_eq!(budget.unit_limit, Some(150_000));
+        assert_eq!(budget.unit_price, Some(1_000_001));
+        assert!(budget.requests_heap_frame());
+        // 1_000_001 µλ/CU * 150k CU = 150_000.15 lamports, rounded up.
+        assert_eq!(budget.priority_fee_lamports, 150_001);
+
+        // No limit: 3k for the ComputeBudget instruction and 200k for the program.
+        let budget = ComputeBudget::from_transaction(&priced_tx(PROGRAM, 5_000));
+        assert_eq!(budget.unit_limit, None);
+        assert_eq!(budget.priority_fee_lamports, 1_015);
+        assert!(!budget.requests_heap_frame());
+
+        // A System transfer only gets builtin defaults: 2 * 3k CU.
+        let transfer = tx_with(
+            &PAYER,
+            &[
+                budget_ix(SET_COMPUTE_UNIT_PRICE, &1_000_000u64.to_le_bytes()),
+                Instruction::new_with_bytes(SYSTEM_PROGRAM, &[2, 0, 0, 0], Vec::new()),
+            ],
+        );
+        assert_eq!(
+            ComputeBudget::from_transaction(&transfer).priority_fee_lamports,
+            6_000
+        );
+    }
+
+    #[test]
+    fn fee_tracker_percentiles_roll_over_the_window() {
+        let mut tracker = FeeTracker::new(2);
+        for price in [10, 20, 30, 40] {
+            tracker.record(100, &priced_tx(PROGRAM, price));
+        }
+        tracker.record(100, &priced_tx(OTHER, 1_000));
+        tracker.record(
+            100,
+            &tx_with(
+                &PAYER,
+                &[Instruction::new_with_bytes(PROGRAM, &[], Vec::new())],
+            ),
+        );
+
+        assert_eq!(tracker.slot_percentile(100, 50), Some(30));
+        assert_eq!(tracker.slot_percentile(100, 100), Some(1_000));
+        assert_eq!(tracker.program_percentile(&PROGRAM, 50), Some(20));
+        assert_eq!(tracker.program_percentile(&PROGRAM, 75), Some(30));
+
+        tracker.record(101, &priced_tx(PROGRAM, 500));
+        assert_eq!(tracker.program_percentile(&PROGRAM, 100), Some(500));
+        // Slot 102 pushes slot 100 out of the two-slot window; late slot 99 is ignored.
+        tracker.record(102, &priced_tx(PROGRAM, 1));
+        tracker.record(99, &priced_tx(PROGRAM, 9_999));
+        assert_eq!(tracker.slot_percentile(100, 50), None);
+        assert_eq!(tracker.percentile(100), Some(500));
+        assert_eq!(tracker.program_percentile(&OTHER, 50), None);
+        assert_eq!(tracker.latest_slot(), Some(102));
+
+        // Slots near u64::MAX must not overflow the window check.
+        tracker.record(u64::MAX, &priced_tx(PROGRAM, 7));
+        tracker.record(u64::MAX - 1, &priced_tx(PROGRAM, 8));
+        assert_eq!(tracker.latest_slot(), Some(u64::MAX));
+        assert_eq!(tracker.percentile(0), Some(7));
+        assert_eq!(tracker.percentile(100), Some(8));
+    }
+}
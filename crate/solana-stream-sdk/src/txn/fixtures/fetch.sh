@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+# Store a mainnet transaction as a fixture: fetch.sh <fixture-name> <signature> [rpc-url]
+#
+# Fetches the transaction with `getTransaction` (base64, v0 allowed) and writes its wire
+# bytes as hex to `<fixture-name>.hex` next to this script. Record the signature in the
+# README table and update the keys/amounts asserted by the matching test.
+set -euo pipefail
+
+if [ $# -lt 2 ]; then
+  echo "usage: $0 <fixture-name> <signature> [rpc-url]" >&2
+  exit 1
+fi
+name="${1%.hex}"
+signature="$2"
+rpc="${3:-${SOLANA_RPC_ENDPOINT:-https://api.mainnet-beta.solana.com}}"
+dir="$(cd "$(dirname "$0")" && pwd)"
+
+payload=$(printf '{"jsonrpc":"2.0","id":1,"method":"getTransaction","params":["%s",{"encoding":"base64","maxSupportedTransactionVersion":0,"commitment":"confirmed"}]}' "$signature")
+base64_tx=$(curl -sS --fail -X POST -H 'content-type: application/json' -d "$payload" "$rpc" \
+  | jq -er '.result.transaction[0]')
+
+printf '%s' "$base64_tx" | base64 -d | od -An -v -tx1 | tr -d ' \n' > "$dir/$name.hex"
+echo "wrote $dir/$name.hex for $signature"
@@ -0,0 +1,995 @@
+//! Anchor IDL-driven instruction decoding.
+//!
+//! [`Idl::parse`] accepts both Anchor IDL layouts: the legacy one (pre-0.30: camelCase names,
+//! `isMut`/`isSigner`, discriminators derived from `global:<name>`) and the current one
+//! (0.30+: explicit `discriminator`, `writable`/`signer`, `{ "defined": { "name": .. } }`).
+//! Names are normalized to snake_case so configs work with either.
+//!
+//! [`IdlDetailer`] matches top-level instructions of one program by discriminator, decodes
+//! their borsh arguments and names their accounts, yielding [`InstructionEvent`]s. It is also
+//! a [`MintDetailer`]: accounts listed in `mint_accounts` become watch details, so a new
+//! protocol only needs an IDL file and a settings entry.
+
+use super::{
+    is_system_id, InstructionDecoder, InstructionEvent, MintDetail, MintDetailer, MintInfo,
+    NamedAccount, ProgramWatchConfig, Protocol, WatchAction,
+};
+use crate::{Result, SolanaStreamError};
+use serde::Deserialize;
+use serde_json::{Map, Number, Value};
+use sha2::{Digest, Sha256};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+    str::FromStr,
+};
+
+const DISCRIMINATOR_LEN: usize = 8;
+// Guards against self-referential type definitions.
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// A borsh type as declared in an IDL.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    F32,
+    F64,
+    Bytes,
+    String,
+    Pubkey,
+    Option(Box<IdlType>),
+    COption(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdlFields {
+    Unit,
+    Named(Vec<(String, IdlType)>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdlTypeDef {
+    Struct(IdlFields),
+    Enum(Vec<(String, IdlFields)>),
+    Alias(IdlType),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdlAccountItem {
+    /// Snake_case name; accounts of nested groups are joined with `.`.
+    pub name: String,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<IdlAccountItem>,
+    pub args: Vec<(String, IdlType)>,
+}
+
+/// The parts of an Anchor IDL needed to decode instructions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Idl {
+    pub name: String,
+    pub address: Option<Pubkey>,
+    pub instructions: Vec<IdlInstruction>,
+    pub types: HashMap<String, IdlTypeDef>,
+}
+
+impl Idl {
+    /// Parse a legacy or 0.30+ Anchor IDL JSON document.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(raw)
+            .map_err(|e| SolanaStreamError::Configuration(format!("IDL is not JSON: {}", e)))?;
+        Self::from_value(&value).map_err(SolanaStreamError::Configuration)
+    }
+
+    fn from_value(value: &Value) -> std::result::Result<Self, String> {
+        let name = value
+            .pointer("/metadata/name")
+            .or_else(|| value.get("name"))
+            .and_then(Value::as_str)
+            .ok_or("IDL has no name")?
+            .to_string();
+        let address = match value
+            .get("address")
+            .or_else(|| value.pointer("/metadata/address"))
+            .and_then(Value::as_str)
+        {
+            Some(raw) => {
+                Some(Pubkey::from_str(raw).map_err(|_| format!("invalid address {:?}", raw))?)
+            }
+            None => None,
+        };
+
+        let mut types = HashMap::new();
+        // Legacy IDLs keep account structs in `accounts`; both may be referenced by `defined`.
+        for section in ["accounts", "types"] {
+            for def in array(value, section)? {
+                if def.get("type").is_none() {
+                    continue;
+                }
+                let def_name = str_field(def, "name", section)?;
+                let parsed = parse_type_def(&def["type"])
+                    .map_err(|e| format!("{} `{}`: {}", section, def_name, e))?;
+                types.insert(def_name.to_string(), parsed);
+            }
+        }
+
+        let mut instructions = Vec::new();
+        for ix in array(value, "instructions")? {
+            let ix_name = to_snake_case(str_field(ix, "name", "instruction")?);
+            let parsed = parse_instruction(ix, &ix_name)
+                .map_err(|e| format!("instruction `{}`: {}", ix_name, e))?;
+            instructions.push(parsed);
+        }
+        if instructions.is_empty() {
+            return Err("IDL declares no instructions".to_string());
+        }
+
+        let idl = Self {
+            name,
+            address,
+            instructions,
+            types,
+        };
+        idl.check_defined()?;
+        Ok(idl)
+    }
+
+    pub fn instruction(&self, name: &str) -> Option<&IdlInstruction> {
+        self.instructions.iter().find(|ix| ix.name == name)
+    }
+
+    /// The instruction whose discriminator prefixes `data`.
+    pub fn match_instruction(&self, data: &[u8]) -> Option<&IdlInstruction> {
+        self.instructions
+            .iter()
+            .find(|ix| !ix.discriminator.is_empty() && data.starts_with(&ix.discriminator))
+    }
+
+    /// Decode `data` (discriminator stripped) as the arguments of `ix`.
+    pub fn decode_args(
+        &self,
+        ix: &IdlInstruction,
+        data: &[u8],
+    ) -> std::result::Result<Value, String> {
+        let mut reader = Reader { data, pos: 0 };
+        let mut args = Map::new();
+        for (name, ty) in &ix.args {
+            let value = self
+                .decode_value(ty, &mut reader, 0)
+                .map_err(|e| format!("arg `{}`: {}", name, e))?;
+            args.insert(name.clone(), value);
+        }
+        Ok(Value::Object(args))
+    }
+
+    fn check_defined(&self) -> std::result::Result<(), String> {
+        fn walk(
+            ty: &IdlType,
+            types: &HashMap<String, IdlTypeDef>,
+        ) -> std::result::Result<(), String> {
+            match ty {
+                IdlType::Option(inner)
+                | IdlType::COption(inner)
+                | IdlType::Vec(inner)
+                | IdlType::Array(inner, _) => walk(inner, types),
+                IdlType::Defined(name) if !types.contains_key(name) => {
+                    Err(format!("undefined type `{}`", name))
+                }
+                _ => Ok(()),
+            }
+        }
+        for ix in &self.instructions {
+            for (arg, ty) in &ix.args {
+                walk(ty, &self.types)
+                    .map_err(|e| format!("instruction `{}` arg `{}`: {}", ix.name, arg, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode_value(
+        &self,
+        ty: &IdlType,
+        r: &mut Reader<'_>,
+        depth: usize,
+    ) -> std::result::Result<Value, String> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err("type nesting too deep".to_string());
+        }
+        Ok(match ty {
+            IdlType::Bool => match r.u8()? {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                other => return Err(format!("invalid bool byte {}", other)),
+            },
+            IdlType::U8 => Value::from(r.u8()?),
+            IdlType::I8 => Value::from(r.array::<1>().map(i8::from_le_bytes)?),
+            IdlType::U16 => Value::from(r.array().map(u16::from_le_bytes)?),
+            IdlType::I16 => Value::from(r.array().map(i16::from_le_bytes)?),
+            IdlType::U32 => Value::from(r.u32()?),
+            IdlType::I32 => Value::from(r.array().map(i32::from_le_bytes)?),
+            IdlType::U64 => Value::from(r.array().map(u64::from_le_bytes)?),
+            IdlType::I64 => Value::from(r.array().map(i64::from_le_bytes)?),
+            IdlType::U128 => Value::String(r.array().map(u128::from_le_bytes)?.to_string()),
+            IdlType::I128 => Value::String(r.array().map(i128::from_le_bytes)?.to_string()),
+            IdlType::F32 => float(f64::from(r.array().map(f32::from_le_bytes)?)),
+            IdlType::F64 => float(r.array().map(f64::from_le_bytes)?),
+            IdlType::Bytes => {
+                let len = r.len_prefix()?;
+                Value::String(hex::encode(r.take(len)?))
+            }
+            IdlType::String => {
+                let len = r.len_prefix()?;
+                let bytes = r.take(len)?;
+                Value::String(
+                    std::str::from_utf8(bytes)
+                        .map_err(|_| "string is not UTF-8".to_string())?
+                        .to_string(),
+                )
+            }
+            IdlType::Pubkey => Value::String(Pubkey::new_from_array(r.array()?).to_string()),
+            IdlType::Option(inner) => match r.u8()? {
+                0 => Value::Null,
+                1 => self.decode_value(inner, r, depth + 1)?,
+                other => return Err(format!("invalid option tag {}", other)),
+            },
+            IdlType::COption(inner) => match r.u32()? {
+                0 => Value::Null,
+                1 => self.decode_value(inner, r, depth + 1)?,
+                other => return Err(format!("invalid coption tag {}", other)),
+            },
+            IdlType::Vec(inner) => {
+                let len = r.len_prefix()?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.decode_value(inner, r, depth + 1)?);
+                }
+                Value::Array(items)
+            }
+            IdlType::Array(inner, len) => {
+                let mut items = Vec::with_capacity((*len).min(r.remaining()));
+                for _ in 0..*len {
+                    items.push(self.decode_value(inner, r, depth + 1)?);
+                }
+                Value::Array(items)
+            }
+            IdlType::Defined(name) => {
+                match self
+                    .types
+                    .get(name)
+                    .ok_or_else(|| format!("undefined type `{}`", name))?
+                {
+                    IdlTypeDef::Struct(fields) => self.decode_fields(fields, r, depth + 1)?,
+                    IdlTypeDef::Alias(inner) => self.decode_value(inner, r, depth + 1)?,
+                    IdlTypeDef::Enum(variants) => {
+                        let index = r.u8()?;
+                        let (variant, fields) = variants
+                            .get(usize::from(index))
+                            .ok_or_else(|| format!("`{}` has no variant {}", name, index))?;
+                        match fields {
+                            IdlFields::Unit => Value::String(variant.clone()),
+                            fields => {
+                                let mut tagged = Map::new();
+                                tagged.insert(
+                                    variant.clone(),
+                                    self.decode_fields(fields, r, depth + 1)?,
+                                );
+                                Value::Object(tagged)
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn decode_fields(
+        &self,
+        fields: &IdlFields,
+        r: &mut Reader<'_>,
+        depth: usize,
+    ) -> std::result::Result<Value, String> {
+        Ok(match fields {
+            IdlFields::Unit => Value::Null,
+            IdlFields::Named(named) => {
+                let mut out = Map::new();
+                for (name, ty) in named {
+                    out.insert(name.clone(), self.decode_value(ty, r, depth)?);
+                }
+                Value::Object(out)
+            }
+            IdlFields::Tuple(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|ty| self.decode_value(ty, r, depth))
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+        })
+    }
+}
+
+/// One `idls` entry in the shreds-udp settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdlProgramConfig {
+    /// Path to the Anchor IDL JSON file (relative paths resolve from the working directory).
+    pub path: PathBuf,
+    /// Program to decode; defaults to the IDL's `address`.
+    #[serde(default)]
+    pub program_id: Option<String>,
+    /// Protocol name reported on events; defaults to the IDL name.
+    #[serde(default)]
+    pub protocol: Option<String>,
+    /// Instruction accounts reported as mint details.
+    #[serde(default = "default_mint_accounts")]
+    pub mint_accounts: Vec<String>,
+    /// Instruction name → action overrides, e.g. `{ "swap_base_in": "buy" }`.
+    #[serde(default)]
+    pub actions: BTreeMap<String, WatchAction>,
+}
+
+fn default_mint_accounts() -> Vec<String> {
+    vec!["mint".to_string()]
+}
+
+/// Decodes one program's instructions from its Anchor IDL.
+pub struct IdlDetailer {
+    program_id: Pubkey,
+    protocol: Protocol,
+    idl: Idl,
+    mint_accounts: Vec<String>,
+    actions: BTreeMap<String, WatchAction>,
+}
+
+impl IdlDetailer {
+    pub fn new(program_id: Pubkey, idl: Idl) -> Self {
+        Self {
+            program_id,
+            protocol: Protocol::from_str(&idl.name).unwrap_or_else(|e| match e {}),
+            idl,
+            mint_accounts: default_mint_accounts(),
+            actions: BTreeMap::new(),
+        }
+    }
+
+    /// Load the IDL file named by `cfg` and apply its overrides.
+    pub fn from_config(cfg: &IdlProgramConfig) -> Result<Self> {
+        let context =
+            |e: String| SolanaStreamError::Configuration(format!("{}: {}", cfg.path.display(), e));
+        let raw = fs::read_to_string(&cfg.path).map_err(|e| context(e.to_string()))?;
+        let idl = Idl::parse(&raw).map_err(|e| context(e.to_string()))?;
+        let program_id = match cfg.program_id.as_deref() {
+            Some(raw) => Pubkey::from_str(raw)
+                .map_err(|_| context(format!("invalid program_id {:?}", raw)))?,
+            None => idl
+                .address
+                .ok_or_else(|| context("IDL has no address; set program_id".to_string()))?,
+        };
+        let mut detailer = Self::new(program_id, idl).with_mint_accounts(cfg.mint_accounts.clone());
+        if let Some(protocol) = cfg.protocol.as_deref() {
+            detailer =
+                detailer.with_protocol(Protocol::from_str(protocol).unwrap_or_else(|e| match e {}));
+        }
+        for (name, action) in &cfg.actions {
+            if detailer.idl.instruction(name).is_none() {
+                return Err(context(format!("actions: unknown instruction `{}`", name)));
+            }
+            detailer = detailer.with_action(name.clone(), *action);
+        }
+        Ok(detailer)
+    }
+
+    pub fn with_protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn with_mint_accounts(mut self, mint_accounts: Vec<String>) -> Self {
+        self.mint_accounts = mint_accounts;
+        self
+    }
+
+    pub fn with_action(mut self, instruction: impl Into<String>, action: WatchAction) -> Self {
+        self.actions.insert(instruction.into(), action);
+        self
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn protocol(&self) -> &Protocol {
+        &self.protocol
+    }
+
+    pub fn idl(&self) -> &Idl {
+        &self.idl
+    }
+
+    /// Action reported for an instruction: explicit overrides first, then the usual
+    /// `create*`/`buy*`/`sell*`/`swap*` naming.
+    pub fn action_for(&self, instruction: &str) -> Option<WatchAction> {
+        if let Some(action) = self.actions.get(instruction) {
+            return Some(*action);
+        }
+        [
+            ("create", WatchAction::Create),
+            ("buy", WatchAction::Buy),
+            ("sell", WatchAction::Sell),
+            ("swap", WatchAction::Trade),
+        ]
+        .into_iter()
+        .find(|(prefix, _)| instruction.starts_with(prefix))
+        .map(|(_, action)| action)
+    }
+
+    /// Decode one instruction given its data and the pubkeys of its accounts, in order.
+    ///
+    /// Returns `None` if no IDL instruction matches the discriminator. Argument decode
+    /// failures still produce an event, with `args` null and `error` set.
+    pub fn decode_instruction(&self, data: &[u8], accounts: &[Pubkey]) -> Option<InstructionEvent> {
+        self.decode_with(data, accounts.iter().copied().map(Some))
+    }
+
+    fn decode_with(
+        &self,
+        data: &[u8],
+        accounts: impl Iterator<Item = Option<Pubkey>>,
+    ) -> Option<InstructionEvent> {
+        let ix = self.idl.match_instruction(data)?;
+        let (args, error) = match self.idl.decode_args(ix, &data[ix.discriminator.len()..]) {
+            Ok(args) => (args, None),
+            Err(e) => (Value::Null, Some(e)),
+        };
+        let accounts = ix
+            .accounts
+            .iter()
+            .zip(accounts)
+            .filter_map(|(item, pubkey)| {
+                Some(NamedAccount {
+                    name: item.name.clone(),
+                    pubkey: pubkey?,
+                    writable: item.writable,
+                    signer: item.signer,
+                })
+            })
+            .collect();
+        Some(InstructionEvent {
+            program_id: self.program_id,
+            protocol: self.protocol.clone(),
+            instruction: ix.name.clone(),
+            args,
+            accounts,
+            error,
+        })
+    }
+}
+
+impl InstructionDecoder for IdlDetailer {
+    fn decode(&self, tx: &VersionedTransaction) -> Vec<InstructionEvent> {
+        let keys = tx.message.static_account_keys();
+        tx.message
+            .instructions()
+            .iter()
+            .filter(|ix| keys.get(ix.program_id_index as usize) == Some(&self.program_id))
+            .filter_map(|ix| {
+                // Accounts from address lookup tables are not resolvable here and are omitted.
+                let accounts = ix.accounts.iter().map(|i| keys.get(*i as usize).copied());
+                self.decode_with(&ix.data, accounts)
+            })
+            .collect()
+    }
+}
+
+impl MintDetailer for IdlDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        _cfg: &ProgramWatchConfig,
+        _mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        let mut out: BTreeMap<Pubkey, MintDetail> = BTreeMap::new();
+        for event in self.decode(tx) {
+            let action = self.action_for(&event.instruction);
+            for name in &self.mint_accounts {
+                let Some(mint) = event.account(name) else {
+                    continue;
+                };
+                if is_system_id(mint) {
+                    continue;
+                }
+                let entry = out.entry(*mint).or_insert_with(|| MintDetail {
+                    protocol: Some(self.protocol.clone()),
+                    ..MintDetail::new(*mint)
+                });
+                if entry.action.is_none() {
+                    entry.action = action;
+                }
+            }
+        }
+        out.into_values().collect()
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> std::result::Result<&'a [u8], String> {
+        if len > self.remaining() {
+            return Err(format!(
+                "needs {} bytes at offset {}, {} left",
+                len,
+                self.pos,
+                self.remaining()
+            ));
+        }
+        let out = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(out)
+    }
+
+    fn array<const N: usize>(&mut self) -> std::result::Result<[u8; N], String> {
+        self.take(N)
+            .map(|b| b.try_into().expect("take returns exactly N bytes"))
+    }
+
+    fn u8(&mut self) -> std::result::Result<u8, String> {
+        self.array::<1>().map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> std::result::Result<u32, String> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    /// Borsh u32 length prefix, rejected up front if it cannot fit in the remaining data.
+    fn len_prefix(&mut self) -> std::result::Result<usize, String> {
+        let len = self.u32()? as usize;
+        if len > self.remaining() {
+            return Err(format!(
+                "length {} exceeds remaining {} bytes",
+                len,
+                self.remaining()
+            ));
+        }
+        Ok(len)
+    }
+}
+
+fn float(v: f64) -> Value {
+    Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+fn array<'v>(value: &'v Value, key: &str) -> std::result::Result<&'v [Value], String> {
+    match value.get(key) {
+        None => Ok(&[]),
+        Some(Value::Array(items)) => Ok(items),
+        Some(_) => Err(format!("`{}` must be an array", key)),
+    }
+}
+
+fn str_field<'v>(value: &'v Value, key: &str, what: &str) -> std::result::Result<&'v str, String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("{} without `{}`", what, key))
+}
+
+fn parse_instruction(ix: &Value, name: &str) -> std::result::Result<IdlInstruction, String> {
+    let discriminator = match ix.get("discriminator") {
+        Some(Value::Array(bytes)) => bytes
+            .iter()
+            .map(|b| {
+                b.as_u64()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| "discriminator must be bytes".to_string())
+            })
+            .collect::<std::result::Result<Vec<u8>, _>>()?,
+        Some(_) => return Err("discriminator must be an array".to_string()),
+        None => anchor_discriminator("global", name).to_vec(),
+    };
+    let mut accounts = Vec::new();
+    flatten_accounts(array(ix, "accounts")?, "", &mut accounts)?;
+    let args = array(ix, "args")?
+        .iter()
+        .map(|arg| {
+            let name = to_snake_case(str_field(arg, "name", "arg")?);
+            let ty = parse_type(arg.get("type").ok_or("arg without `type`")?)
+                .map_err(|e| format!("arg `{}`: {}", name, e))?;
+            Ok((name, ty))
+        })
+        .collect::<std::result::Result<_, String>>()?;
+    Ok(IdlInstruction {
+        name: name.to_string(),
+        discriminator,
+        accounts,
+        args,
+    })
+}
+
+fn flatten_accounts(
+    items: &[Value],
+    prefix: &str,
+    out: &mut Vec<IdlAccountItem>,
+) -> std::result::Result<(), String> {
+    for item in items {
+        let name = format!(
+            "{}{}",
+            prefix,
+            to_snake_case(str_field(item, "name", "account")?)
+        );
+        if let Some(Value::Array(nested)) = item.get("accounts") {
+            flatten_accounts(nested, &format!("{}.", name), out)?;
+            continue;
+        }
+        let flag = |new: &str, legacy: &str| {
+            item.get(new)
+                .or_else(|| item.get(legacy))
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        };
+        out.push(IdlAccountItem {
+            writable: flag("writable", "isMut"),
+            signer: flag("signer", "isSigner"),
+            name,
+        });
+    }
+    Ok(())
+}
+
+fn parse_type(value: &Value) -> std::result::Result<IdlType, String> {
+    match value {
+        Value::String(name) => Ok(match name.as_str() {
+            "bool" => IdlType::Bool,
+            "u8" => IdlType::U8,
+            "i8" => IdlType::I8,
+            "u16" => IdlType::U16,
+            "i16" => IdlType::I16,
+            "u32" => IdlType::U32,
+            "i32" => IdlType::I32,
+            "u64" => IdlType::U64,
+            "i64" => IdlType::I64,
+            "u128" => IdlType::U128,
+            "i128" => IdlType::I128,
+            "f32" => IdlType::F32,
+            "f64" => IdlType::F64,
+            "bytes" => IdlType::Bytes,
+            "string" => IdlType::String,
+            "publicKey" | "pubkey" => IdlType::Pubkey,
+            other => return Err(format!("unsupported type {:?}", other)),
+        }),
+        Value::Object(map) => {
+            let (kind, inner) = map
+                .iter()
+                .next()
+                .filter(|_| map.len() == 1)
+                .ok_or_else(|| format!("unsupported type {}", value))?;
+            Ok(match kind.as_str() {
+                "option" => IdlType::Option(Box::new(parse_type(inner)?)),
+                "coption" => IdlType::COption(Box::new(parse_type(inner)?)),
+                "vec" => IdlType::Vec(Box::new(parse_type(inner)?)),
+                "array" => match inner.as_array().map(Vec::as_slice) {
+                    Some([ty, Value::Number(len)]) => IdlType::Array(
+                        Box::new(parse_type(ty)?),
+                        len.as_u64()
+                            .and_then(|l| usize::try_from(l).ok())
+                            .ok_or("array length must be a non-negative integer")?,
+                    ),
+                    _ => return Err(format!("unsupported array {}", inner)),
+                },
+                "defined" => IdlType::Defined(
+                    inner
+                        .as_str()
+                        .or_else(|| inner.get("name").and_then(Value::as_str))
+                        .ok_or_else(|| format!("unsupported defined {}", inner))?
+                        .to_string(),
+                ),
+                _ => return Err(format!("unsupported type {}", value)),
+            })
+        }
+        _ => Err(format!("unsupported type {}", value)),
+    }
+}
+
+fn parse_type_def(value: &Value) -> std::result::Result<IdlTypeDef, String> {
+    match value.get("kind").and_then(Value::as_str) {
+        Some("struct") => Ok(IdlTypeDef::Struct(parse_fields(value.get("fields"))?)),
+        Some("enum") => array(value, "variants")?
+            .iter()
+            .map(|variant| {
+                Ok((
+                    str_field(variant, "name", "variant")?.to_string(),
+                    parse_fields(variant.get("fields"))?,
+                ))
+            })
+            .collect::<std::result::Result<_, String>>()
+            .map(IdlTypeDef::Enum),
+        Some("type") => Ok(IdlTypeDef::Alias(parse_type(
+            value.get("alias").ok_or("type alias without `alias`")?,
+        )?)),
+        other => Err(format!("unsupported kind {:?}", other)),
+    }
+}
+
+fn parse_fields(fields: Option<&Value>) -> std::result::Result<IdlFields, String> {
+    let items = match fields {
+        None => return Ok(IdlFields::Unit),
+        Some(Value::Array(items)) if items.is_empty() => return Ok(IdlFields::Unit),
+        Some(Value::Array(items)) => items,
+        Some(_) => return Err("`fields` must be an array".to_string()),
+    };
+    if items.iter().all(|f| f.get("name").is_some()) {
+        items
+            .iter()
+            .map(|f| {
+                let name = to_snake_case(str_field(f, "name", "field")?);
+                let ty = parse_type(f.get("type").ok_or("field without `type`")?)
+                    .map_err(|e| format!("field `{}`: {}", name, e))?;
+                Ok((name, ty))
+            })
+            .collect::<std::result::Result<_, String>>()
+            .map(IdlFields::Named)
+    } else {
+        items
+            .iter()
+            .map(parse_type)
+            .collect::<std::result::Result<_, String>>()
+            .map(IdlFields::Tuple)
+    }
+}
+
+/// First 8 bytes of `sha256("<namespace>:<name>")`, as Anchor derives discriminators.
+pub fn anchor_discriminator(namespace: &str, name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    let hash = Sha256::digest(format!("{}:{}", namespace, name).as_bytes());
+    let mut out = [0u8; DISCRIMINATOR_LEN];
+    out.copy_from_slice(&hash[..DISCRIMINATOR_LEN]);
+    out
+}
+
+/// `createPool` / `CreatePool` / `create_pool` → `create_pool`.
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1);
+            let boundary = match prev {
+                Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit() => true,
+                Some(p) if p.is_ascii_uppercase() => next.is_some_and(|n| n.is_ascii_lowercase()),
+                _ => false,
+            };
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(*c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txn::test_support::tx_with;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+
+    const PUMP_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+    // Legacy (pre-0.30) layout: camelCase names, isMut/isSigner, derived discriminators.
+    const LEGACY_IDL: &str = r#"{
+        "version": "0.1.0",
+        "name": "pump",
+        "instructions": [
+            {
+                "name": "buy",
+                "accounts": [
+                    { "name": "global", "isMut": false, "isSigner": false },
+                    { "name": "feeRecipient", "isMut": true, "isSigner": false },
+                    { "name": "mint", "isMut": false, "isSigner": false },
+                    { "name": "user", "isMut": true, "isSigner": true }
+                ],
+                "args": [
+                    { "name": "amount", "type": "u64" },
+                    { "name": "maxSolCost", "type": "u64" }
+                ]
+            }
+        ],
+        "metadata": { "address": "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P" }
+    }"#;
+
+    // 0.30+ layout with explicit discriminators and user-defined types.
+    const MODERN_IDL: &str = r#"{
+        "address": "11111111111111111111111111111112",
+        "metadata": { "name": "demo", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [
+            {
+                "name": "configure",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "authority", "writable": true, "signer": true },
+                    { "name": "pool", "accounts": [
+                        { "name": "state", "writable": true },
+                        { "name": "mint" }
+                    ] }
+                ],
+                "args": [
+                    { "name": "params", "type": { "defined": { "name": "Params" } } },
+                    { "name": "mode", "type": { "defined": { "name": "Mode" } } },
+                    { "name": "label", "type": { "option": "string" } },
+                    { "name": "weights", "type": { "vec": "u16" } },
+                    { "name": "seed", "type": { "array": ["u8", 2] } },
+                    { "name": "big", "type": "u128" }
+                ]
+            }
+        ],
+        "types": [
+            { "name": "Params", "type": { "kind": "struct", "fields": [
+                { "name": "fee_bps", "type": "u16" },
+                { "name": "owner", "type": "pubkey" }
+            ] } },
+            { "name": "Mode", "type": { "kind": "enum", "variants": [
+                { "name": "Off" },
+                { "name": "Limit", "fields": [{ "name": "max", "type": "u64" }] }
+            ] } }
+        ]
+    }"#;
+
+    #[test]
+    fn legacy_discriminators_match_anchor_and_decode_args() {
+        let idl = Idl::parse(LEGACY_IDL).expect("parse legacy idl");
+        let program = Pubkey::from_str(PUMP_PROGRAM).unwrap();
+        assert_eq!(idl.address, Some(program));
+        let buy = idl.instruction("buy").expect("buy");
+        assert_eq!(buy.discriminator, super::super::PUMPFUN_BUY_DISC.to_vec());
+        assert_eq!(buy.accounts[1].name, "fee_recipient");
+
+        let detailer = IdlDetailer::new(program, idl);
+        let mut data = buy_data(1_000, 250_000_000);
+        let keys: Vec<Pubkey> = (1..=4).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let event = detailer.decode_instruction(&data, &keys).expect("decoded");
+        assert_eq!(event.instruction, "buy");
+        assert_eq!(event.protocol, Protocol::Pump);
+        assert_eq!(
+            event.args,
+            serde_json::json!({ "amount": 1000, "max_sol_cost": 250000000 })
+        );
+        assert_eq!(event.account("mint"), Some(&keys[2]));
+        assert!(event.accounts[3].signer && event.accounts[3].writable);
+
+        data.truncate(12);
+        let truncated = detailer
+            .decode_instruction(&data, &keys)
+            .expect("still matched");
+        assert_eq!(truncated.args, Value::Null);
+        assert!(truncated.error.is_some());
+        assert!(detailer.decode_instruction(&[0u8; 24], &keys).is_none());
+    }
+
+    #[test]
+    fn modern_idl_decodes_defined_types_and_nested_accounts() {
+        let idl = Idl::parse(MODERN_IDL).expect("parse modern idl");
+        let program = idl.address.expect("address");
+        let detailer = IdlDetailer::new(program, idl);
+        let owner = Pubkey::new_from_array([9u8; 32]);
+
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        data.extend_from_slice(&30u16.to_le_bytes());
+        data.extend_from_slice(owner.as_ref());
+        data.push(1);
+        data.extend_from_slice(&77u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(b"hi");
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&5u16.to_le_bytes());
+        data.extend_from_slice(&6u16.to_le_bytes());
+        data.extend_from_slice(&[0xaa, 0xbb]);
+        data.extend_from_slice(&(u128::MAX).to_le_bytes());
+
+        let keys: Vec<Pubkey> = (1..=3).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let event = detailer.decode_instruction(&data, &keys).expect("decoded");
+        assert_eq!(event.error, None);
+        assert_eq!(
+            event.args,
+            serde_json::json!({
+                "params": { "fee_bps": 30, "owner": owner.to_string() },
+                "mode": { "Limit": { "max": 77 } },
+                "label": "hi",
+                "weights": [5, 6],
+                "seed": [170, 187],
+                "big": u128::MAX.to_string(),
+            })
+        );
+        let names: Vec<&str> = event.accounts.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["authority", "pool.state", "pool.mint"]);
+        assert_eq!(event.protocol, Protocol::Other("demo".to_string()));
+    }
+
+    #[test]
+    fn detailer_reports_configured_mint_accounts() {
+        let program = Pubkey::from_str(PUMP_PROGRAM).unwrap();
+        let detailer = IdlDetailer::new(program, Idl::parse(LEGACY_IDL).unwrap())
+            .with_protocol(Protocol::Other("pump-idl".to_string()));
+        let mint = Pubkey::new_from_array([7u8; 32]);
+        let user = Pubkey::new_from_array([8u8; 32]);
+        let ix = Instruction::new_with_bytes(
+            program,
+            &buy_data(5, 10),
+            vec![
+                AccountMeta::new_readonly(Pubkey::new_from_array([1u8; 32]), false),
+                AccountMeta::new(Pubkey::new_from_array([2u8; 32]), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(user, true),
+            ],
+        );
+        let tx = tx_with(&user, &[ix]);
+        let cfg = ProgramWatchConfig::new(vec![program], Vec::new());
+
+        let details = detailer.detail(&tx, &cfg, &[]);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].mint, mint);
+        assert_eq!(details[0].action, Some(WatchAction::Buy));
+        assert_eq!(
+            details[0].protocol,
+            Some(Protocol::Other("pump-idl".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_undefined_types() {
+        let raw = MODERN_IDL.replace(r#""name": "Mode", "type""#, r#""name": "Other", "type""#);
+        let err = Idl::parse(&raw).expect_err("Mode is undefined");
+        assert!(err.to_string().contains("undefined type `Mode`"), "{}", err);
+    }
+
+    #[test]
+    fn snake_cases_legacy_names() {
+        assert_eq!(to_snake_case("maxSolCost"), "max_sol_cost");
+        assert_eq!(to_snake_case("CreatePool"), "create_pool");
+        assert_eq!(to_snake_case("swap_base_in"), "swap_base_in");
+        assert_eq!(
+            to_snake_case("initializeAMMConfig"),
+            "initialize_amm_config"
+        );
+    }
+
+    fn buy_data(amount: u64, max_sol_cost: u64) -> Vec<u8> {
+        let mut data = anchor_discriminator("global", "buy").to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&max_sol_cost.to_le_bytes());
+        data
+    }
+}
@@ -0,0 +1,271 @@
+//! Jito tips: system transfers to the tip accounts, tipped-transaction runs ("likely bundles")
+//! and per-slot tip totals.
+//!
+//! Bundles are not marked on chain. Their transactions execute back to back, so consecutive
+//! tipped transactions within one entry are grouped as one likely bundle.
+
+use super::{read_u64_le, serde_b58};
+use serde::{Deserialize, Serialize};
+use solana_entry::entry::Entry;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+use std::collections::BTreeMap;
+
+/// Mainnet Jito tip accounts.
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Slots a [`JitoTipTracker`] waits past a slot before reporting its totals.
+pub const DEFAULT_TIP_SETTLE_SLOTS: u64 = 2;
+
+const SYSTEM_PROGRAM: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+const SYSTEM_TRANSFER: u32 = 2;
+const SYSTEM_TRANSFER_WITH_SEED: u32 = 11;
+
+pub fn default_jito_tip_accounts() -> Vec<Pubkey> {
+    JITO_TIP_ACCOUNTS
+        .iter()
+        .map(|key| Pubkey::from_str_const(key))
+        .collect()
+}
+
+/// Lamports `tx` transfers to `tip_accounts` with top-level System `Transfer` and
+/// `TransferWithSeed` instructions.
+pub fn tip_lamports(tx: &VersionedTransaction, tip_accounts: &[Pubkey]) -> u64 {
+    if tip_accounts.is_empty() {
+        return 0;
+    }
+    let keys = tx.message.static_account_keys();
+    let account = |accounts: &[u8], index: usize| {
+        accounts
+            .get(index)
+            .and_then(|i| keys.get(*i as usize))
+            .copied()
+    };
+    let mut total: u64 = 0;
+    for ix in tx.message.instructions() {
+        if keys.get(ix.program_id_index as usize) != Some(&SYSTEM_PROGRAM) {
+            continue;
+        }
+        let Some(tag) = ix
+            .data
+            .get(..4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        else {
+            continue;
+        };
+        // Transfer { lamports }: from, to. TransferWithSeed { lamports, .. }: from, base, to.
+        let to = match tag {
+            SYSTEM_TRANSFER => account(&ix.accounts, 1),
+            SYSTEM_TRANSFER_WITH_SEED => account(&ix.accounts, 2),
+            _ => continue,
+        };
+        if !to.is_some_and(|to| tip_accounts.contains(&to)) {
+            continue;
+        }
+        if let Some(lamports) = read_u64_le(&ix.data, 4) {
+            total = total.saturating_add(lamports);
+        }
+    }
+    total
+}
+
+/// Consecutive tipped transactions of one entry.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TipBundle {
+    /// Index of the entry within the decoded batch.
+    pub entry_index: usize,
+    #[serde(with = "serde_b58::vec")]
+    pub signatures: Vec<Signature>,
+    pub tip_lamports: u64,
+}
+
+/// Tips seen in one slot (or the part of it decoded so far).
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SlotTips {
+    pub slot: u64,
+    pub tip_lamports: u64,
+    pub tipped_transactions: usize,
+    pub bundles: Vec<TipBundle>,
+}
+
+impl SlotTips {
+    pub fn from_entries(slot: u64, entries: &[Entry], tip_accounts: &[Pubkey]) -> Self {
+        let mut tips = Self {
+            slot,
+            ..Self::default()
+        };
+        for (entry_index, entry) in entries.iter().enumerate() {
+            let mut run: Option<TipBundle> = None;
+            for tx in &entry.transactions {
+                let lamports = tip_lamports(tx, tip_accounts);
+                if lamports == 0 {
+                    tips.bundles.extend(run.take());
+                    continue;
+                }
+                tips.tip_lamports = tips.tip_lamports.saturating_add(lamports);
+                tips.tipped_transactions += 1;
+                let bundle = run.get_or_insert_with(|| TipBundle {
+                    entry_index,
+                    signatures: Vec::new(),
+                    tip_lamports: 0,
+                });
+                bundle.signatures.extend(tx.signatures.first().copied());
+                bundle.tip_lamports = bundle.tip_lamports.saturating_add(lamports);
+            }
+            tips.bundles.extend(run);
+        }
+        tips
+    }
+
+    /// Add another batch of the same slot.
+    pub fn merge(&mut self, other: SlotTips) {
+        self.tip_lamports = self.tip_lamports.saturating_add(other.tip_lamports);
+        self.tipped_transactions += other.tipped_transactions;
+        self.bundles.extend(other.bundles);
+    }
+}
+
+/// Accumulates [`SlotTips`] from partial batches and reports each slot's total once
+/// `settle_slots` newer slots have been seen.
+pub struct JitoTipTracker {
+    settle_slots: u64,
+    latest_slot: u64,
+    slots: BTreeMap<u64, SlotTips>,
+}
+
+impl Default for JitoTipTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIP_SETTLE_SLOTS)
+    }
+}
+
+impl JitoTipTracker {
+    pub fn new(settle_slots: u64) -> Self {
+        Self {
+            settle_slots,
+            latest_slot: 0,
+            slots: BTreeMap::new(),
+        }
+    }
+
+    /// Merge a batch and return the totals of slots that are now settled, oldest first. Batches
+    /// for already settled slots are dropped.
+    pub fn observe(&mut self, tips: SlotTips) -> Vec<SlotTips> {
+        if tips.slot + self.settle_slots < self.latest_slot {
+            return Vec::new();
+        }
+        self.latest_slot = self.latest_slot.max(tips.slot);
+        match self.slots.get_mut(&tips.slot) {
+            Some(current) => current.merge(tips),
+            None => {
+                self.slots.insert(tips.slot, tips);
+            }
+        }
+        let first_open = self.latest_slot.saturating_sub(self.settle_slots);
+        let open = self.slots.split_off(&first_open);
+        std::mem::replace(&mut self.slots, open)
+            .into_values()
+            .collect()
+    }
+
+    /// Totals of every slot still open.
+    pub fn flush(&mut self) -> Vec<SlotTips> {
+        std::mem::take(&mut self.slots).into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txn::test_support::tx_with;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+    };
+
+    const PAYER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+
+    fn transfer(to: Pubkey, lamports: u64) -> Instruction {
+        let mut data = SYSTEM_TRANSFER.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        Instruction::new_with_bytes(
+            SYSTEM_PROGRAM,
+            &data,
+            vec![AccountMeta::new(PAYER, true), AccountMeta::new(to, false)],
+        )
+    }
+
+    fn tip_tx(lamports: u64) -> VersionedTransaction {
+        let tip = Pubkey::from_str_const(JITO_TIP_ACCOUNTS[3]);
+        tx_with(&PAYER, &[transfer(tip, lamports)])
+    }
+
+    fn plain_tx() -> VersionedTransaction {
+        let other = Pubkey::new_from_array([9u8; 32]);
+        tx_with(
+            &PAYER,
+            &[
+                transfer(other, 5),
+                Instruction::new_with_bytes(other, &[], Vec::new()),
+            ],
+        )
+    }
+
+    fn entry(transactions: Vec<VersionedTransaction>) -> Entry {
+        Entry {
+            num_hashes: 1,
+            hash: Hash::default(),
+            transactions,
+        }
+    }
+
+    #[test]
+    fn tips_group_into_runs_per_entry() {
+        let tips = default_jito_tip_accounts();
+        assert_eq!(tip_lamports(&tip_tx(10_000), &tips), 10_000);
+        assert_eq!(tip_lamports(&plain_tx(), &tips), 0);
+        assert_eq!(tip_lamports(&tip_tx(10_000), &[]), 0);
+
+        let entries = [
+            entry(vec![tip_tx(1), tip_tx(2), plain_tx(), tip_tx(4)]),
+            entry(vec![plain_tx()]),
+            entry(vec![tip_tx(8)]),
+        ];
+        let slot = SlotTips::from_entries(7, &entries, &tips);
+        assert_eq!((slot.tip_lamports, slot.tipped_transactions), (15, 4));
+        let runs: Vec<_> = slot
+            .bundles
+            .iter()
+            .map(|b| (b.entry_index, b.signatures.len(), b.tip_lamports))
+            .collect();
+        assert_eq!(runs, [(0, 2, 3), (0, 1, 4), (2, 1, 8)]);
+    }
+
+    #[test]
+    fn tracker_reports_settled_slots_once() {
+        let tips = default_jito_tip_accounts();
+        let batch =
+            |slot, lamports| SlotTips::from_entries(slot, &[entry(vec![tip_tx(lamports)])], &tips);
+        let mut tracker = JitoTipTracker::new(1);
+
+        assert!(tracker.observe(batch(10, 100)).is_empty());
+        assert!(tracker.observe(batch(10, 50)).is_empty());
+        assert!(tracker.observe(batch(11, 1)).is_empty());
+        let settled = tracker.observe(batch(12, 1));
+        assert_eq!(settled.len(), 1);
+        assert_eq!((settled[0].slot, settled[0].tip_lamports), (10, 150));
+        assert_eq!(settled[0].bundles.len(), 2);
+        // Late batch for a settled slot is dropped.
+        assert!(tracker.observe(batch(10, 7)).is_empty());
+        let rest: Vec<_> = tracker.flush().iter().map(|t| t.slot).collect();
+        assert_eq!(rest, [11, 12]);
+    }
+}
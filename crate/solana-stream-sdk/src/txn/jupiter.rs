@@ -0,0 +1,430 @@
+//! Jupiter v6 aggregator routes: `route`, `shared_accounts_route`, `exact_out_route` and
+//! `shared_accounts_exact_out_route`.
+//!
+//! The underlying AMM calls are CPIs, so a shred-level watcher only sees the Jupiter
+//! instruction. Each transaction yields at most one [`RouteDetail`]: the first route's input
+//! side, the last route's output side and every route plan step in between.
+//!
+//! Amounts and slippage are read from the fixed tail of the instruction data, so they survive
+//! route plan steps this module cannot decode; those only cut the `venues` list short.
+
+use super::{
+    amm::anchor_disc, read_u64_le, token_account_mints, MintDetail, MintDetailer, MintFinder,
+    MintInfo, ProgramWatchConfig, Protocol, RouteDetail, RouteStep,
+};
+use solana_sdk::{
+    message::compiled_instruction::CompiledInstruction, pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use std::collections::BTreeMap;
+
+pub const JUPITER_V6_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+pub(super) const JUPITER_V6: Pubkey = Pubkey::from_str_const(JUPITER_V6_PROGRAM_ID);
+
+const ROUTE_DISC: [u8; 8] = [0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a];
+const SHARED_ACCOUNTS_ROUTE_DISC: [u8; 8] = [0xc1, 0x20, 0x9b, 0x33, 0x41, 0xd6, 0x9c, 0x81];
+const EXACT_OUT_ROUTE_DISC: [u8; 8] = [0xd0, 0x33, 0xef, 0x97, 0x7b, 0x2b, 0xed, 0x5c];
+const SHARED_ACCOUNTS_EXACT_OUT_ROUTE_DISC: [u8; 8] =
+    [0xb0, 0xd1, 0x69, 0xa8, 0x9a, 0x7d, 0x45, 0x3e];
+
+/// `amount (u64), quoted_amount (u64), slippage_bps (u16), platform_fee_bps (u8)`.
+const ARGS_TAIL_LEN: usize = 19;
+
+/// Payload of a `Swap` route step variant after its tag.
+#[derive(Clone, Copy)]
+enum Payload {
+    Fixed(usize),
+    /// `a_to_b: bool, remaining_accounts_info: Option<{ slices: Vec<(u8, u8)> }>`.
+    WhirlpoolSwapV2,
+}
+
+use Payload::Fixed;
+
+/// `Swap` enum variants of the v6 IDL, by tag.
+const SWAP_VARIANTS: &[(&str, Payload)] = &[
+    ("Saber", Fixed(0)),
+    ("SaberAddDecimalsDeposit", Fixed(0)),
+    ("SaberAddDecimalsWithdraw", Fixed(0)),
+    ("TokenSwap", Fixed(0)),
+    ("Sencha", Fixed(0)),
+    ("Step", Fixed(0)),
+    ("Cropper", Fixed(0)),
+    ("Raydium", Fixed(0)),
+    ("Crema", Fixed(1)),
+    ("Lifinity", Fixed(0)),
+    ("Mercurial", Fixed(0)),
+    ("Cykura", Fixed(0)),
+    ("Serum", Fixed(1)),
+    ("MarinadeDeposit", Fixed(0)),
+    ("MarinadeUnstake", Fixed(0)),
+    ("Aldrin", Fixed(1)),
+    ("AldrinV2", Fixed(1)),
+    ("Whirlpool", Fixed(1)),
+    ("Invariant", Fixed(1)),
+    ("Meteora", Fixed(0)),
+    ("GooseFX", Fixed(0)),
+    ("DeltaFi", Fixed(1)),
+    ("Balansol", Fixed(0)),
+    ("MarcoPolo", Fixed(1)),
+    ("Dradex", Fixed(1)),
+    ("LifinityV2", Fixed(0)),
+    ("RaydiumClmm", Fixed(0)),
+    ("Openbook", Fixed(1)),
+    ("Phoenix", Fixed(1)),
+    ("Symmetry", Fixed(16)),
+    ("TokenSwapV2", Fixed(0)),
+    ("HeliumTreasuryManagementRedeemV0", Fixed(0)),
+    ("StakeDexStakeWrappedSol", Fixed(0)),
+    ("StakeDexSwapViaStake", Fixed(4)),
+    ("GooseFXV2", Fixed(0)),
+    ("Perps", Fixed(0)),
+    ("PerpsAddLiquidity", Fixed(0)),
+    ("PerpsRemoveLiquidity", Fixed(0)),
+    ("MeteoraDlmm", Fixed(0)),
+    ("OpenBookV2", Fixed(1)),
+    ("RaydiumClmmV2", Fixed(0)),
+    ("StakeDexPrefundWithdrawStakeAndDepositStake", Fixed(4)),
+    ("Clone", Fixed(3)),
+    ("SanctumS", Fixed(10)),
+    ("SanctumSAddLiquidity", Fixed(5)),
+    ("SanctumSRemoveLiquidity", Fixed(5)),
+    ("RaydiumCP", Fixed(0)),
+    ("WhirlpoolSwapV2", Payload::WhirlpoolSwapV2),
+    ("OneIntro", Fixed(0)),
+    ("PumpdotfunWrappedBuy", Fixed(0)),
+    ("PumpdotfunWrappedSell", Fixed(0)),
+    ("PerpsV2", Fixed(0)),
+    ("PerpsV2AddLiquidity", Fixed(0)),
+    ("PerpsV2RemoveLiquidity", Fixed(0)),
+    ("MoonshotWrappedBuy", Fixed(0)),
+    ("MoonshotWrappedSell", Fixed(0)),
+    ("StabbleStableSwap", Fixed(0)),
+    ("StabbleWeightedSwap", Fixed(0)),
+    ("Obric", Fixed(1)),
+    ("FoxBuyFromEstimatedCost", Fixed(0)),
+    ("FoxClaimPartial", Fixed(1)),
+    ("SolFi", Fixed(1)),
+    ("SolayerDelegateNoInit", Fixed(0)),
+    ("SolayerUndelegateNoInit", Fixed(0)),
+    ("TokenMill", Fixed(1)),
+    ("DaosFunBuy", Fixed(0)),
+    ("DaosFunSell", Fixed(0)),
+    ("ZeroFi", Fixed(0)),
+    ("StakeDexWithdrawWrappedSol", Fixed(0)),
+    ("VirtualsBuy", Fixed(0)),
+    ("VirtualsSell", Fixed(0)),
+    ("Perena", Fixed(2)),
+    ("PumpdotfunAmmBuy", Fixed(0)),
+    ("PumpdotfunAmmSell", Fixed(0)),
+    ("Gamma", Fixed(0)),
+];
+
+/// Decode `Vec<RoutePlanStep>` at `offset`, stopping at the first undecodable step.
+fn route_plan(data: &[u8], offset: usize) -> Vec<RouteStep> {
+    let mut steps = Vec::new();
+    let Some(count) = data
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    else {
+        return steps;
+    };
+    let mut pos = offset + 4;
+    for _ in 0..count {
+        let Some((venue, payload)) = data
+            .get(pos)
+            .and_then(|tag| SWAP_VARIANTS.get(*tag as usize))
+        else {
+            break;
+        };
+        pos += 1;
+        pos += match payload {
+            Fixed(len) => *len,
+            Payload::WhirlpoolSwapV2 => match data.get(pos + 1) {
+                Some(0) => 2,
+                Some(1) => {
+                    let Some(len) = data.get(pos + 2..pos + 6) else {
+                        break;
+                    };
+                    6 + 2 * u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize
+                }
+                _ => break,
+            },
+        };
+        let Some(&[percent, input_index, output_index]) = data.get(pos..pos + 3) else {
+            break;
+        };
+        pos += 3;
+        steps.push(RouteStep {
+            venue: (*venue).to_string(),
+            percent,
+            input_index,
+            output_index,
+        });
+    }
+    steps
+}
+
+fn decode(
+    keys: &[Pubkey],
+    ix: &CompiledInstruction,
+    token_accounts: &mut Option<BTreeMap<Pubkey, Pubkey>>,
+    tx: &VersionedTransaction,
+) -> Option<RouteDetail> {
+    let data = &ix.data;
+    let account = |index: usize| {
+        ix.accounts
+            .get(index)
+            .and_then(|i| keys.get(*i as usize))
+            .copied()
+    };
+    // (route plan offset, exact_out, source mint, destination mint)
+    let (plan_offset, exact_out, input_mint, output_mint) = match anchor_disc(data)? {
+        // route: token_program, user_transfer_authority, user_source_token_account,
+        // user_destination_token_account, destination_token_account, destination_mint, ...
+        ROUTE_DISC => {
+            let input = account(2).and_then(|source| {
+                token_accounts
+                    .get_or_insert_with(|| token_account_mints(tx))
+                    .get(&source)
+                    .copied()
+            });
+            (8, false, input, account(5))
+        }
+        // shared_accounts_route(id: u8, ..): ..., source_mint, destination_mint at [7]/[8].
+        SHARED_ACCOUNTS_ROUTE_DISC => (9, false, account(7), account(8)),
+        // exact_out_route: ..., source_mint, destination_mint at [5]/[6].
+        EXACT_OUT_ROUTE_DISC => (8, true, account(5), account(6)),
+        SHARED_ACCOUNTS_EXACT_OUT_ROUTE_DISC => (9, true, account(7), account(8)),
+        _ => return None,
+    };
+    let tail = data.len().checked_sub(ARGS_TAIL_LEN)?;
+    if tail < plan_offset + 4 {
+        return None;
+    }
+    let amount = read_u64_le(data, tail)?;
+    let quoted = read_u64_le(data, tail + 8)?;
+    let (amount_in, amount_out) = if exact_out {
+        (quoted, amount)
+    } else {
+        (amount, quoted)
+    };
+    Some(RouteDetail {
+        input_mint,
+        output_mint,
+        amount_in,
+        amount_out,
+        exact_out,
+        slippage_bps: u16::from_le_bytes([data[tail + 16], data[tail + 17]]),
+        venues: route_plan(&data[..tail], plan_offset),
+    })
+}
+
+/// The transaction's Jupiter routes folded into one: input side of the first, output side of
+/// the last, all venues in order, and the widest slippage.
+fn route_detail(program_ids: &[Pubkey], tx: &VersionedTransaction) -> Option<RouteDetail> {
+    let keys = tx.message.static_account_keys();
+    let mut token_accounts = None;
+    let mut routes = tx.message.instructions().iter().filter_map(|ix| {
+        keys.get(ix.program_id_index as usize)
+            .filter(|id| program_ids.contains(id))
+            .and_then(|_| decode(keys, ix, &mut token_accounts, tx))
+    });
+    let first = routes.next()?;
+    Some(routes.fold(first, |mut acc, next| {
+        acc.output_mint = next.output_mint;
+        acc.amount_out = next.amount_out;
+        acc.exact_out |= next.exact_out;
+        acc.slippage_bps = acc.slippage_bps.max(next.slippage_bps);
+        acc.venues.extend(next.venues);
+        acc
+    }))
+}
+
+fn mint_detail(program_ids: &[Pubkey], tx: &VersionedTransaction) -> Option<MintDetail> {
+    route_detail(program_ids, tx)?.to_mint_detail(Protocol::Jupiter)
+}
+
+/// Jupiter finder: reports the traded mint of the aggregated route.
+pub struct JupiterMintFinder {
+    program_ids: Vec<Pubkey>,
+}
+
+impl JupiterMintFinder {
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self { program_ids }
+    }
+}
+
+impl Default for JupiterMintFinder {
+    fn default() -> Self {
+        Self::new(vec![JUPITER_V6])
+    }
+}
+
+impl MintFinder for JupiterMintFinder {
+    fn find_mints(&self, tx: &VersionedTransaction, _cfg: &ProgramWatchConfig) -> Vec<MintInfo> {
+        mint_detail(&self.program_ids, tx)
+            .map(|detail| MintInfo {
+                mint: detail.mint,
+                protocol: detail.protocol,
+                action: detail.action,
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Jupiter detailer: one aggregated swap detail per transaction.
+pub struct JupiterDetailer {
+    program_ids: Vec<Pubkey>,
+}
+
+impl JupiterDetailer {
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self { program_ids }
+    }
+}
+
+impl Default for JupiterDetailer {
+    fn default() -> Self {
+        Self::new(vec![JUPITER_V6])
+    }
+}
+
+impl MintDetailer for JupiterDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        _cfg: &ProgramWatchConfig,
+        _mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        mint_detail(&self.program_ids, tx).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{
+            test_support::{program_ix, tx_with},
+            Amount, AmountBound, WatchAction, WSOL,
+        },
+    };
+
+    const TOKEN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const USER: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const USDC: Pubkey = Pubkey::new_from_array([6u8; 32]);
+
+    /// `(tag, payload)` steps with percent 100 and chained indices, followed by the tail args.
+    fn route_args(steps: &[(u8, &[u8])], amount: u64, quoted: u64, slippage_bps: u16) -> Vec<u8> {
+        let mut data = (steps.len() as u32).to_le_bytes().to_vec();
+        for (n, (tag, payload)) in steps.iter().enumerate() {
+            data.push(*tag);
+            data.extend_from_slice(payload);
+            data.extend_from_slice(&[100, n as u8, n as u8 + 1]);
+        }
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&quoted.to_le_bytes());
+        data.extend_from_slice(&slippage_bps.to_le_bytes());
+        data.push(0);
+        data
+    }
+
+    #[test]
+    fn shared_accounts_route_reports_buy_with_min_out() {
+        // SOL -> USDC via Whirlpool, USDC -> TOKEN via WhirlpoolSwapV2 with remaining accounts.
+        let mut args = vec![3u8];
+        args.extend(route_args(
+            &[(17, &[1]), (47, &[0, 1, 1, 0, 0, 0, 0, 2])],
+            1_000_000_000,
+            50_000,
+            100,
+        ));
+        let ix = program_ix(
+            JUPITER_V6,
+            &SHARED_ACCOUNTS_ROUTE_DISC,
+            &args,
+            13,
+            &[(7, WSOL), (8, TOKEN)],
+        );
+        let tx = tx_with(&USER, &[ix]);
+        let cfg = ProgramWatchConfig::new(vec![JUPITER_V6], Vec::new());
+
+        let events = collect_watch_events(5, &[&tx], &cfg, 0);
+        assert_eq!(events.len(), 1);
+        let detail = &events[0].details[0];
+        assert_eq!(detail.mint, TOKEN);
+        assert_eq!(detail.protocol, Some(Protocol::Jupiter));
+        assert_eq!(detail.action, Some(WatchAction::Buy));
+        assert_eq!(detail.sol_amount, Some(Amount::lamports(1_000_000_000)));
+        assert_eq!(
+            detail.token_amount,
+            Some(Amount::token(49_500).with_bound(AmountBound::Min))
+        );
+        let route = detail.route.as_ref().unwrap();
+        assert_eq!((route.amount_out, route.slippage_bps), (50_000, 100));
+        let venues: Vec<_> = route.venues.iter().map(|s| s.venue.as_str()).collect();
+        assert_eq!(venues, ["Whirlpool", "WhirlpoolSwapV2"]);
+    }
+
+    #[test]
+    fn exact_out_route_reports_sell_with_max_in() {
+        let ix = program_ix(
+            JUPITER_V6,
+            &EXACT_OUT_ROUTE_DISC,
+            &route_args(&[(38, &[])], 2_000_000_000, 10_000, 50),
+            11,
+            &[(5, TOKEN), (6, WSOL)],
+        );
+        let tx = tx_with(&USER, &[ix]);
+        let cfg = ProgramWatchConfig::new(vec![JUPITER_V6], Vec::new());
+
+        let details = JupiterDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].action, Some(WatchAction::Sell));
+        assert_eq!(details[0].sol_amount, Some(Amount::lamports(2_000_000_000)));
+        assert_eq!(
+            details[0].token_amount,
+            Some(Amount::token(10_050).with_bound(AmountBound::Max))
+        );
+        assert!(details[0].route.as_ref().unwrap().exact_out);
+    }
+
+    #[test]
+    fn routes_fold_into_one_and_unknown_venues_keep_amounts() {
+        // `route` does not pass the source mint; the second leg's venue tag is unknown.
+        let first = program_ix(
+            JUPITER_V6,
+            &ROUTE_DISC,
+            &route_args(&[(26, &[])], 700, 650, 30),
+            9,
+            &[(5, USDC)],
+        );
+        let second = program_ix(
+            JUPITER_V6,
+            &ROUTE_DISC,
+            &route_args(&[(250, &[])], 650, 9_000, 80),
+            9,
+            &[(5, TOKEN)],
+        );
+        let tx = tx_with(&USER, &[first, second]);
+        let cfg = ProgramWatchConfig::new(vec![JUPITER_V6], Vec::new());
+
+        let details = JupiterDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].mint, TOKEN);
+        assert_eq!(details[0].action, Some(WatchAction::Trade));
+        let route = details[0].route.as_ref().unwrap();
+        assert_eq!(route.input_mint, None);
+        assert_eq!((route.amount_in, route.amount_out), (700, 9_000));
+        assert_eq!(route.slippage_bps, 80);
+        assert_eq!(route.venues.len(), 1);
+        assert_eq!(route.venues[0].venue, "RaydiumClmm");
+
+        let mints = JupiterMintFinder::default().find_mints(&tx, &cfg);
+        assert_eq!(mints.len(), 1);
+        assert_eq!(mints[0].mint, TOKEN);
+    }
+}
@@ -0,0 +1,483 @@
+//! Transaction status meta for feeds that carry it (Geyser, RPC).
+//!
+//! Shreds only carry the signed transaction, so everything here is optional: detection runs
+//! without meta and uses it, when present, to see CPI-invoked instructions, report executed
+//! amounts instead of instruction limits and mark failed transactions.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    message::{compiled_instruction::CompiledInstruction, VersionedMessage},
+    pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+
+use super::{serde_b58, token_account_mints, Amount, MintDetail, WSOL};
+
+/// Status meta of one executed transaction.
+///
+/// Account indexes address the transaction's keys in runtime order: static keys, then keys
+/// loaded from lookup tables (writable, then readonly).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxMeta {
+    /// Execution error; `None` for a successful transaction.
+    pub err: Option<String>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    pub inner_instructions: Vec<InnerInstructions>,
+    pub pre_token_balances: Vec<TokenBalance>,
+    pub post_token_balances: Vec<TokenBalance>,
+    pub log_messages: Vec<String>,
+}
+
+/// Instructions invoked via CPI by the top-level instruction at `index`, in execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InnerInstructions {
+    pub index: u8,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+/// Token account balance before or after execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub account_index: u8,
+    pub mint: Pubkey,
+    pub owner: Option<Pubkey>,
+    /// Raw amount before applying `decimals`.
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+/// What a transaction actually did, from its meta.
+///
+/// Serialized under `outcome` in a [`WatchEvent`](crate::shreds_udp::WatchEvent); only
+/// accounts whose balance changed are listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxOutcome {
+    pub failed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub fee: u64,
+    #[serde(default)]
+    pub sol_changes: Vec<SolChange>,
+    #[serde(default)]
+    pub token_changes: Vec<TokenChange>,
+    /// Lamports left as rent in token accounts created by the transaction (ATAs, temporary
+    /// WSOL accounts that were not closed).
+    #[serde(default)]
+    pub token_account_rent: u64,
+}
+
+/// Lamport balance of one account before and after execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolChange {
+    #[serde(with = "serde_b58")]
+    pub account: Pubkey,
+    pub pre: u64,
+    pub post: u64,
+}
+
+impl SolChange {
+    pub fn delta(&self) -> i128 {
+        i128::from(self.post) - i128::from(self.pre)
+    }
+}
+
+/// Balance of one mint held by one owner, summed over the owner's token accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenChange {
+    #[serde(with = "serde_b58")]
+    pub owner: Pubkey,
+    #[serde(with = "serde_b58")]
+    pub mint: Pubkey,
+    pub pre: u64,
+    pub post: u64,
+    pub decimals: u8,
+}
+
+impl TokenChange {
+    pub fn delta(&self) -> i128 {
+        i128::from(self.post) - i128::from(self.pre)
+    }
+}
+
+impl TxMeta {
+    pub fn failed(&self) -> bool {
+        self.err.is_some()
+    }
+
+    /// `tx` with each top-level instruction followed by the instructions it invoked via CPI,
+    /// so detectors see swaps routed through aggregators or other programs. Signatures and
+    /// header are unchanged; returns `None` when nothing was invoked.
+    pub fn with_inner_instructions(
+        &self,
+        tx: &VersionedTransaction,
+    ) -> Option<VersionedTransaction> {
+        if self
+            .inner_instructions
+            .iter()
+            .all(|set| set.instructions.is_empty())
+        {
+            return None;
+        }
+        let mut instructions = Vec::new();
+        for (index, ix) in tx.message.instructions().iter().enumerate() {
+            instructions.push(ix.clone());
+            for set in &self.inner_instructions {
+                if usize::from(set.index) == index {
+                    instructions.extend(set.instructions.iter().cloned());
+                }
+            }
+        }
+        let mut message = tx.message.clone();
+        match &mut message {
+            VersionedMessage::Legacy(message) => message.instructions = instructions,
+            VersionedMessage::V0(message) => message.instructions = instructions,
+        }
+        Some(VersionedTransaction {
+            signatures: tx.signatures.clone(),
+            message,
+        })
+    }
+
+    /// Balance changes of `tx`. `account_keys` are its keys in runtime order, loaded addresses
+    /// included (see [`account_keys_from_geyser`](crate::geyser::account_keys_from_geyser)),
+    /// which the meta's balance indexes address.
+    pub fn outcome(&self, tx: &VersionedTransaction, account_keys: &[Pubkey]) -> TxOutcome {
+        let keys = account_keys;
+        let resolved = with_resolved_keys(tx, account_keys);
+        let created = token_account_mints(resolved.as_ref().unwrap_or(tx));
+        let token_account_rent = keys
+            .iter()
+            .enumerate()
+            .filter_map(|(index, account)| {
+                let mint = created.get(account)?;
+                let (0, post) = (
+                    *self.pre_balances.get(index)?,
+                    *self.post_balances.get(index)?,
+                ) else {
+                    return None;
+                };
+                // A WSOL account's lamports also hold the wrapped SOL.
+                let wrapped = self
+                    .post_token_balances
+                    .iter()
+                    .find(|b| *mint == WSOL && usize::from(b.account_index) == index)
+                    .map_or(0, |b| b.amount);
+                Some(post.saturating_sub(wrapped))
+            })
+            .fold(0u64, u64::saturating_add);
+        let sol_changes = self
+            .pre_balances
+            .iter()
+            .zip(&self.post_balances)
+            .zip(keys)
+            .filter(|((pre, post), _)| pre != post)
+            .map(|((pre, post), account)| SolChange {
+                account: *account,
+                pre: *pre,
+                post: *post,
+            })
+            .collect();
+
+        let mut token_changes: BTreeMap<(Pubkey, Pubkey), TokenChange> = BTreeMap::new();
+        for (balances, post) in [
+            (&self.pre_token_balances, false),
+            (&self.post_token_balances, true),
+        ] {
+            for balance in balances {
+                let Some(owner) = balance.owner else {
+                    continue;
+                };
+                let change = token_changes
+                    .entry((owner, balance.mint))
+                    .or_insert(TokenChange {
+                        owner,
+                        mint: balance.mint,
+                        pre: 0,
+                        post: 0,
+                        decimals: balance.decimals,
+                    });
+                if post {
+                    change.post = change.post.saturating_add(balance.amount);
+                } else {
+                    change.pre = change.pre.saturating_add(balance.amount);
+                }
+            }
+        }
+
+        TxOutcome {
+            failed: self.failed(),
+            error: self.err.clone(),
+            fee: self.fee,
+            sol_changes,
+            token_changes: token_changes
+                .into_values()
+                .filter(|change| change.pre != change.post)
+                .collect(),
+            token_account_rent,
+        }
+    }
+}
+
+/// Copy of `tx` whose static keys are `account_keys` (static keys, then loaded addresses) and
+/// without lookups, so detectors reading `static_account_keys()` resolve every instruction
+/// account. The header no longer describes writability: the copy is only read, never
+/// sanitized or sent. `None` when `account_keys` are just the static keys.
+pub(crate) fn with_resolved_keys(
+    tx: &VersionedTransaction,
+    account_keys: &[Pubkey],
+) -> Option<VersionedTransaction> {
+    let VersionedMessage::V0(message) = &tx.message else {
+        return None;
+    };
+    if message.account_keys == account_keys {
+        return None;
+    }
+    let mut message = message.clone();
+    message.account_keys = account_keys.to_vec();
+    message.address_table_lookups.clear();
+    Some(VersionedTransaction {
+        signatures: tx.signatures.clone(),
+        message: VersionedMessage::V0(message),
+    })
+}
+
+impl TxOutcome {
+    fn token_delta(&self, owner: &Pubkey, mint: &Pubkey) -> i128 {
+        self.token_changes
+            .iter()
+            .filter(|change| change.owner == *owner && change.mint == *mint)
+            .map(TokenChange::delta)
+            .sum()
+    }
+
+    /// Net SOL moved by `owner`: its lamport change with the fee added back, plus its wrapped
+    /// SOL change. Tips and rent paid for accounts created in the transaction are included;
+    /// [`traded_sol`](Self::traded_sol) leaves them out.
+    pub fn sol_delta(&self, owner: &Pubkey) -> i128 {
+        let lamports: i128 = self
+            .sol_changes
+            .iter()
+            .filter(|change| change.account == *owner)
+            .map(SolChange::delta)
+            .sum();
+        let fee = if self.sol_changes.first().map(|c| c.account) == Some(*owner) {
+            i128::from(self.fee)
+        } else {
+            0
+        };
+        lamports + fee + self.token_delta(owner, &WSOL)
+    }
+
+    /// Lamports received by `tip_accounts` (see
+    /// [`ProgramWatchConfig::jito_tip_accounts`](super::ProgramWatchConfig::jito_tip_accounts)).
+    pub fn tips(&self, tip_accounts: &[Pubkey]) -> u64 {
+        self.sol_changes
+            .iter()
+            .filter(|change| tip_accounts.contains(&change.account))
+            .map(|change| change.post.saturating_sub(change.pre))
+            .fold(0, u64::saturating_add)
+    }
+
+    /// SOL `owner` traded: [`sol_delta`](Self::sol_delta) without the tips paid to
+    /// `tip_accounts` and the rent of token accounts created in the transaction, both assumed
+    /// to be paid by `owner`.
+    pub fn traded_sol(&self, owner: &Pubkey, tip_accounts: &[Pubkey]) -> i128 {
+        self.sol_delta(owner)
+            + i128::from(self.tips(tip_accounts))
+            + i128::from(self.token_account_rent)
+    }
+
+    /// Replace instruction-limit amounts of buy/sell details with what `owner` (normally the
+    /// fee payer) executed, the SOL side from [`traded_sol`](Self::traded_sol). Failed
+    /// transactions moved nothing and are left untouched. The SOL side is only set when a
+    /// single mint was traded, since a route through several mints cannot be split from
+    /// balances alone.
+    pub fn apply_executed_amounts(
+        &self,
+        details: &mut [MintDetail],
+        owner: &Pubkey,
+        tip_accounts: &[Pubkey],
+    ) {
+        if self.failed {
+            return;
+        }
+        let trades = details
+            .iter()
+            .filter(|d| d.mint != WSOL && d.action.is_some_and(|a| a.is_trade()))
+            .count();
+        let sol = self.traded_sol(owner, tip_accounts).unsigned_abs();
+        for detail in details
+            .iter_mut()
+            .filter(|d| d.mint != WSOL && d.action.is_some_and(|a| a.is_trade()))
+        {
+            let token = self.token_delta(owner, &detail.mint).unsigned_abs();
+            if token > 0 {
+                detail.token_amount = u64::try_from(token).ok().map(Amount::token);
+            }
+            if trades == 1 && sol > 0 {
+                detail.sol_amount = u64::try_from(sol).ok().map(Amount::lamports);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txn::{test_support::tx_with, AmountBound, WatchAction};
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+
+    const PAYER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const ROUTER: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([3u8; 32]);
+
+    fn balance(mint: Pubkey, amount: u64) -> TokenBalance {
+        TokenBalance {
+            account_index: 1,
+            mint,
+            owner: Some(PAYER),
+            amount,
+            decimals: 6,
+        }
+    }
+
+    #[test]
+    fn inner_instructions_follow_their_parent() {
+        let tx = tx_with(
+            &PAYER,
+            &[
+                Instruction::new_with_bytes(ROUTER, &[1], Vec::new()),
+                Instruction::new_with_bytes(ROUTER, &[2], Vec::new()),
+            ],
+        );
+        assert_eq!(TxMeta::default().with_inner_instructions(&tx), None);
+        let cpi = CompiledInstruction {
+            program_id_index: 1,
+            accounts: Vec::new(),
+            data: vec![9],
+        };
+        let meta = TxMeta {
+            inner_instructions: vec![InnerInstructions {
+                index: 0,
+                instructions: vec![cpi],
+            }],
+            ..TxMeta::default()
+        };
+        let flat = meta.with_inner_instructions(&tx).unwrap();
+        let data: Vec<_> = flat
+            .message
+            .instructions()
+            .iter()
+            .map(|ix| ix.data.clone())
+            .collect();
+        assert_eq!(data, vec![vec![1], vec![9], vec![2]]);
+        assert_eq!(flat.signatures, tx.signatures);
+    }
+
+    #[test]
+    fn executed_amounts_replace_limits() {
+        let tx = tx_with(
+            &PAYER,
+            &[Instruction::new_with_bytes(ROUTER, &[1], Vec::new())],
+        );
+        let meta = TxMeta {
+            fee: 5_000,
+            pre_balances: vec![10_000_000, 0],
+            post_balances: vec![7_995_000, 0],
+            pre_token_balances: vec![balance(MINT, 0)],
+            post_token_balances: vec![balance(MINT, 42_000)],
+            ..TxMeta::default()
+        };
+        let outcome = meta.outcome(&tx, tx.message.static_account_keys());
+        assert!(!outcome.failed);
+        assert_eq!(outcome.sol_changes.len(), 1);
+        assert_eq!(outcome.token_changes[0].delta(), 42_000);
+        assert_eq!(outcome.sol_delta(&PAYER), -2_000_000);
+
+        let mut details = vec![MintDetail {
+            action: Some(WatchAction::Buy),
+            sol_amount: Some(Amount::lamports(3_000_000).with_bound(AmountBound::Max)),
+            token_amount: Some(Amount::token(40_000)),
+            ..MintDetail::new(MINT)
+        }];
+        outcome.apply_executed_amounts(&mut details, &PAYER, &[]);
+        assert_eq!(details[0].sol_amount, Some(Amount::lamports(2_000_000)));
+        assert_eq!(details[0].token_amount, Some(Amount::token(42_000)));
+
+        let failed = TxMeta {
+            err: Some("InstructionError(0, Custom(6002))".to_string()),
+            ..meta
+        }
+        .outcome(&tx, tx.message.static_account_keys());
+        let mut limits = details.clone();
+        limits[0].sol_amount = Some(Amount::lamports(1));
+        failed.apply_executed_amounts(&mut limits, &PAYER, &[]);
+        assert!(failed.failed);
+        assert_eq!(limits[0].sol_amount, Some(Amount::lamports(1)));
+    }
+
+    #[test]
+    fn executed_sol_excludes_tips_and_created_token_account_rent() {
+        let tip = Pubkey::new_from_array([4u8; 32]);
+        let ata = Pubkey::new_from_array([5u8; 32]);
+        let create_ata = Instruction::new_with_bytes(
+            super::super::ASSOCIATED_TOKEN_PROGRAM,
+            &[1],
+            vec![
+                AccountMeta::new(PAYER, true),
+                AccountMeta::new(ata, false),
+                AccountMeta::new_readonly(PAYER, false),
+                AccountMeta::new_readonly(MINT, false),
+            ],
+        );
+        let tx = tx_with(
+            &PAYER,
+            &[
+                create_ata,
+                Instruction::new_with_bytes(ROUTER, &[1], vec![AccountMeta::new(ata, false)]),
+                // System Transfer of the tip; the system program id is all zeros.
+                Instruction::new_with_bytes(
+                    Pubkey::default(),
+                    &[&2u32.to_le_bytes()[..], &100_000u64.to_le_bytes()].concat(),
+                    vec![AccountMeta::new(PAYER, true), AccountMeta::new(tip, false)],
+                ),
+            ],
+        );
+        let keys = tx.message.static_account_keys();
+        let index = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap();
+        let mut pre_balances = vec![0; keys.len()];
+        let mut post_balances = vec![0; keys.len()];
+        pre_balances[index(&PAYER)] = 10_000_000;
+        post_balances[index(&PAYER)] = 10_000_000 - 2_000_000 - 100_000 - 2_039_280 - 5_000;
+        pre_balances[index(&tip)] = 1_000_000;
+        post_balances[index(&tip)] = 1_100_000;
+        post_balances[index(&ata)] = 2_039_280;
+        let meta = TxMeta {
+            fee: 5_000,
+            pre_balances,
+            post_balances,
+            post_token_balances: vec![TokenBalance {
+                account_index: u8::try_from(index(&ata)).unwrap(),
+                ..balance(MINT, 42_000)
+            }],
+            ..TxMeta::default()
+        };
+        let outcome = meta.outcome(&tx, tx.message.static_account_keys());
+        assert_eq!(outcome.token_account_rent, 2_039_280);
+        assert_eq!(outcome.tips(&[tip]), 100_000);
+        assert_eq!(outcome.sol_delta(&PAYER), -4_139_280);
+        assert_eq!(outcome.traded_sol(&PAYER, &[tip]), -2_000_000);
+
+        let mut details = vec![MintDetail {
+            action: Some(WatchAction::Buy),
+            ..MintDetail::new(MINT)
+        }];
+        outcome.apply_executed_amounts(&mut details, &PAYER, &[tip]);
+        assert_eq!(details[0].sol_amount, Some(Amount::lamports(2_000_000)));
+        assert_eq!(details[0].token_amount, Some(Amount::token(42_000)));
+    }
+}
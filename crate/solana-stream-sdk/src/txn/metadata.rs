@@ -0,0 +1,216 @@
+//! Token name/symbol/uri carried in instruction data, so create events are readable without an
+//! RPC lookup.
+//!
+//! Sources: the pump.fun `create`/`create_v2` args (read by
+//! [`PumpfunDetailer`](super::PumpfunDetailer)), Metaplex Token Metadata
+//! `CreateMetadataAccountV2/V3` and `Create`, and the SPL token-metadata interface `Initialize`
+//! (the Token-2022 metadata extension).
+
+use super::{MintDetail, MintDetailer, MintInfo, MintMetadata, ProgramWatchConfig};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use std::collections::BTreeMap;
+
+pub const METAPLEX_TOKEN_METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+const METAPLEX_TOKEN_METADATA: Pubkey = Pubkey::from_str_const(METAPLEX_TOKEN_METADATA_PROGRAM_ID);
+
+const METAPLEX_CREATE_METADATA_ACCOUNT_V2: u8 = 16;
+const METAPLEX_CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const METAPLEX_CREATE: u8 = 42;
+/// `spl_token_metadata_interface:initialize_account`.
+const TOKEN_METADATA_INITIALIZE_DISC: [u8; 8] = [0xd2, 0xe1, 0x1e, 0xa2, 0x58, 0xb8, 0x4d, 0x8d];
+
+/// Borsh `String` (u32 length + UTF-8) at `offset`, with the offset just past it. Metaplex pads
+/// names to a fixed width with NULs; those are trimmed.
+fn read_borsh_string(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let len = data
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)?;
+    let start = offset + 4;
+    let bytes = data.get(start..start.checked_add(len)?)?;
+    let text = String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_string();
+    Some((text, start + len))
+}
+
+/// Consecutive borsh `name`, `symbol`, `uri` at `offset`.
+pub(super) fn read_name_symbol_uri(data: &[u8], offset: usize) -> Option<MintMetadata> {
+    let (name, offset) = read_borsh_string(data, offset)?;
+    let (symbol, offset) = read_borsh_string(data, offset)?;
+    let (uri, _) = read_borsh_string(data, offset)?;
+    let non_empty = |s: String| (!s.is_empty()).then_some(s);
+    Some(MintMetadata {
+        name: non_empty(name),
+        symbol: non_empty(symbol),
+        uri: non_empty(uri),
+    })
+}
+
+/// `(mint account index, metadata)` for a Metaplex or token-metadata interface instruction.
+fn decode(program_id: &Pubkey, data: &[u8]) -> Option<(usize, MintMetadata)> {
+    if *program_id == METAPLEX_TOKEN_METADATA {
+        // V2/V3: metadata, mint, ..., args start with DataV2 { name, symbol, uri, .. }.
+        // Create: metadata, master_edition, mint, ..., CreateArgs::V1 { asset_data: { name, .. } }.
+        return match *data.first()? {
+            METAPLEX_CREATE_METADATA_ACCOUNT_V2 | METAPLEX_CREATE_METADATA_ACCOUNT_V3 => {
+                Some((1, read_name_symbol_uri(data, 1)?))
+            }
+            METAPLEX_CREATE if data.get(1) == Some(&0) => Some((2, read_name_symbol_uri(data, 2)?)),
+            _ => None,
+        };
+    }
+    // The interface can be served by any program (Token-2022 for the metadata extension), so
+    // only the discriminator is checked. Accounts: metadata, update_authority, mint, ...
+    if data.get(..8)? == TOKEN_METADATA_INITIALIZE_DISC {
+        return Some((2, read_name_symbol_uri(data, 8)?));
+    }
+    None
+}
+
+/// Metadata detailer: attaches name/symbol/uri to the mint they describe. Details carry no
+/// action or protocol and only fill in what other detailers report for the same mint.
+#[derive(Default)]
+pub struct TokenMetadataDetailer;
+
+impl MintDetailer for TokenMetadataDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        _cfg: &ProgramWatchConfig,
+        _mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        let keys = tx.message.static_account_keys();
+        let mut out: BTreeMap<Pubkey, MintDetail> = BTreeMap::new();
+        for ix in tx.message.instructions() {
+            let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            let Some((mint_index, metadata)) = decode(program_id, &ix.data) else {
+                continue;
+            };
+            let Some(mint) = ix
+                .accounts
+                .get(mint_index)
+                .and_then(|i| keys.get(*i as usize))
+            else {
+                continue;
+            };
+            out.entry(*mint)
+                .or_insert_with(|| MintDetail::new(*mint))
+                .metadata
+                .fill_missing(&metadata);
+        }
+        out.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{
+            test_support::{program_ix, tx_with},
+            WatchAction, DEFAULT_PUMPFUN_PROGRAM_ID, PUMPFUN_CREATE_DISC,
+        },
+    };
+    use std::str::FromStr;
+
+    const MINT: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const USER: Pubkey = Pubkey::new_from_array([3u8; 32]);
+
+    fn borsh_strings(prefix: &[u8], fields: &[&str]) -> Vec<u8> {
+        let mut data = prefix.to_vec();
+        for field in fields {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn pumpfun_create_event_carries_metadata() {
+        let pump = Pubkey::from_str(DEFAULT_PUMPFUN_PROGRAM_ID).unwrap();
+        let mut data = borsh_strings(
+            &PUMPFUN_CREATE_DISC,
+            &["Shred Cat", "SCAT", "https://x/y.json"],
+        );
+        data.extend_from_slice(USER.as_ref());
+        let tx = tx_with(&USER, &[program_ix(pump, &data, &[], 6, &[(0, MINT)])]);
+        let cfg = ProgramWatchConfig::new(vec![pump], Vec::new());
+
+        let events = collect_watch_events(1, &[&tx], &cfg, 0);
+        let detail = &events[0].details[0];
+        assert_eq!(detail.action, Some(WatchAction::Create));
+        assert_eq!(detail.metadata.name.as_deref(), Some("Shred Cat"));
+        assert_eq!(detail.metadata.symbol.as_deref(), Some("SCAT"));
+        assert_eq!(detail.metadata.uri.as_deref(), Some("https://x/y.json"));
+    }
+
+    #[test]
+    fn metaplex_and_token_2022_metadata_decode() {
+        let cfg = ProgramWatchConfig::new(Vec::new(), Vec::new());
+        // Metaplex pads the name with NULs; the trailing DataV2 fields are not needed.
+        let mut v3 = borsh_strings(
+            &[METAPLEX_CREATE_METADATA_ACCOUNT_V3],
+            &["Padded\0\0\0\0", "PAD", ""],
+        );
+        v3.extend_from_slice(&[0xf4, 0x01, 0, 0, 0, 1, 0]);
+        let details = TokenMetadataDetailer.detail(
+            &tx_with(
+                &USER,
+                &[program_ix(
+                    METAPLEX_TOKEN_METADATA,
+                    &v3,
+                    &[],
+                    6,
+                    &[(1, MINT)],
+                )],
+            ),
+            &cfg,
+            &[],
+        );
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].mint, MINT);
+        assert_eq!(details[0].action, None);
+        assert_eq!(
+            details[0].metadata,
+            MintMetadata {
+                name: Some("Padded".to_string()),
+                symbol: Some("PAD".to_string()),
+                uri: None,
+            }
+        );
+
+        let token_2022 = Pubkey::from_str(super::super::TOKEN_2022_PROGRAM_ID).unwrap();
+        let init = borsh_strings(&TOKEN_METADATA_INITIALIZE_DISC, &["Ext", "EXT", "ipfs://e"]);
+        let details = TokenMetadataDetailer.detail(
+            &tx_with(
+                &USER,
+                &[program_ix(token_2022, &init, &[], 6, &[(2, MINT)])],
+            ),
+            &cfg,
+            &[],
+        );
+        assert_eq!(details[0].metadata.uri.as_deref(), Some("ipfs://e"));
+
+        // Truncated strings are ignored rather than guessed.
+        let truncated = borsh_strings(&[METAPLEX_CREATE_METADATA_ACCOUNT_V3], &["Name"]);
+        assert!(TokenMetadataDetailer
+            .detail(
+                &tx_with(
+                    &USER,
+                    &[program_ix(
+                        METAPLEX_TOKEN_METADATA,
+                        &truncated,
+                        &[],
+                        6,
+                        &[(1, MINT)]
+                    )]
+                ),
+                &cfg,
+                &[]
+            )
+            .is_empty());
+    }
+}
@@ -0,0 +1,488 @@
+//! Meteora DLMM and dynamic AMM (DAMM v1/v2) instructions: pool creation, swaps and liquidity
+//! changes.
+//!
+//! DLMM and DAMM v2 pass both pool mints on every instruction; swap direction comes from the
+//! user's input/output token account, matched against the mints when it was created in the
+//! same transaction or is the signer's associated token account. DAMM v1 swaps and balanced
+//! deposits/withdrawals only take token accounts, so their mints are resolved with
+//! [`token_account_mints`](super::token_account_mints) and otherwise not reported.
+
+use super::{
+    amm::{anchor_disc, AmmCall, AmmDetailer, AmmMintFinder, AmmProgram, InstructionView},
+    read_u64_le, Amount, AmountBound, Protocol, WatchAction,
+};
+use solana_sdk::pubkey::Pubkey;
+
+pub const METEORA_DLMM_PROGRAM_ID: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo";
+pub const METEORA_DAMM_V1_PROGRAM_ID: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
+pub const METEORA_DAMM_V2_PROGRAM_ID: &str = "cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG";
+const METEORA_DLMM: Pubkey = Pubkey::from_str_const(METEORA_DLMM_PROGRAM_ID);
+const METEORA_DAMM_V1: Pubkey = Pubkey::from_str_const(METEORA_DAMM_V1_PROGRAM_ID);
+const METEORA_DAMM_V2: Pubkey = Pubkey::from_str_const(METEORA_DAMM_V2_PROGRAM_ID);
+
+const DLMM_INITIALIZE_LB_PAIR_DISC: [u8; 8] = [0x2d, 0x9a, 0xed, 0xd2, 0xdd, 0x0f, 0xa6, 0x5c];
+const DLMM_INITIALIZE_LB_PAIR2_DISC: [u8; 8] = [0x49, 0x3b, 0x24, 0x78, 0xed, 0x53, 0x6c, 0xc6];
+const DLMM_INITIALIZE_CUSTOMIZABLE_LB_PAIR_DISC: [u8; 8] =
+    [0x2e, 0x27, 0x29, 0x87, 0x6f, 0xb7, 0xc8, 0x40];
+const DLMM_INITIALIZE_CUSTOMIZABLE_LB_PAIR2_DISC: [u8; 8] =
+    [0xf3, 0x49, 0x81, 0x7e, 0x33, 0x13, 0xf1, 0x6b];
+const DLMM_SWAP_DISC: [u8; 8] = [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+const DLMM_SWAP2_DISC: [u8; 8] = [0x41, 0x4b, 0x3f, 0x4c, 0xeb, 0x5b, 0x5b, 0x88];
+const DLMM_SWAP_EXACT_OUT_DISC: [u8; 8] = [0xfa, 0x49, 0x65, 0x21, 0x26, 0xcf, 0x4b, 0xb8];
+const DLMM_SWAP_EXACT_OUT2_DISC: [u8; 8] = [0x2b, 0xd7, 0xf7, 0x84, 0x89, 0x3c, 0xf3, 0x51];
+const DLMM_ADD_LIQUIDITY_DISC: [u8; 8] = [0xb5, 0x9d, 0x59, 0x43, 0x8f, 0xb6, 0x34, 0x48];
+const DLMM_ADD_LIQUIDITY2_DISC: [u8; 8] = [0xe4, 0xa2, 0x4e, 0x1c, 0x46, 0xdb, 0x74, 0x73];
+const DLMM_ADD_LIQUIDITY_BY_WEIGHT_DISC: [u8; 8] = [0x1c, 0x8c, 0xee, 0x63, 0xe7, 0xa2, 0x15, 0x95];
+const DLMM_ADD_LIQUIDITY_BY_STRATEGY_DISC: [u8; 8] =
+    [0x07, 0x03, 0x96, 0x7f, 0x94, 0x28, 0x3d, 0xc8];
+const DLMM_ADD_LIQUIDITY_BY_STRATEGY2_DISC: [u8; 8] =
+    [0x03, 0xdd, 0x95, 0xda, 0x6f, 0x8d, 0x76, 0xd5];
+const DLMM_REMOVE_LIQUIDITY_DISC: [u8; 8] = [0x50, 0x55, 0xd1, 0x48, 0x18, 0xce, 0xb1, 0x6c];
+const DLMM_REMOVE_LIQUIDITY2_DISC: [u8; 8] = [0xe6, 0xd7, 0x52, 0x7f, 0xf1, 0x65, 0xe3, 0x92];
+const DLMM_REMOVE_LIQUIDITY_BY_RANGE_DISC: [u8; 8] =
+    [0x1a, 0x52, 0x66, 0x98, 0xf0, 0x4a, 0x69, 0x1a];
+const DLMM_REMOVE_LIQUIDITY_BY_RANGE2_DISC: [u8; 8] =
+    [0xcc, 0x02, 0xc3, 0x91, 0x35, 0x91, 0x91, 0xcd];
+const REMOVE_ALL_LIQUIDITY_DISC: [u8; 8] = [0x0a, 0x33, 0x3d, 0x23, 0x70, 0x69, 0x18, 0x55];
+
+const DAMM_V1_INITIALIZE_PERMISSIONLESS_POOL_DISC: [u8; 8] =
+    [0x76, 0xad, 0x29, 0x9d, 0xad, 0x48, 0x61, 0x67];
+const DAMM_V1_INITIALIZE_PERMISSIONLESS_POOL_WITH_FEE_TIER_DISC: [u8; 8] =
+    [0x06, 0x87, 0x44, 0x93, 0xe5, 0x52, 0xa9, 0x71];
+const DAMM_V1_INITIALIZE_CONSTANT_PRODUCT_POOL_WITH_CONFIG_DISC: [u8; 8] =
+    [0x07, 0xa6, 0x8a, 0xab, 0xce, 0xab, 0xec, 0xf4];
+const DAMM_V1_INITIALIZE_CONSTANT_PRODUCT_POOL_WITH_CONFIG2_DISC: [u8; 8] =
+    [0x30, 0x95, 0xdc, 0x82, 0x3d, 0x0b, 0x09, 0xb2];
+const DAMM_V1_INITIALIZE_CUSTOMIZABLE_CONSTANT_PRODUCT_POOL_DISC: [u8; 8] =
+    [0x91, 0x18, 0xac, 0xc2, 0xdb, 0x7d, 0x03, 0xbe];
+const DAMM_V1_ADD_BALANCE_LIQUIDITY_DISC: [u8; 8] =
+    [0xa8, 0xe3, 0x32, 0x3e, 0xbd, 0xab, 0x54, 0xb0];
+const DAMM_V1_REMOVE_BALANCE_LIQUIDITY_DISC: [u8; 8] =
+    [0x85, 0x6d, 0x2c, 0xb3, 0x38, 0xee, 0x72, 0x21];
+// DAMM v1 and v2 both name their swap `swap`, same as DLMM.
+const SWAP_DISC: [u8; 8] = DLMM_SWAP_DISC;
+
+const DAMM_V2_INITIALIZE_POOL_DISC: [u8; 8] = [0x5f, 0xb4, 0x0a, 0xac, 0x54, 0xae, 0xe8, 0x28];
+const DAMM_V2_INITIALIZE_POOL_WITH_DYNAMIC_CONFIG_DISC: [u8; 8] =
+    [0x95, 0x52, 0x48, 0xc5, 0xfd, 0xfc, 0x44, 0x0f];
+const DAMM_V2_INITIALIZE_CUSTOMIZABLE_POOL_DISC: [u8; 8] =
+    [0x14, 0xa1, 0xf1, 0x18, 0xbd, 0xdd, 0xb4, 0x02];
+const DAMM_V2_SWAP2_DISC: [u8; 8] = DLMM_SWAP2_DISC;
+const DAMM_V2_ADD_LIQUIDITY_DISC: [u8; 8] = DLMM_ADD_LIQUIDITY_DISC;
+const DAMM_V2_REMOVE_LIQUIDITY_DISC: [u8; 8] = DLMM_REMOVE_LIQUIDITY_DISC;
+
+/// Which Meteora program a watched program id speaks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MeteoraProgram {
+    Dlmm,
+    DammV1,
+    DammV2,
+}
+
+impl MeteoraProgram {
+    pub const ALL: [MeteoraProgram; 3] = [Self::Dlmm, Self::DammV1, Self::DammV2];
+
+    /// Mainnet program id.
+    pub fn program_id(self) -> Pubkey {
+        match self {
+            Self::Dlmm => METEORA_DLMM,
+            Self::DammV1 => METEORA_DAMM_V1,
+            Self::DammV2 => METEORA_DAMM_V2,
+        }
+    }
+
+    pub fn from_program_id(id: &Pubkey) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.program_id() == *id)
+    }
+
+    pub fn protocol(self) -> Protocol {
+        match self {
+            Self::Dlmm => Protocol::MeteoraDlmm,
+            Self::DammV1 => Protocol::MeteoraDammV1,
+            Self::DammV2 => Protocol::MeteoraDammV2,
+        }
+    }
+}
+
+impl AmmProgram for MeteoraProgram {
+    const PROGRAMS: &'static [Self] = &Self::ALL;
+
+    fn program_id(self) -> Pubkey {
+        MeteoraProgram::program_id(self)
+    }
+
+    fn protocol(self) -> Protocol {
+        MeteoraProgram::protocol(self)
+    }
+
+    fn decode(self, ix: &InstructionView<'_>) -> Option<AmmCall> {
+        match self {
+            Self::Dlmm => decode_dlmm(ix),
+            Self::DammV1 => decode_damm_v1(ix),
+            Self::DammV2 => decode_damm_v2(ix),
+        }
+    }
+}
+
+/// Account indices of a swap whose pool passes both mints.
+struct SwapAccounts {
+    pool: usize,
+    user_in: usize,
+    user_out: usize,
+    owner: usize,
+    /// `(mint, token program)` for each side of the pool.
+    mints: [(usize, usize); 2],
+}
+
+/// Swap whose direction is read from the user's input (or output) token account.
+fn directed_swap(
+    ix: &InstructionView<'_>,
+    accounts: SwapAccounts,
+    amount_in: (usize, AmountBound),
+    amount_out: (usize, AmountBound),
+) -> Option<AmmCall> {
+    let [x, y] = accounts.mints.map(|(mint, _)| ix.get(mint));
+    let other = |mint: Pubkey| if Some(mint) == x { y } else { x };
+    let (input, output) =
+        if let Some(mint) = ix.held_mint(accounts.user_in, accounts.owner, accounts.mints) {
+            (Some(mint), other(mint))
+        } else if let Some(mint) = ix.held_mint(accounts.user_out, accounts.owner, accounts.mints) {
+            (other(mint), Some(mint))
+        } else {
+            (None, None)
+        };
+    ix.swap(
+        (ix.get(accounts.pool), input, output),
+        amount_in,
+        amount_out,
+    )
+}
+
+fn decode_dlmm(ix: &InstructionView<'_>) -> Option<AmmCall> {
+    use AmountBound::{Exact, Max, Min};
+    // lb_pair, bin_array_bitmap_extension, reserve_x, reserve_y, user_token_in, user_token_out,
+    // token_x_mint, token_y_mint, oracle, host_fee_in, user, token_x_program, token_y_program
+    let swap_accounts = SwapAccounts {
+        pool: 0,
+        user_in: 4,
+        user_out: 5,
+        owner: 10,
+        mints: [(6, 11), (7, 12)],
+    };
+    match anchor_disc(ix.data())? {
+        // initialize_lb_pair*(..): lb_pair at [0], token_mint_x/y at [2]/[3]. Liquidity comes
+        // with separate add_liquidity instructions.
+        DLMM_INITIALIZE_LB_PAIR_DISC
+        | DLMM_INITIALIZE_LB_PAIR2_DISC
+        | DLMM_INITIALIZE_CUSTOMIZABLE_LB_PAIR_DISC
+        | DLMM_INITIALIZE_CUSTOMIZABLE_LB_PAIR2_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(0), ix.get(2), ix.get(3)),
+            None,
+        ),
+        // swap(amount_in, min_amount_out)
+        DLMM_SWAP_DISC | DLMM_SWAP2_DISC => directed_swap(ix, swap_accounts, (8, Exact), (16, Min)),
+        // swap_exact_out(max_in_amount, out_amount)
+        DLMM_SWAP_EXACT_OUT_DISC | DLMM_SWAP_EXACT_OUT2_DISC => {
+            directed_swap(ix, swap_accounts, (8, Max), (16, Exact))
+        }
+        // add_liquidity*(amount_x, amount_y, ..): position, lb_pair at [1], ..., mints at
+        // [7]/[8] in every variant.
+        DLMM_ADD_LIQUIDITY_DISC
+        | DLMM_ADD_LIQUIDITY2_DISC
+        | DLMM_ADD_LIQUIDITY_BY_WEIGHT_DISC
+        | DLMM_ADD_LIQUIDITY_BY_STRATEGY_DISC
+        | DLMM_ADD_LIQUIDITY_BY_STRATEGY2_DISC => ix.pool(
+            WatchAction::AddLiquidity,
+            (ix.get(1), ix.get(7), ix.get(8)),
+            Some((8, 16, Max)),
+        ),
+        // remove_liquidity* take per-bin basis points, so there are no token amounts.
+        DLMM_REMOVE_LIQUIDITY_DISC
+        | DLMM_REMOVE_LIQUIDITY2_DISC
+        | DLMM_REMOVE_LIQUIDITY_BY_RANGE_DISC
+        | DLMM_REMOVE_LIQUIDITY_BY_RANGE2_DISC
+        | REMOVE_ALL_LIQUIDITY_DISC => ix.pool(
+            WatchAction::RemoveLiquidity,
+            (ix.get(1), ix.get(7), ix.get(8)),
+            None,
+        ),
+        _ => None,
+    }
+}
+
+fn decode_damm_v1(ix: &InstructionView<'_>) -> Option<AmmCall> {
+    use AmountBound::{Exact, Max, Min};
+    match anchor_disc(ix.data())? {
+        // initialize_permissionless_pool(curve_type, token_a_amount, token_b_amount); pool at
+        // [0], mints at [2]/[3]. Amounts follow the curve enum, read for ConstantProduct (tag 0)
+        // only; the fee-tier variant has trade_fee_bps in between.
+        disc @ (DAMM_V1_INITIALIZE_PERMISSIONLESS_POOL_DISC
+        | DAMM_V1_INITIALIZE_PERMISSIONLESS_POOL_WITH_FEE_TIER_DISC) => {
+            let offset = if disc == DAMM_V1_INITIALIZE_PERMISSIONLESS_POOL_DISC {
+                9
+            } else {
+                17
+            };
+            let amounts = (*ix.data().get(8)? == 0).then_some((offset, offset + 8, Exact));
+            ix.pool(
+                WatchAction::CreatePool,
+                (ix.get(0), ix.get(2), ix.get(3)),
+                amounts,
+            )
+        }
+        // *_with_config(token_a_amount, token_b_amount, ..): pool, config, lp_mint, mints at
+        // [3]/[4].
+        DAMM_V1_INITIALIZE_CONSTANT_PRODUCT_POOL_WITH_CONFIG_DISC
+        | DAMM_V1_INITIALIZE_CONSTANT_PRODUCT_POOL_WITH_CONFIG2_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(0), ix.get(3), ix.get(4)),
+            Some((8, 16, Exact)),
+        ),
+        // initialize_customizable_permissionless_constant_product_pool(token_a_amount,
+        // token_b_amount, params): pool, lp_mint, mints at [2]/[3].
+        DAMM_V1_INITIALIZE_CUSTOMIZABLE_CONSTANT_PRODUCT_POOL_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(0), ix.get(2), ix.get(3)),
+            Some((8, 16, Exact)),
+        ),
+        // swap(in_amount, minimum_out_amount): pool, user_source_token, user_destination_token.
+        SWAP_DISC => ix.swap(
+            (ix.get(0), ix.mint_of(1), ix.mint_of(2)),
+            (8, Exact),
+            (16, Min),
+        ),
+        // add/remove_balance_liquidity(pool_token_amount, token_a limit, token_b limit); user
+        // token accounts at [11]/[12].
+        disc @ (DAMM_V1_ADD_BALANCE_LIQUIDITY_DISC | DAMM_V1_REMOVE_BALANCE_LIQUIDITY_DISC) => {
+            let (action, bound) = if disc == DAMM_V1_ADD_BALANCE_LIQUIDITY_DISC {
+                (WatchAction::AddLiquidity, Max)
+            } else {
+                (WatchAction::RemoveLiquidity, Min)
+            };
+            let mut call = ix.pool(
+                action,
+                (ix.get(0), ix.mint_of(11), ix.mint_of(12)),
+                Some((16, 24, bound)),
+            )?;
+            if let AmmCall::Pool { pool, .. } = &mut call {
+                pool.lp_amount = read_u64_le(ix.data(), 8).map(Amount::token);
+            }
+            Some(call)
+        }
+        _ => None,
+    }
+}
+
+fn decode_damm_v2(ix: &InstructionView<'_>) -> Option<AmmCall> {
+    use AmountBound::{Exact, Max, Min};
+    // pool_authority, pool at [1], input/output token accounts at [2]/[3], vaults, mints at
+    // [6]/[7], payer at [8], token programs at [9]/[10].
+    let swap_accounts = SwapAccounts {
+        pool: 1,
+        user_in: 2,
+        user_out: 3,
+        owner: 8,
+        mints: [(6, 9), (7, 10)],
+    };
+    match anchor_disc(ix.data())? {
+        // Pool creation takes liquidity/sqrt_price (u128) rather than token amounts.
+        DAMM_V2_INITIALIZE_POOL_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(6), ix.get(8), ix.get(9)),
+            None,
+        ),
+        DAMM_V2_INITIALIZE_POOL_WITH_DYNAMIC_CONFIG_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(7), ix.get(9), ix.get(10)),
+            None,
+        ),
+        DAMM_V2_INITIALIZE_CUSTOMIZABLE_POOL_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(5), ix.get(7), ix.get(8)),
+            None,
+        ),
+        // swap(amount_in, minimum_amount_out)
+        SWAP_DISC => directed_swap(ix, swap_accounts, (8, Exact), (16, Min)),
+        // swap2(amount_0, amount_1, swap_mode): ExactIn/PartialFill read like swap, ExactOut (2)
+        // is (amount_out, maximum_amount_in).
+        DAMM_V2_SWAP2_DISC => match *ix.data().get(24)? {
+            2 => directed_swap(ix, swap_accounts, (16, Max), (8, Exact)),
+            _ => directed_swap(ix, swap_accounts, (8, Exact), (16, Min)),
+        },
+        // add_liquidity(liquidity_delta: u128, token_a_amount_threshold, token_b_amount_threshold);
+        // pool at [0], mints at [6]/[7].
+        DAMM_V2_ADD_LIQUIDITY_DISC => ix.pool(
+            WatchAction::AddLiquidity,
+            (ix.get(0), ix.get(6), ix.get(7)),
+            Some((24, 32, Max)),
+        ),
+        // remove_liquidity has the same args; pool at [1], mints at [7]/[8].
+        DAMM_V2_REMOVE_LIQUIDITY_DISC => ix.pool(
+            WatchAction::RemoveLiquidity,
+            (ix.get(1), ix.get(7), ix.get(8)),
+            Some((24, 32, Min)),
+        ),
+        // remove_all_liquidity(token_a_amount_threshold, token_b_amount_threshold)
+        REMOVE_ALL_LIQUIDITY_DISC => ix.pool(
+            WatchAction::RemoveLiquidity,
+            (ix.get(1), ix.get(7), ix.get(8)),
+            Some((8, 16, Min)),
+        ),
+        _ => None,
+    }
+}
+
+/// Meteora finder: reports the token side of each pool or swap touched.
+pub type MeteoraMintFinder = AmmMintFinder<MeteoraProgram>;
+
+/// Meteora detailer: pool, mints and amount limits for DLMM and DAMM v1/v2 instructions.
+pub type MeteoraDetailer = AmmDetailer<MeteoraProgram>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{
+            test_support::{program_ix, tx_with, u64_args},
+            AmountBound, MintDetailer, MintFinder, ProgramWatchConfig, WSOL,
+        },
+    };
+    use solana_sdk::instruction::Instruction;
+
+    const POOL: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const TOKEN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const USER: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const TOKEN_PROGRAM: Pubkey =
+        Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+    fn ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[owner.as_ref(), TOKEN_PROGRAM.as_ref(), mint.as_ref()],
+            &super::super::ASSOCIATED_TOKEN_PROGRAM,
+        )
+        .0
+    }
+
+    fn dlmm_swap(disc: [u8; 8], args: &[u64], user_in: Pubkey, user_out: Pubkey) -> Instruction {
+        let accounts = [
+            (0, POOL),
+            (4, user_in),
+            (5, user_out),
+            (6, TOKEN),
+            (7, WSOL),
+            (10, USER),
+            (11, TOKEN_PROGRAM),
+            (12, TOKEN_PROGRAM),
+        ];
+        program_ix(METEORA_DLMM, &disc, &u64_args(args), 15, &accounts)
+    }
+
+    #[test]
+    fn dlmm_swap_direction_from_signer_ata() {
+        // Paying from the WSOL ATA: a buy of TOKEN.
+        let tx = tx_with(
+            &USER,
+            &[dlmm_swap(
+                DLMM_SWAP_DISC,
+                &[400_000_000, 1_000],
+                ata(&USER, &WSOL),
+                ata(&USER, &TOKEN),
+            )],
+        );
+        let cfg = ProgramWatchConfig::new(vec![METEORA_DLMM], Vec::new());
+
+        let events = collect_watch_events(3, &[&tx], &cfg, 0);
+        assert_eq!(events.len(), 1);
+        let detail = &events[0].details[0];
+        assert_eq!(detail.mint, TOKEN);
+        assert_eq!(detail.protocol, Some(Protocol::MeteoraDlmm));
+        assert_eq!(detail.action, Some(WatchAction::Buy));
+        assert_eq!(detail.lamports(), Some(400_000_000));
+        assert_eq!(
+            detail.token_amount,
+            Some(Amount::token(1_000).with_bound(AmountBound::Min))
+        );
+
+        // Exact-out sell: only the output (WSOL) account is recognisable.
+        let tx = tx_with(
+            &USER,
+            &[dlmm_swap(
+                DLMM_SWAP_EXACT_OUT2_DISC,
+                &[5_000, 90_000_000],
+                Pubkey::new_from_array([9; 32]),
+                ata(&USER, &WSOL),
+            )],
+        );
+        let details = MeteoraDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details[0].action, Some(WatchAction::Sell));
+        assert_eq!(
+            details[0].token_amount,
+            Some(Amount::token(5_000).with_bound(AmountBound::Max))
+        );
+        assert_eq!(details[0].sol_amount, Some(Amount::lamports(90_000_000)));
+    }
+
+    #[test]
+    fn dlmm_liquidity_and_unresolved_swap() {
+        let add = program_ix(
+            METEORA_DLMM,
+            &DLMM_ADD_LIQUIDITY_BY_STRATEGY_DISC,
+            &u64_args(&[7_000, 3_000_000_000]),
+            16,
+            &[(1, POOL), (7, TOKEN), (8, WSOL)],
+        );
+        // Neither token account can be tied to a mint.
+        let swap = dlmm_swap(
+            DLMM_SWAP_DISC,
+            &[1, 1],
+            Pubkey::new_from_array([8; 32]),
+            Pubkey::new_from_array([9; 32]),
+        );
+        let tx = tx_with(&USER, &[add, swap]);
+        let cfg = ProgramWatchConfig::new(vec![METEORA_DLMM], Vec::new());
+
+        let details = MeteoraDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].action, Some(WatchAction::AddLiquidity));
+        let pool = details[0].pool.as_ref().unwrap();
+        assert_eq!((pool.pool, pool.base_mint), (POOL, TOKEN));
+        assert_eq!(
+            pool.quote_amount,
+            Some(Amount::lamports(3_000_000_000).with_bound(AmountBound::Max))
+        );
+    }
+
+    #[test]
+    fn damm_pool_creation() {
+        let v1 = program_ix(
+            METEORA_DAMM_V1,
+            &DAMM_V1_INITIALIZE_CONSTANT_PRODUCT_POOL_WITH_CONFIG2_DISC,
+            &u64_args(&[1_000_000_000, 20_000_000_000]),
+            20,
+            &[(0, POOL), (3, TOKEN), (4, WSOL)],
+        );
+        let tx = tx_with(&USER, &[v1]);
+        let cfg = ProgramWatchConfig::new(vec![METEORA_DAMM_V1], Vec::new());
+        let details = MeteoraDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details[0].protocol, Some(Protocol::MeteoraDammV1));
+        assert_eq!(details[0].action, Some(WatchAction::CreatePool));
+        assert_eq!(details[0].token_amount, Some(Amount::token(1_000_000_000)));
+        assert_eq!(details[0].lamports(), Some(20_000_000_000));
+
+        let v2 = program_ix(
+            METEORA_DAMM_V2,
+            &DAMM_V2_INITIALIZE_POOL_DISC,
+            &u64_args(&[0; 5]),
+            20,
+            &[(6, POOL), (8, TOKEN), (9, WSOL)],
+        );
+        let tx = tx_with(&USER, &[v2]);
+        let finder = MeteoraMintFinder::default().find_mints(&tx, &cfg);
+        assert_eq!(finder.len(), 1);
+        assert_eq!(finder[0].mint, TOKEN);
+        assert_eq!(finder[0].protocol, Some(Protocol::MeteoraDammV2));
+        assert_eq!(finder[0].action, Some(WatchAction::CreatePool));
+    }
+}
@@ -0,0 +1,1526 @@
+use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::{
+    message::VersionedMessage, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_vote_program::id as vote_program_id;
+
+mod amm;
+pub mod fees;
+pub mod idl;
+pub mod jito;
+pub mod jupiter;
+pub mod meta;
+pub mod metadata;
+pub mod meteora;
+pub mod orca;
+pub mod pumpswap;
+pub mod raydium;
+pub mod spl_token;
+pub mod wallets;
+pub mod watch_index;
+
+pub use fees::{ComputeBudget, FeeTracker, COMPUTE_BUDGET_PROGRAM_ID};
+pub use idl::{Idl, IdlDetailer, IdlProgramConfig};
+pub use jito::{
+    default_jito_tip_accounts, tip_lamports, JitoTipTracker, SlotTips, TipBundle, JITO_TIP_ACCOUNTS,
+};
+pub use jupiter::{JupiterDetailer, JupiterMintFinder, JUPITER_V6_PROGRAM_ID};
+pub(crate) use meta::with_resolved_keys;
+pub use meta::{InnerInstructions, SolChange, TokenBalance, TokenChange, TxMeta, TxOutcome};
+pub use metadata::{TokenMetadataDetailer, METAPLEX_TOKEN_METADATA_PROGRAM_ID};
+pub use meteora::{
+    MeteoraDetailer, MeteoraMintFinder, MeteoraProgram, METEORA_DAMM_V1_PROGRAM_ID,
+    METEORA_DAMM_V2_PROGRAM_ID, METEORA_DLMM_PROGRAM_ID,
+};
+pub use orca::{OrcaWhirlpoolDetailer, OrcaWhirlpoolMintFinder, ORCA_WHIRLPOOL_PROGRAM_ID};
+pub use pumpswap::{PumpSwapDetailer, PumpSwapMintFinder, PUMPSWAP_PROGRAM_ID};
+pub use raydium::{
+    RaydiumDetailer, RaydiumMintFinder, RaydiumProgram, RAYDIUM_AMM_V4_PROGRAM_ID,
+    RAYDIUM_CLMM_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID,
+};
+pub use spl_token::{
+    token_instructions, AuthorityType, DecodedTokenInstruction, SplTokenDecoder, SplTokenDetailer,
+    TokenInstruction,
+};
+pub use wallets::{load_wallets, WalletMatch};
+pub use watch_index::{KeySet, Prefilter, WatchIndex};
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqPjhAG8cHpQdV3ESy1dpeBeXcAD9fQg";
+const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const DEFAULT_PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const WSOL: Pubkey = Pubkey::from_str_const(WSOL_MINT);
+const PUMPFUN_CREATE_DISC: [u8; 8] = [0x18, 0x1e, 0xc8, 0x28, 0x05, 0x1c, 0x07, 0x77];
+const PUMPFUN_CREATE_V2_DISC: [u8; 8] = [0xd6, 0x90, 0x4c, 0xec, 0x5f, 0x8b, 0x31, 0xb4];
+pub(crate) const PUMPFUN_BUY_DISC: [u8; 8] = [0x66, 0x06, 0x3d, 0x12, 0x01, 0xda, 0xeb, 0xea];
+const PUMPFUN_BUY_EXACT_SOL_IN_DISC: [u8; 8] =
+    [0x38, 0xfc, 0x74, 0x08, 0x9e, 0xdf, 0xcd, 0x5f];
+const PUMPFUN_SELL_DISC: [u8; 8] = [0x33, 0xe6, 0x85, 0xa4, 0x01, 0x7f, 0x83, 0xad];
+
+/// Watch lists and the detectors run on each transaction.
+///
+/// The lists matched per transaction (program ids, authorities, token mints, wallets) are
+/// private and indexed into hash sets; change them through the builders or setters, which
+/// rebuild the index.
+#[derive(Clone)]
+pub struct ProgramWatchConfig {
+    program_ids: Vec<Pubkey>,
+    authorities: Vec<Pubkey>,
+    pub token_program_ids: Vec<Pubkey>,
+    pub skip_vote_txs: bool,
+    pub mint_finder: Arc<dyn MintFinder + Send + Sync>,
+    pub detailers: Vec<Arc<dyn MintDetailer + Send + Sync>>,
+    pub instruction_decoders: Vec<Arc<dyn InstructionDecoder + Send + Sync>>,
+    /// Transfers to these accounts count as Jito tips (mainnet tip accounts by default).
+    pub jito_tip_accounts: Vec<Pubkey>,
+    /// Mints whose token instructions make a transaction a hit (see [`spl_token`]).
+    token_mints: Vec<Pubkey>,
+    /// Tracked wallets, sorted (see [`with_wallets`](Self::with_wallets)).
+    wallets: Vec<Pubkey>,
+    pub wallet_match: WalletMatch,
+    /// Hash-set index of the lists above; rebuilt whenever one of them changes.
+    index: Arc<WatchIndex>,
+}
+
+impl ProgramWatchConfig {
+    pub fn new(program_ids: Vec<Pubkey>, authorities: Vec<Pubkey>) -> Self {
+        let mf = Arc::new(default_mint_finder(&program_ids));
+        Self {
+            program_ids: program_ids.clone(),
+            authorities,
+            token_program_ids: default_token_program_ids(),
+            skip_vote_txs: true,
+            mint_finder: mf.clone(),
+            detailers: default_detailers_from_programs(&program_ids),
+            instruction_decoders: Vec::new(),
+            jito_tip_accounts: default_jito_tip_accounts(),
+            token_mints: Vec::new(),
+            wallets: Vec::new(),
+            wallet_match: WalletMatch::default(),
+            index: Arc::default(),
+        }
+        .reindexed()
+    }
+
+    /// Indexed watch lists used for matching.
+    pub fn index(&self) -> &WatchIndex {
+        &self.index
+    }
+
+    pub fn program_ids(&self) -> &[Pubkey] {
+        &self.program_ids
+    }
+
+    pub fn authorities(&self) -> &[Pubkey] {
+        &self.authorities
+    }
+
+    /// Mints set by [`with_token_mints`](Self::with_token_mints).
+    pub fn token_mints(&self) -> &[Pubkey] {
+        &self.token_mints
+    }
+
+    /// Tracked wallets, sorted.
+    pub fn wallets(&self) -> &[Pubkey] {
+        &self.wallets
+    }
+
+    /// Replace the watched program ids. The mint finder and detailers picked by
+    /// [`new`](Self::new) are kept.
+    pub fn set_program_ids(&mut self, program_ids: Vec<Pubkey>) {
+        self.program_ids = program_ids;
+        self.reindex();
+    }
+
+    pub fn set_authorities(&mut self, authorities: Vec<Pubkey>) {
+        self.authorities = authorities;
+        self.reindex();
+    }
+
+    /// Replace the tracked wallets, keeping the [`WalletMatch`] mode.
+    pub fn set_wallets(&mut self, wallets: Vec<Pubkey>) {
+        self.wallets = wallets::sorted_wallets(wallets);
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.index = Arc::new(WatchIndex::new(
+            &self.program_ids,
+            &self.authorities,
+            &self.token_mints,
+            &self.wallets,
+            self.index.prefilter,
+        ));
+    }
+
+    fn reindexed(mut self) -> Self {
+        self.reindex();
+        self
+    }
+
+    /// Front every watch list with a probabilistic prefilter (see [`watch_index`]).
+    pub fn with_prefilter(mut self, prefilter: Prefilter) -> Self {
+        self.index = Arc::new(WatchIndex {
+            prefilter,
+            ..WatchIndex::default()
+        });
+        self.reindexed()
+    }
+
+    pub fn with_token_program_ids(mut self, token_program_ids: Vec<Pubkey>) -> Self {
+        self.token_program_ids = token_program_ids;
+        self
+    }
+
+    pub fn with_skip_vote_txs(mut self, skip_vote_txs: bool) -> Self {
+        self.skip_vote_txs = skip_vote_txs;
+        self
+    }
+
+    pub fn with_jito_tip_accounts(mut self, jito_tip_accounts: Vec<Pubkey>) -> Self {
+        self.jito_tip_accounts = jito_tip_accounts;
+        self
+    }
+
+    /// Watch token flows of `mints`: transfers, mint-to, burns, closes, approvals, freezes and
+    /// authority changes become hits, with a detail per mint and the decoded instructions.
+    /// Call once; like [`with_idl_detailers`](Self::with_idl_detailers) it appends.
+    pub fn with_token_mints(mut self, mints: Vec<Pubkey>) -> Self {
+        if !mints.is_empty() {
+            self.instruction_decoders
+                .push(Arc::new(SplTokenDecoder::new(mints.clone())));
+        }
+        self.token_mints = mints;
+        self.reindexed()
+    }
+
+    /// Track wallets: transactions they sign (or pay for, with [`WalletMatch::FeePayer`])
+    /// become hits and get every detail the detailers find, plus their token instructions.
+    pub fn with_wallets(mut self, wallets: Vec<Pubkey>, wallet_match: WalletMatch) -> Self {
+        self.wallets = wallets::sorted_wallets(wallets);
+        self.wallet_match = wallet_match;
+        self.reindexed()
+    }
+
+    pub fn with_mint_finder(mut self, mint_finder: Arc<dyn MintFinder + Send + Sync>) -> Self {
+        self.mint_finder = mint_finder;
+        self
+    }
+
+    pub fn with_detailers(mut self, detailers: Vec<Arc<dyn MintDetailer + Send + Sync>>) -> Self {
+        self.detailers = detailers;
+        self
+    }
+
+    pub fn with_instruction_decoders(
+        mut self,
+        decoders: Vec<Arc<dyn InstructionDecoder + Send + Sync>>,
+    ) -> Self {
+        self.instruction_decoders = decoders;
+        self
+    }
+
+    /// Watch each IDL's program and use it both as a detailer and as an instruction decoder.
+    pub fn with_idl_detailers(mut self, idls: Vec<Arc<IdlDetailer>>) -> Self {
+        for idl in idls {
+            if !self.program_ids.contains(&idl.program_id()) {
+                self.program_ids.push(idl.program_id());
+            }
+            self.detailers.push(idl.clone());
+            self.instruction_decoders.push(idl);
+        }
+        self.reindexed()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramHit {
+    #[serde(with = "serde_b58")]
+    pub signature: Signature,
+    pub program_hit: bool,
+    pub authority_hit: bool,
+    /// A token instruction touched one of [`ProgramWatchConfig::token_mints`].
+    #[serde(default)]
+    pub mint_hit: bool,
+    /// Required signers of the transaction, fee payer first.
+    #[serde(default, with = "serde_b58::vec")]
+    pub signers: Vec<Pubkey>,
+    /// Tracked wallets that signed the transaction; omitted from JSON when empty.
+    #[serde(
+        default,
+        with = "serde_b58::vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub wallets: Vec<Pubkey>,
+    pub mints: Vec<MintInfo>,
+    /// Token instructions decoded once with [`ProgramWatchConfig::token_program_ids`] while
+    /// detecting; `None` when neither token mints nor wallets are watched.
+    #[serde(skip)]
+    pub token_instructions: Option<Vec<DecodedTokenInstruction>>,
+}
+
+/// Program family that produced a mint or detail.
+///
+/// Serialized as a plain string (`"pump"`, `"pump-swap"`, `"raydium-amm"`, ...); unknown names
+/// round-trip through [`Protocol::Other`].
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Protocol {
+    Pump,
+    PumpSwap,
+    /// Raydium AMM v4 (legacy OpenBook-backed pools).
+    RaydiumAmm,
+    RaydiumCpmm,
+    RaydiumClmm,
+    MeteoraDlmm,
+    MeteoraDammV1,
+    MeteoraDammV2,
+    OrcaWhirlpool,
+    /// Jupiter aggregator routes (the venues are listed in [`RouteDetail::venues`]).
+    Jupiter,
+    SplToken,
+    Other(String),
+}
+
+impl Protocol {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Protocol::Pump => "pump",
+            Protocol::PumpSwap => "pump-swap",
+            Protocol::RaydiumAmm => "raydium-amm",
+            Protocol::RaydiumCpmm => "raydium-cpmm",
+            Protocol::RaydiumClmm => "raydium-clmm",
+            Protocol::MeteoraDlmm => "meteora-dlmm",
+            Protocol::MeteoraDammV1 => "meteora-damm-v1",
+            Protocol::MeteoraDammV2 => "meteora-damm-v2",
+            Protocol::OrcaWhirlpool => "orca-whirlpool",
+            Protocol::Jupiter => "jupiter",
+            Protocol::SplToken => "spl-token",
+            Protocol::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "pump" => Protocol::Pump,
+            "pump-swap" => Protocol::PumpSwap,
+            "raydium-amm" => Protocol::RaydiumAmm,
+            "raydium-cpmm" => Protocol::RaydiumCpmm,
+            "raydium-clmm" => Protocol::RaydiumClmm,
+            "meteora-dlmm" => Protocol::MeteoraDlmm,
+            "meteora-damm-v1" => Protocol::MeteoraDammV1,
+            "meteora-damm-v2" => Protocol::MeteoraDammV2,
+            "orca-whirlpool" => Protocol::OrcaWhirlpool,
+            "jupiter" => Protocol::Jupiter,
+            "spl-token" => Protocol::SplToken,
+            other => Protocol::Other(other.to_string()),
+        })
+    }
+}
+
+impl Serialize for Protocol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Protocol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Ok(raw.parse().unwrap_or_else(|never| match never {}))
+    }
+}
+
+/// What a watched instruction does to a mint.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchAction {
+    Create,
+    Buy,
+    Sell,
+    /// Protocol instruction touching the mint that could not be classified further.
+    Trade,
+    /// SPL Token InitializeMint/MintTo.
+    Mint,
+    CreatePool,
+    AddLiquidity,
+    RemoveLiquidity,
+    /// Concentrated-liquidity position opened/closed (liquidity moves separately).
+    OpenPosition,
+    ClosePosition,
+    /// SPL Token instructions on a watched mint (see [`spl_token`]).
+    Transfer,
+    Burn,
+    CloseAccount,
+    SetAuthority,
+    Freeze,
+    Thaw,
+    Approve,
+}
+
+impl WatchAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchAction::Create => "create",
+            WatchAction::Buy => "buy",
+            WatchAction::Sell => "sell",
+            WatchAction::Trade => "trade",
+            WatchAction::Mint => "mint",
+            WatchAction::CreatePool => "create_pool",
+            WatchAction::AddLiquidity => "add_liquidity",
+            WatchAction::RemoveLiquidity => "remove_liquidity",
+            WatchAction::OpenPosition => "open_position",
+            WatchAction::ClosePosition => "close_position",
+            WatchAction::Transfer => "transfer",
+            WatchAction::Burn => "burn",
+            WatchAction::CloseAccount => "close_account",
+            WatchAction::SetAuthority => "set_authority",
+            WatchAction::Freeze => "freeze",
+            WatchAction::Thaw => "thaw",
+            WatchAction::Approve => "approve",
+        }
+    }
+
+    pub fn is_trade(&self) -> bool {
+        matches!(self, WatchAction::Buy | WatchAction::Sell)
+    }
+}
+
+impl fmt::Display for WatchAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmountUnit {
+    Lamports,
+    /// Raw token amount before applying the mint's decimals.
+    TokenBaseUnits,
+}
+
+/// Whether an amount is what actually moved or a limit taken from instruction data.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmountBound {
+    #[default]
+    Exact,
+    /// Upper limit, e.g. the max SOL cost of a pump.fun buy.
+    Max,
+    /// Lower limit, e.g. the min SOL output of a pump.fun sell.
+    Min,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Amount {
+    pub value: u64,
+    pub unit: AmountUnit,
+    #[serde(default)]
+    pub bound: AmountBound,
+}
+
+impl Amount {
+    pub fn lamports(value: u64) -> Self {
+        Self {
+            value,
+            unit: AmountUnit::Lamports,
+            bound: AmountBound::Exact,
+        }
+    }
+
+    pub fn token(value: u64) -> Self {
+        Self {
+            value,
+            unit: AmountUnit::TokenBaseUnits,
+            bound: AmountBound::Exact,
+        }
+    }
+
+    pub fn with_bound(mut self, bound: AmountBound) -> Self {
+        self.bound = bound;
+        self
+    }
+
+    /// Value in SOL for lamport amounts.
+    pub fn as_sol(&self) -> Option<f64> {
+        (self.unit == AmountUnit::Lamports).then(|| self.value as f64 / 1_000_000_000_f64)
+    }
+}
+
+/// Token metadata (name/symbol/uri) when the transaction carries it.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MintMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+}
+
+impl MintMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.symbol.is_none() && self.uri.is_none()
+    }
+
+    /// Fill fields that are still unknown from `other`.
+    pub fn fill_missing(&mut self, other: &MintMetadata) {
+        if self.name.is_none() {
+            self.name = other.name.clone();
+        }
+        if self.symbol.is_none() {
+            self.symbol = other.symbol.clone();
+        }
+        if self.uri.is_none() {
+            self.uri = other.uri.clone();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct MintInfo {
+    #[serde(with = "serde_b58")]
+    pub mint: Pubkey,
+    pub protocol: Option<Protocol>,
+    pub action: Option<WatchAction>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MintDetail {
+    #[serde(with = "serde_b58")]
+    pub mint: Pubkey,
+    pub protocol: Option<Protocol>,
+    pub action: Option<WatchAction>,
+    pub sol_amount: Option<Amount>,
+    pub token_amount: Option<Amount>,
+    #[serde(default)]
+    pub metadata: MintMetadata,
+    /// Set by AMM detailers; omitted from JSON otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PoolDetail>,
+    /// Set by detailers that know the swap direction; omitted from JSON otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swap: Option<SwapDetail>,
+    /// Set by aggregator detailers; omitted from JSON otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<RouteDetail>,
+}
+
+impl MintDetail {
+    pub fn new(mint: Pubkey) -> Self {
+        Self {
+            mint,
+            protocol: None,
+            action: None,
+            sol_amount: None,
+            token_amount: None,
+            metadata: MintMetadata::default(),
+            pool: None,
+            swap: None,
+            route: None,
+        }
+    }
+
+    pub fn from_info(info: &MintInfo) -> Self {
+        Self {
+            protocol: info.protocol.clone(),
+            action: info.action,
+            ..Self::new(info.mint)
+        }
+    }
+
+    /// SOL amount in lamports, if known (regardless of bound).
+    pub fn lamports(&self) -> Option<u64> {
+        self.sol_amount
+            .filter(|a| a.unit == AmountUnit::Lamports)
+            .map(|a| a.value)
+    }
+}
+
+/// Pool-side view of an AMM instruction, in the program's own base/quote orientation.
+///
+/// Amounts come from instruction data, so most are limits (see [`AmountBound`]); a side
+/// holding wrapped SOL is expressed in lamports.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PoolDetail {
+    #[serde(with = "serde_b58")]
+    pub pool: Pubkey,
+    #[serde(with = "serde_b58")]
+    pub base_mint: Pubkey,
+    #[serde(with = "serde_b58")]
+    pub quote_mint: Pubkey,
+    pub base_amount: Option<Amount>,
+    pub quote_amount: Option<Amount>,
+    /// LP tokens minted or burned by liquidity instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lp_amount: Option<Amount>,
+    /// Concentrated-liquidity position account, for position instructions.
+    #[serde(
+        default,
+        with = "serde_b58::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub position: Option<Pubkey>,
+}
+
+impl PoolDetail {
+    pub fn new(pool: Pubkey, base_mint: Pubkey, quote_mint: Pubkey) -> Self {
+        Self {
+            pool,
+            base_mint,
+            quote_mint,
+            base_amount: None,
+            quote_amount: None,
+            lp_amount: None,
+            position: None,
+        }
+    }
+
+    /// The non-SOL side (base if neither side is wrapped SOL).
+    pub fn token_mint(&self) -> Pubkey {
+        if self.base_mint == WSOL {
+            self.quote_mint
+        } else {
+            self.base_mint
+        }
+    }
+
+    /// True when base is wrapped SOL, so the program's buy/sell is inverted for the token.
+    pub fn is_inverted(&self) -> bool {
+        self.base_mint == WSOL && self.quote_mint != WSOL
+    }
+
+    /// Token-centric detail: `mint` is the non-SOL side, `sol_amount` the wrapped-SOL side.
+    pub fn to_mint_detail(&self, protocol: Protocol, action: WatchAction) -> MintDetail {
+        let (token_amount, sol_amount) = if self.is_inverted() {
+            (self.quote_amount, self.base_amount)
+        } else if self.quote_mint == WSOL {
+            (self.base_amount, self.quote_amount)
+        } else {
+            (self.base_amount, None)
+        };
+        MintDetail {
+            protocol: Some(protocol),
+            action: Some(action),
+            sol_amount,
+            token_amount,
+            pool: Some(self.clone()),
+            ..MintDetail::new(self.token_mint())
+        }
+    }
+}
+
+/// Direction-aware view of a swap instruction.
+///
+/// Mints are `None` when the program does not pass them as accounts and they could not be
+/// resolved from token accounts initialized in the same transaction (see
+/// [`token_account_mints`]). Amounts follow the same limit convention as [`PoolDetail`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SwapDetail {
+    #[serde(with = "serde_b58")]
+    pub pool: Pubkey,
+    #[serde(default, with = "serde_b58::option")]
+    pub input_mint: Option<Pubkey>,
+    #[serde(default, with = "serde_b58::option")]
+    pub output_mint: Option<Pubkey>,
+    pub amount_in: Option<Amount>,
+    pub amount_out: Option<Amount>,
+    /// Pools after `pool` for multi-hop swaps, in order.
+    #[serde(
+        default,
+        with = "serde_b58::vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub route: Vec<Pubkey>,
+}
+
+impl SwapDetail {
+    pub fn new(pool: Pubkey, input_mint: Option<Pubkey>, output_mint: Option<Pubkey>) -> Self {
+        Self {
+            pool,
+            input_mint,
+            output_mint,
+            amount_in: None,
+            amount_out: None,
+            route: Vec::new(),
+        }
+    }
+
+    /// Token-centric detail: paying wrapped SOL is a buy of the output mint, receiving it a
+    /// sell of the input mint; anything else is a `trade` reported on the output mint (input
+    /// if the output is unknown). `None` when neither mint is known.
+    pub fn to_mint_detail(&self, protocol: Protocol) -> Option<MintDetail> {
+        Some(MintDetail {
+            swap: Some(self.clone()),
+            ..directed_mint_detail(
+                protocol,
+                (self.input_mint, self.amount_in),
+                (self.output_mint, self.amount_out),
+            )?
+        })
+    }
+}
+
+/// Shared by [`SwapDetail`] and [`RouteDetail`]: picks the reported mint and action from the
+/// swap direction.
+fn directed_mint_detail(
+    protocol: Protocol,
+    (input_mint, amount_in): (Option<Pubkey>, Option<Amount>),
+    (output_mint, amount_out): (Option<Pubkey>, Option<Amount>),
+) -> Option<MintDetail> {
+    let (mint, action, sol_amount, token_amount) = match (input_mint, output_mint) {
+        (Some(input), Some(output)) if input == WSOL && output != WSOL => {
+            (output, WatchAction::Buy, amount_in, amount_out)
+        }
+        (Some(input), Some(output)) if output == WSOL && input != WSOL => {
+            (input, WatchAction::Sell, amount_out, amount_in)
+        }
+        (_, Some(output)) => (output, WatchAction::Trade, None, amount_out),
+        (Some(input), None) => (input, WatchAction::Trade, None, amount_in),
+        (None, None) => return None,
+    };
+    Some(MintDetail {
+        protocol: Some(protocol),
+        action: Some(action),
+        sol_amount,
+        token_amount,
+        ..MintDetail::new(mint)
+    })
+}
+
+/// One step of an aggregator route plan.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RouteStep {
+    /// Venue name as the aggregator labels it (e.g. `Whirlpool`, `RaydiumClmm`).
+    pub venue: String,
+    /// Share of the step's input routed through this venue.
+    pub percent: u8,
+    pub input_index: u8,
+    pub output_index: u8,
+}
+
+/// Aggregated view of an aggregator swap: the end mints, the amount the user fixed, the quote
+/// for the other side and the slippage applied to it.
+///
+/// `input_mint` is `None` when the route only passes the user's source token account and it
+/// was not created in the transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RouteDetail {
+    #[serde(default, with = "serde_b58::option")]
+    pub input_mint: Option<Pubkey>,
+    #[serde(default, with = "serde_b58::option")]
+    pub output_mint: Option<Pubkey>,
+    /// Exact for exact-in routes, the quote for exact-out routes.
+    pub amount_in: u64,
+    /// The quote for exact-in routes, exact for exact-out routes.
+    pub amount_out: u64,
+    #[serde(default)]
+    pub exact_out: bool,
+    pub slippage_bps: u16,
+    /// Route plan steps, in execution order; stops at the first venue this SDK cannot decode.
+    #[serde(default)]
+    pub venues: Vec<RouteStep>,
+}
+
+impl RouteDetail {
+    /// The quoted side after slippage: the minimum output for exact-in routes, the maximum
+    /// input for exact-out routes.
+    pub fn limit(&self) -> u64 {
+        let bps = u128::from(self.slippage_bps);
+        let limit = if self.exact_out {
+            u128::from(self.amount_in) * (10_000 + bps) / 10_000
+        } else {
+            u128::from(self.amount_out) * 10_000u128.saturating_sub(bps) / 10_000
+        };
+        u64::try_from(limit).unwrap_or(u64::MAX)
+    }
+
+    /// Token-centric detail like [`SwapDetail::to_mint_detail`]; the quoted side is reported as
+    /// its slippage [`limit`](Self::limit).
+    pub fn to_mint_detail(&self, protocol: Protocol) -> Option<MintDetail> {
+        let side = |mint: Option<Pubkey>, value: u64, bound: AmountBound| {
+            (
+                mint,
+                Some(match mint {
+                    Some(mint) => side_amount(&mint, value, bound),
+                    None => Amount::token(value).with_bound(bound),
+                }),
+            )
+        };
+        let (input, output) = if self.exact_out {
+            (
+                side(self.input_mint, self.limit(), AmountBound::Max),
+                side(self.output_mint, self.amount_out, AmountBound::Exact),
+            )
+        } else {
+            (
+                side(self.input_mint, self.amount_in, AmountBound::Exact),
+                side(self.output_mint, self.limit(), AmountBound::Min),
+            )
+        };
+        Some(MintDetail {
+            route: Some(self.clone()),
+            ..directed_mint_detail(protocol, input, output)?
+        })
+    }
+}
+
+/// Amount in the unit matching `mint`: lamports for wrapped SOL, base units otherwise.
+pub fn side_amount(mint: &Pubkey, value: u64, bound: AmountBound) -> Amount {
+    if *mint == WSOL {
+        Amount::lamports(value).with_bound(bound)
+    } else {
+        Amount::token(value).with_bound(bound)
+    }
+}
+
+/// An account passed to a decoded instruction, named after the IDL.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NamedAccount {
+    pub name: String,
+    #[serde(with = "serde_b58")]
+    pub pubkey: Pubkey,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+/// A top-level instruction decoded from its program's schema (e.g. an Anchor IDL).
+///
+/// `args` mirrors the borsh layout: structs become objects, unit enum variants strings,
+/// data-carrying variants `{ "Variant": ... }`, pubkeys base58, byte vectors hex, and
+/// 128-bit integers decimal strings.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InstructionEvent {
+    #[serde(with = "serde_b58")]
+    pub program_id: Pubkey,
+    pub protocol: Protocol,
+    pub instruction: String,
+    pub args: serde_json::Value,
+    pub accounts: Vec<NamedAccount>,
+    /// Why `args` could not be decoded, if the discriminator matched but the payload did not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl InstructionEvent {
+    pub fn account(&self, name: &str) -> Option<&Pubkey> {
+        self.accounts
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| &a.pubkey)
+    }
+}
+
+/// Serde adapter that writes keys and signatures as base58 strings instead of byte arrays.
+pub(crate) mod serde_b58 {
+    use std::{fmt::Display, str::FromStr};
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(D::Error::custom)
+    }
+
+    /// Same as the parent module for lists.
+    pub mod vec {
+        use std::{fmt::Display, str::FromStr};
+
+        use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<T: Display, S: Serializer>(
+            values: &[T],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(values.len()))?;
+            for value in values {
+                seq.serialize_element(&value.to_string())?;
+            }
+            seq.end()
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+        where
+            T: FromStr,
+            T::Err: Display,
+            D: Deserializer<'de>,
+        {
+            Vec::<String>::deserialize(deserializer)?
+                .into_iter()
+                .map(|raw| raw.parse().map_err(D::Error::custom))
+                .collect()
+        }
+    }
+
+    /// Same as the parent module for optional values (`null` when absent).
+    pub mod option {
+        use std::{fmt::Display, str::FromStr};
+
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<T: Display, S: Serializer>(
+            value: &Option<T>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => serializer.collect_str(value),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            T: FromStr,
+            T::Err: Display,
+            D: Deserializer<'de>,
+        {
+            Option::<String>::deserialize(deserializer)?
+                .map(|raw| raw.parse().map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+}
+
+pub fn parse_pubkeys_env(var: &str, defaults: &[&str]) -> Vec<Pubkey> {
+    let raw = std::env::var(var).ok();
+    parse_pubkeys(raw.as_deref(), defaults)
+}
+
+pub fn parse_pubkeys(raw: Option<&str>, defaults: &[&str]) -> Vec<Pubkey> {
+    let mut set = BTreeSet::new();
+
+    match raw {
+        Some(value) => {
+            for part in value.split(',') {
+                let trimmed = part.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if let Ok(pk) = Pubkey::from_str(trimmed) {
+                    set.insert(pk);
+                }
+            }
+        }
+        None => {
+            for def in defaults {
+                if let Ok(pk) = Pubkey::from_str(def) {
+                    set.insert(pk);
+                }
+            }
+        }
+    }
+
+    set.into_iter().collect()
+}
+
+pub fn default_token_program_ids() -> Vec<Pubkey> {
+    parse_pubkeys(
+        None,
+        &[
+            TOKEN_PROGRAM_ID,
+            TOKEN_2022_PROGRAM_ID, //
+        ],
+    )
+}
+
+pub fn is_vote_transaction(tx: &VersionedTransaction) -> bool {
+    let keys = tx.message.static_account_keys();
+    tx.message.instructions().iter().any(|ix| {
+        keys.get(ix.program_id_index as usize)
+            .map(|pid| pid == &vote_program_id())
+            .unwrap_or(false)
+    })
+}
+
+pub fn detect_program_hit(
+    tx: &VersionedTransaction,
+    cfg: &ProgramWatchConfig,
+) -> Option<ProgramHit> {
+    let index = cfg.index();
+    if index.is_empty() {
+        return None;
+    }
+    let keys = tx.message.static_account_keys();
+    let authority_hit =
+        !index.authorities.is_empty() && keys.iter().any(|key| index.authorities.contains(key));
+    let mut program_hit = false;
+
+    for ix in tx.message.instructions() {
+        let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+            continue;
+        };
+        if cfg.skip_vote_txs && *program_id == vote_program_id() {
+            return None;
+        }
+        if !program_hit && index.program_ids.contains(program_id) {
+            program_hit = true;
+        }
+    }
+
+    let token_instructions = (!index.token_mints.is_empty() || !index.wallets.is_empty())
+        .then(|| token_instructions(tx, &cfg.token_program_ids));
+    let mint_hit = token_instructions
+        .as_deref()
+        .is_some_and(|ixs| spl_token::touches_mints(ixs, &index.token_mints));
+    let wallets = wallets::matched_wallets(tx, &index.wallets, cfg.wallet_match);
+    if !program_hit && !authority_hit && !mint_hit && wallets.is_empty() {
+        return None;
+    }
+    let mint_accounts: Vec<MintInfo> = cfg.mint_finder.find_mints(tx, cfg);
+    let signers = usize::from(tx.message.header().num_required_signatures);
+
+    Some(ProgramHit {
+        signature: tx.signatures.get(0).cloned().unwrap_or_default(),
+        program_hit,
+        authority_hit,
+        mint_hit,
+        signers: keys.iter().take(signers).copied().collect(),
+        wallets,
+        mints: mint_accounts,
+        token_instructions,
+    })
+}
+
+pub fn extract_mint_accounts(
+    message: &VersionedMessage,
+    token_program_ids: &[Pubkey],
+) -> Vec<Pubkey> {
+    let mut mints = BTreeSet::new();
+    let keys = message.static_account_keys();
+    for ix in message.instructions() {
+        let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+            continue;
+        };
+        if !token_program_ids.iter().any(|id| id == program_id) {
+            continue;
+        }
+        let Some(tag) = ix.data.first() else {
+            continue;
+        };
+        // Token program: 0 InitializeMint, 7 MintTo, 14 InitializeMint2, 20 InitializeMintCloseAuthority
+        if matches!(tag, 0 | 7 | 14 | 20) {
+            if let Some(mint_idx) = ix.accounts.get(0) {
+                if let Some(mint) = keys.get(*mint_idx as usize) {
+                    mints.insert(*mint);
+                }
+            }
+        }
+    }
+    mints.into_iter().collect()
+}
+
+/// Token account -> mint for accounts created in `tx` itself: Associated Token Account
+/// create/create_idempotent (accounts[1]/[3]) and Token/Token-2022 InitializeAccount,
+/// InitializeAccount2 and InitializeAccount3 (tags 1/16/18, accounts[0]/[1]).
+///
+/// Lets detailers name the mints of programs that only take token accounts (e.g. a swap into a
+/// freshly created ATA or a temporary WSOL account).
+pub fn token_account_mints(tx: &VersionedTransaction) -> BTreeMap<Pubkey, Pubkey> {
+    token_account_mints_for(tx, &default_token_program_ids())
+}
+
+/// [`token_account_mints`] with explicit Token program ids.
+pub fn token_account_mints_for(
+    tx: &VersionedTransaction,
+    token_program_ids: &[Pubkey],
+) -> BTreeMap<Pubkey, Pubkey> {
+    let keys = tx.message.static_account_keys();
+    let mut out = BTreeMap::new();
+    for ix in tx.message.instructions() {
+        let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+            continue;
+        };
+        let (account_idx, mint_idx) = if *program_id == ASSOCIATED_TOKEN_PROGRAM {
+            // Empty data is the original `create`; 0/1 are create/create_idempotent.
+            if !matches!(ix.data.first(), None | Some(0) | Some(1)) {
+                continue;
+            }
+            (1, 3)
+        } else if token_program_ids.contains(program_id)
+            && matches!(ix.data.first(), Some(1 | 16 | 18))
+        {
+            (0, 1)
+        } else {
+            continue;
+        };
+        let account = ix
+            .accounts
+            .get(account_idx)
+            .and_then(|i| keys.get(*i as usize));
+        let mint = ix
+            .accounts
+            .get(mint_idx)
+            .and_then(|i| keys.get(*i as usize));
+        if let (Some(account), Some(mint)) = (account, mint) {
+            out.insert(*account, *mint);
+        }
+    }
+    out
+}
+
+pub fn first_signatures<'a, I>(txs: I, limit: usize, skip_vote_txs: bool) -> Vec<Signature>
+where
+    I: IntoIterator<Item = &'a VersionedTransaction>,
+{
+    txs.into_iter()
+        .filter(|tx| !(skip_vote_txs && is_vote_transaction(tx)))
+        .filter_map(|tx| tx.signatures.get(0))
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+pub fn fmt_pubkeys(pubkeys: &[Pubkey]) -> Vec<String> {
+    pubkeys.iter().map(|p| p.to_string()).collect()
+}
+
+pub trait MintFinder {
+    fn find_mints(&self, tx: &VersionedTransaction, cfg: &ProgramWatchConfig) -> Vec<MintInfo>;
+}
+
+pub trait MintDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        cfg: &ProgramWatchConfig,
+        mints: &[MintInfo],
+    ) -> Vec<MintDetail>;
+
+    /// [`MintDetailer::detail`] with the transaction meta when the feed carries it (Geyser, RPC);
+    /// `tx` then also lists CPI-invoked instructions. Defaults to ignoring the meta.
+    fn detail_with_meta(
+        &self,
+        tx: &VersionedTransaction,
+        cfg: &ProgramWatchConfig,
+        mints: &[MintInfo],
+        meta: Option<&TxMeta>,
+    ) -> Vec<MintDetail> {
+        let _ = meta;
+        self.detail(tx, cfg, mints)
+    }
+
+    /// [`MintDetailer::detail_with_meta`] for a transaction [`detect_program_hit`] matched, so
+    /// work done while detecting (e.g. [`ProgramHit::token_instructions`]) can be reused.
+    /// Defaults to detailing `hit.mints`.
+    fn detail_hit(
+        &self,
+        tx: &VersionedTransaction,
+        cfg: &ProgramWatchConfig,
+        hit: &ProgramHit,
+        meta: Option<&TxMeta>,
+    ) -> Vec<MintDetail> {
+        self.detail_with_meta(tx, cfg, &hit.mints, meta)
+    }
+}
+
+pub trait InstructionDecoder {
+    fn decode(&self, tx: &VersionedTransaction) -> Vec<InstructionEvent>;
+
+    /// [`InstructionDecoder::decode`] for a transaction [`detect_program_hit`] matched.
+    /// Defaults to ignoring the hit.
+    fn decode_hit(&self, tx: &VersionedTransaction, hit: &ProgramHit) -> Vec<InstructionEvent> {
+        let _ = hit;
+        self.decode(tx)
+    }
+}
+
+fn insert_mint(map: &mut BTreeMap<Pubkey, MintInfo>, info: MintInfo) {
+    map.entry(info.mint)
+        .and_modify(|existing| {
+            if existing.protocol.is_none() && info.protocol.is_some() {
+                existing.protocol = info.protocol.clone();
+                existing.action = info.action;
+            }
+        })
+        .or_insert(info);
+}
+
+/// Default SPL Token/Token-2022 finder: looks for Initialize/MintTo tags at accounts[0].
+pub struct SplTokenMintFinder;
+
+impl MintFinder for SplTokenMintFinder {
+    fn find_mints(&self, tx: &VersionedTransaction, cfg: &ProgramWatchConfig) -> Vec<MintInfo> {
+        let mut mint_accounts = BTreeMap::new();
+        let keys = tx.message.static_account_keys();
+        for ix in tx.message.instructions() {
+            let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            if !cfg.token_program_ids.iter().any(|id| id == program_id) {
+                continue;
+            }
+            let Some(tag) = ix.data.first() else {
+                continue;
+            };
+            if matches!(tag, 0 | 7 | 14 | 20) {
+                if let Some(mint_idx) = ix.accounts.get(0) {
+                    if let Some(mint) = keys.get(*mint_idx as usize) {
+                        insert_mint(
+                            &mut mint_accounts,
+                            MintInfo {
+                                mint: *mint,
+                                protocol: Some(Protocol::SplToken),
+                                action: Some(WatchAction::Mint),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        mint_accounts.into_values().collect()
+    }
+}
+
+/// Pump.fun instructions we know how to read, keyed by Anchor discriminator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PumpfunInstruction {
+    Create,
+    Buy,
+    BuyExactSolIn,
+    Sell,
+}
+
+impl PumpfunInstruction {
+    fn from_data(data: &[u8]) -> Option<Self> {
+        match data.get(0..8) {
+            Some(bytes) if bytes == PUMPFUN_CREATE_V2_DISC || bytes == PUMPFUN_CREATE_DISC => {
+                Some(Self::Create)
+            }
+            Some(bytes) if bytes == PUMPFUN_BUY_DISC => Some(Self::Buy),
+            Some(bytes) if bytes == PUMPFUN_BUY_EXACT_SOL_IN_DISC => Some(Self::BuyExactSolIn),
+            Some(bytes) if bytes == PUMPFUN_SELL_DISC => Some(Self::Sell),
+            _ => None,
+        }
+    }
+
+    fn action(self) -> WatchAction {
+        match self {
+            Self::Create => WatchAction::Create,
+            Self::Buy | Self::BuyExactSolIn => WatchAction::Buy,
+            Self::Sell => WatchAction::Sell,
+        }
+    }
+
+    /// Account position of the mint: create takes it first, trades after global/fee recipient.
+    fn mint_account_index(self) -> usize {
+        match self {
+            Self::Create => 0,
+            _ => 2,
+        }
+    }
+}
+
+/// Pump.fun finder: picks mint from pump.fun instructions by account position (create_v2/buy/sell).
+pub struct PumpfunAccountMintFinder {
+    pumpfun_ids: Vec<Pubkey>,
+}
+
+impl PumpfunAccountMintFinder {
+    pub fn new(pumpfun_ids: Vec<Pubkey>) -> Self {
+        Self { pumpfun_ids }
+    }
+}
+
+fn is_system_id(pk: &Pubkey) -> bool {
+    // Avoid depending on solana_sdk::system_program in this build profile.
+    pk.to_string() == "11111111111111111111111111111111"
+}
+
+impl MintFinder for PumpfunAccountMintFinder {
+    fn find_mints(&self, tx: &VersionedTransaction, _cfg: &ProgramWatchConfig) -> Vec<MintInfo> {
+        let keys = tx.message.static_account_keys();
+        let mut mints = BTreeMap::new();
+        for ix in tx.message.instructions() {
+            let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            if !self.pumpfun_ids.iter().any(|id| id == program_id) {
+                continue;
+            }
+            // Fallback: keep a generic trade tag if we can't classify (e.g., new ix name)
+            let kind = PumpfunInstruction::from_data(&ix.data);
+            let (mint_pos, action) = match kind {
+                Some(kind) => (kind.mint_account_index(), kind.action()),
+                None => (2, WatchAction::Trade),
+            };
+            if kind != Some(PumpfunInstruction::Create) && ix.accounts.len() <= 2 {
+                continue;
+            }
+            if let Some(mint_idx) = ix.accounts.get(mint_pos) {
+                if let Some(mint) = keys.get(*mint_idx as usize) {
+                    if !is_system_id(mint) {
+                        insert_mint(
+                            &mut mints,
+                            MintInfo {
+                                mint: *mint,
+                                protocol: Some(Protocol::Pump),
+                                action: Some(action),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        mints.into_values().collect()
+    }
+}
+
+fn read_u64_le(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_le_bytes)
+}
+
+/// Pump.fun detailer: decodes action and amounts from pump.fun instruction data.
+pub struct PumpfunDetailer {
+    pumpfun_ids: Vec<Pubkey>,
+}
+
+impl PumpfunDetailer {
+    pub fn new(pumpfun_ids: Vec<Pubkey>) -> Self {
+        Self { pumpfun_ids }
+    }
+}
+
+impl MintDetailer for PumpfunDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        _cfg: &ProgramWatchConfig,
+        mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        let keys = tx.message.static_account_keys();
+        let mut out = BTreeMap::new();
+        for ix in tx.message.instructions() {
+            let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            if !self.pumpfun_ids.iter().any(|id| id == program_id) {
+                continue;
+            }
+            // Unknown discriminator: skip to avoid bogus values.
+            let Some(kind) = PumpfunInstruction::from_data(&ix.data) else {
+                continue;
+            };
+            let Some(mint_idx) = ix.accounts.get(kind.mint_account_index()) else {
+                continue;
+            };
+            let Some(mint) = keys.get(*mint_idx as usize) else {
+                continue;
+            };
+            if is_system_id(mint) {
+                continue;
+            }
+            // Instruction data only carries limits: buy(amount, max_sol_cost),
+            // sell(amount, min_sol_output), buy_exact_sol_in(spendable_sol_in, min_tokens_out).
+            let (token_amount, sol_amount) = match kind {
+                PumpfunInstruction::Create => (None, None),
+                PumpfunInstruction::BuyExactSolIn => {
+                    (None, read_u64_le(&ix.data, 8).map(Amount::lamports))
+                }
+                PumpfunInstruction::Buy => (
+                    read_u64_le(&ix.data, 8).map(Amount::token),
+                    read_u64_le(&ix.data, 16)
+                        .map(|v| Amount::lamports(v).with_bound(AmountBound::Max)),
+                ),
+                PumpfunInstruction::Sell => (
+                    read_u64_le(&ix.data, 8).map(Amount::token),
+                    read_u64_le(&ix.data, 16)
+                        .map(|v| Amount::lamports(v).with_bound(AmountBound::Min)),
+                ),
+            };
+            let action = kind.action();
+            let entry = out.entry(*mint).or_insert(MintDetail {
+                protocol: Some(Protocol::Pump),
+                action: Some(action),
+                sol_amount,
+                token_amount,
+                ..MintDetail::new(*mint)
+            });
+            if entry.action.is_none() || entry.action == Some(WatchAction::Create) {
+                entry.action = Some(action);
+            }
+            if sol_amount.is_some() {
+                entry.sol_amount = sol_amount;
+            }
+            if token_amount.is_some() {
+                entry.token_amount = token_amount;
+            }
+            // create/create_v2(name, symbol, uri, creator, ..)
+            if kind == PumpfunInstruction::Create {
+                if let Some(metadata) = metadata::read_name_symbol_uri(&ix.data, 8) {
+                    entry.metadata.fill_missing(&metadata);
+                }
+            }
+        }
+        for m in mints {
+            if !out.contains_key(&m.mint) {
+                let action = m.action.map(|a| match a {
+                    WatchAction::Trade => WatchAction::Sell,
+                    other => other,
+                });
+                out.insert(
+                    m.mint,
+                    MintDetail {
+                        action,
+                        ..MintDetail::from_info(m)
+                    },
+                );
+            }
+        }
+        out.into_values().collect()
+    }
+}
+
+/// Composite finder: aggregates multiple strategies.
+pub struct CompositeMintFinder {
+    finders: Vec<Arc<dyn MintFinder + Send + Sync>>,
+}
+
+impl CompositeMintFinder {
+    pub fn new(finders: Vec<Arc<dyn MintFinder + Send + Sync>>) -> Self {
+        Self { finders }
+    }
+}
+
+impl MintFinder for CompositeMintFinder {
+    fn find_mints(&self, tx: &VersionedTransaction, cfg: &ProgramWatchConfig) -> Vec<MintInfo> {
+        let mut out = BTreeMap::new();
+        for f in &self.finders {
+            for m in f.find_mints(tx, cfg) {
+                insert_mint(&mut out, m);
+            }
+        }
+        out.into_values().collect()
+    }
+}
+
+/// Programs decoded by a dedicated venue finder/detailer.
+fn is_venue_program(id: &Pubkey) -> bool {
+    *id == pumpswap::PUMPSWAP
+        || RaydiumProgram::from_program_id(id).is_some()
+        || MeteoraProgram::from_program_id(id).is_some()
+        || *id == orca::ORCA_WHIRLPOOL
+        || *id == jupiter::JUPITER_V6
+}
+
+/// Watched programs left to the pump.fun bonding-curve finder/detailer: venues with their own
+/// decoder are excluded (PumpSwap shares pump.fun's `buy`/`sell` discriminators).
+fn pumpfun_program_ids(program_ids: &[Pubkey]) -> Vec<Pubkey> {
+    let mut pumpfun_ids: Vec<Pubkey> = program_ids
+        .iter()
+        .filter(|id| !is_venue_program(id))
+        .copied()
+        .collect();
+    if pumpfun_ids.is_empty() {
+        if let Ok(id) = Pubkey::from_str(DEFAULT_PUMPFUN_PROGRAM_ID) {
+            pumpfun_ids.push(id);
+        }
+    }
+    pumpfun_ids
+}
+
+fn default_mint_finder(program_ids: &[Pubkey]) -> CompositeMintFinder {
+    CompositeMintFinder::new(vec![
+        Arc::new(PumpfunAccountMintFinder::new(pumpfun_program_ids(
+            program_ids,
+        ))),
+        Arc::new(PumpSwapMintFinder::default()),
+        Arc::new(RaydiumMintFinder::default()),
+        Arc::new(MeteoraMintFinder::default()),
+        Arc::new(OrcaWhirlpoolMintFinder::default()),
+        Arc::new(JupiterMintFinder::default()),
+        Arc::new(SplTokenMintFinder),
+    ])
+}
+
+pub fn default_detailers_from_programs(
+    program_ids: &[Pubkey],
+) -> Vec<Arc<dyn MintDetailer + Send + Sync>> {
+    vec![
+        Arc::new(PumpfunDetailer::new(pumpfun_program_ids(program_ids))),
+        Arc::new(PumpSwapDetailer::default()),
+        Arc::new(RaydiumDetailer::default()),
+        Arc::new(MeteoraDetailer::default()),
+        Arc::new(OrcaWhirlpoolDetailer::default()),
+        Arc::new(JupiterDetailer::default()),
+        Arc::new(TokenMetadataDetailer),
+        Arc::new(SplTokenDetailer),
+    ]
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, VersionedMessage},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::VersionedTransaction,
+    };
+
+    /// Unsigned v0 transaction carrying `instructions`, paid for by `payer`.
+    pub fn tx_with(payer: &Pubkey, instructions: &[Instruction]) -> VersionedTransaction {
+        let message = v0::Message::try_compile(payer, instructions, &[], Hash::default())
+            .expect("compile test message");
+        VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        }
+    }
+
+    /// `program` instruction with data `disc` + `args`, over `len` read-only filler accounts
+    /// (`[100 + n; 32]`) with the writable `accounts` placed at their indices.
+    pub fn program_ix(
+        program: Pubkey,
+        disc: &[u8],
+        args: &[u8],
+        len: u8,
+        accounts: &[(usize, Pubkey)],
+    ) -> Instruction {
+        let mut metas: Vec<AccountMeta> = (0..len)
+            .map(|n| AccountMeta::new_readonly(Pubkey::new_from_array([100 + n; 32]), false))
+            .collect();
+        for (index, key) in accounts {
+            metas[*index] = AccountMeta::new(*key, false);
+        }
+        Instruction::new_with_bytes(program, &[disc, args].concat(), metas)
+    }
+
+    /// Little-endian `u64` instruction arguments.
+    pub fn u64_args(args: &[u64]) -> Vec<u8> {
+        args.iter().flat_map(|arg| arg.to_le_bytes()).collect()
+    }
+}
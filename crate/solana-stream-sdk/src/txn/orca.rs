@@ -0,0 +1,584 @@
+//! Orca Whirlpool instructions: initialize_pool(_v2), swap(_v2), two_hop_swap(_v2), position
+//! open/close and increase/decrease_liquidity(_v2).
+//!
+//! `swap_v2`, `two_hop_swap_v2`, `initialize_pool*` and the `*_liquidity_v2` instructions pass
+//! the mints. The others are resolved within the transaction: whirlpool -> mints from any
+//! instruction that names both, position -> whirlpool from the liquidity instructions (needed by
+//! `close_position`, which only takes the position), and v1 swap/liquidity token accounts with
+//! [`token_account_mints`]. Instructions whose mints stay unknown are not reported.
+
+use super::{
+    amm::{self, anchor_disc, InstructionView},
+    is_system_id, read_u64_le, side_amount, token_account_mints, Amount, AmountBound, MintDetail,
+    MintDetailer, MintFinder, MintInfo, PoolDetail, ProgramWatchConfig, Protocol, SwapDetail,
+    WatchAction,
+};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use std::collections::BTreeMap;
+
+pub const ORCA_WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+pub(super) const ORCA_WHIRLPOOL: Pubkey = Pubkey::from_str_const(ORCA_WHIRLPOOL_PROGRAM_ID);
+
+const SWAP_DISC: [u8; 8] = [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+const SWAP_V2_DISC: [u8; 8] = [0x2b, 0x04, 0xed, 0x0b, 0x1a, 0xc9, 0x1e, 0x62];
+const TWO_HOP_SWAP_DISC: [u8; 8] = [0xc3, 0x60, 0xed, 0x6c, 0x44, 0xa2, 0xdb, 0xe6];
+const TWO_HOP_SWAP_V2_DISC: [u8; 8] = [0xba, 0x8f, 0xd1, 0x1d, 0xfe, 0x02, 0xc2, 0x75];
+const INITIALIZE_POOL_DISC: [u8; 8] = [0x5f, 0xb4, 0x0a, 0xac, 0x54, 0xae, 0xe8, 0x28];
+const INITIALIZE_POOL_V2_DISC: [u8; 8] = [0xcf, 0x2d, 0x57, 0xf2, 0x1b, 0x3f, 0xcc, 0x43];
+const OPEN_POSITION_DISC: [u8; 8] = [0x87, 0x80, 0x2f, 0x4d, 0x0f, 0x98, 0xf0, 0x31];
+const OPEN_POSITION_WITH_METADATA_DISC: [u8; 8] = [0xf2, 0x1d, 0x86, 0x30, 0x3a, 0x6e, 0x0e, 0x3c];
+const OPEN_POSITION_WITH_TOKEN_EXTENSIONS_DISC: [u8; 8] =
+    [0xd4, 0x2f, 0x5f, 0x5c, 0x72, 0x66, 0x83, 0xfa];
+const CLOSE_POSITION_DISC: [u8; 8] = [0x7b, 0x86, 0x51, 0x00, 0x31, 0x44, 0x62, 0x62];
+const CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_DISC: [u8; 8] =
+    [0x01, 0xb6, 0x87, 0x3b, 0x9b, 0x19, 0x63, 0xdf];
+const INCREASE_LIQUIDITY_DISC: [u8; 8] = [0x2e, 0x9c, 0xf3, 0x76, 0x0d, 0xcd, 0xfb, 0xb2];
+const INCREASE_LIQUIDITY_V2_DISC: [u8; 8] = [0x85, 0x1d, 0x59, 0xdf, 0x45, 0xee, 0xb0, 0x0a];
+const DECREASE_LIQUIDITY_DISC: [u8; 8] = [0xa0, 0x26, 0xd0, 0x6f, 0x68, 0x5b, 0x2c, 0x01];
+const DECREASE_LIQUIDITY_V2_DISC: [u8; 8] = [0x3a, 0x7f, 0xbc, 0x3e, 0x4f, 0x52, 0xc4, 0x60];
+
+/// An instruction amount before its mint (and so its unit) is known.
+type RawAmount = Option<(u64, AmountBound)>;
+
+/// A whirlpool and whatever is known about its mints.
+#[derive(Clone, Copy, Default)]
+struct Whirlpool {
+    id: Option<Pubkey>,
+    mint_a: Option<Pubkey>,
+    mint_b: Option<Pubkey>,
+}
+
+struct Hop {
+    pool: Whirlpool,
+    a_to_b: bool,
+}
+
+enum WhirlpoolCall {
+    Pool {
+        action: WatchAction,
+        pool: Whirlpool,
+        position: Option<Pubkey>,
+        amount_a: RawAmount,
+        amount_b: RawAmount,
+    },
+    Swap {
+        hops: Vec<Hop>,
+        amount_in: RawAmount,
+        amount_out: RawAmount,
+    },
+}
+
+fn raw(ix: &InstructionView<'_>, offset: usize, bound: AmountBound) -> RawAmount {
+    read_u64_le(ix.data(), offset).map(|value| (value, bound))
+}
+
+/// `(amount_in, amount_out)` from `amount`/`other_amount_threshold` at [8]/[16] and the
+/// `amount_specified_is_input` flag.
+fn swap_amounts(ix: &InstructionView<'_>, is_input: bool) -> (RawAmount, RawAmount) {
+    if is_input {
+        (
+            raw(ix, 8, AmountBound::Exact),
+            raw(ix, 16, AmountBound::Min),
+        )
+    } else {
+        (
+            raw(ix, 16, AmountBound::Max),
+            raw(ix, 8, AmountBound::Exact),
+        )
+    }
+}
+
+fn flag(ix: &InstructionView<'_>, offset: usize) -> Option<bool> {
+    ix.data().get(offset).map(|b| *b != 0)
+}
+
+fn decode(ix: &InstructionView<'_>) -> Option<WhirlpoolCall> {
+    let pool = |id: usize, mint_a: Option<Pubkey>, mint_b: Option<Pubkey>| Whirlpool {
+        id: ix.get(id),
+        mint_a,
+        mint_b,
+    };
+    let liquidity = |action: WatchAction, pool: Whirlpool, position: usize, bound: AmountBound| {
+        WhirlpoolCall::Pool {
+            action,
+            pool,
+            position: ix.get(position),
+            amount_a: raw(ix, 24, bound),
+            amount_b: raw(ix, 32, bound),
+        }
+    };
+    let position = |action: WatchAction, whirlpool: Option<usize>| WhirlpoolCall::Pool {
+        action,
+        pool: whirlpool.map(|id| pool(id, None, None)).unwrap_or_default(),
+        position: ix.get(2),
+        amount_a: None,
+        amount_b: None,
+    };
+    Some(match anchor_disc(ix.data())? {
+        // swap(amount, other_amount_threshold, sqrt_price_limit: u128, amount_specified_is_input,
+        // a_to_b): token_program, token_authority, whirlpool, token_owner_account_a, vault_a,
+        // token_owner_account_b, vault_b, ...
+        SWAP_DISC => {
+            let (amount_in, amount_out) = swap_amounts(ix, flag(ix, 40)?);
+            WhirlpoolCall::Swap {
+                hops: vec![Hop {
+                    pool: pool(2, ix.mint_of(3), ix.mint_of(5)),
+                    a_to_b: flag(ix, 41)?,
+                }],
+                amount_in,
+                amount_out,
+            }
+        }
+        // swap_v2: same args; whirlpool at [4], token_mint_a/b at [5]/[6].
+        SWAP_V2_DISC => {
+            let (amount_in, amount_out) = swap_amounts(ix, flag(ix, 40)?);
+            WhirlpoolCall::Swap {
+                hops: vec![Hop {
+                    pool: pool(4, ix.get(5), ix.get(6)),
+                    a_to_b: flag(ix, 41)?,
+                }],
+                amount_in,
+                amount_out,
+            }
+        }
+        // two_hop_swap(amount, other_amount_threshold, amount_specified_is_input, a_to_b_one,
+        // a_to_b_two, ..): whirlpool_one/two at [2]/[3], owner accounts one_a/one_b at [4]/[6]
+        // and two_a/two_b at [8]/[10].
+        TWO_HOP_SWAP_DISC => {
+            let (amount_in, amount_out) = swap_amounts(ix, flag(ix, 24)?);
+            WhirlpoolCall::Swap {
+                hops: vec![
+                    Hop {
+                        pool: pool(2, ix.mint_of(4), ix.mint_of(6)),
+                        a_to_b: flag(ix, 25)?,
+                    },
+                    Hop {
+                        pool: pool(3, ix.mint_of(8), ix.mint_of(10)),
+                        a_to_b: flag(ix, 26)?,
+                    },
+                ],
+                amount_in,
+                amount_out,
+            }
+        }
+        // two_hop_swap_v2: same args; whirlpool_one/two at [0]/[1], input, intermediate and
+        // output mints at [2]/[3]/[4].
+        TWO_HOP_SWAP_V2_DISC => {
+            let (amount_in, amount_out) = swap_amounts(ix, flag(ix, 24)?);
+            let (input, middle, output) = (ix.get(2), ix.get(3), ix.get(4));
+            let (a_to_b_one, a_to_b_two) = (flag(ix, 25)?, flag(ix, 26)?);
+            let one = if a_to_b_one {
+                (input, middle)
+            } else {
+                (middle, input)
+            };
+            let two = if a_to_b_two {
+                (middle, output)
+            } else {
+                (output, middle)
+            };
+            WhirlpoolCall::Swap {
+                hops: vec![
+                    Hop {
+                        pool: pool(0, one.0, one.1),
+                        a_to_b: a_to_b_one,
+                    },
+                    Hop {
+                        pool: pool(1, two.0, two.1),
+                        a_to_b: a_to_b_two,
+                    },
+                ],
+                amount_in,
+                amount_out,
+            }
+        }
+        // initialize_pool(bumps, tick_spacing, initial_sqrt_price): whirlpools_config,
+        // token_mint_a/b at [1]/[2], whirlpool at [4] ([6] for v2). No liquidity yet.
+        disc @ (INITIALIZE_POOL_DISC | INITIALIZE_POOL_V2_DISC) => WhirlpoolCall::Pool {
+            action: WatchAction::CreatePool,
+            pool: pool(
+                if disc == INITIALIZE_POOL_DISC { 4 } else { 6 },
+                ix.get(1),
+                ix.get(2),
+            ),
+            position: None,
+            amount_a: None,
+            amount_b: None,
+        },
+        // open_position*(..): funder, owner, position at [2], ..., whirlpool at [5] ([6] with
+        // the metadata account).
+        OPEN_POSITION_DISC | OPEN_POSITION_WITH_TOKEN_EXTENSIONS_DISC => {
+            position(WatchAction::OpenPosition, Some(5))
+        }
+        OPEN_POSITION_WITH_METADATA_DISC => position(WatchAction::OpenPosition, Some(6)),
+        // close_position*: position_authority, receiver, position at [2]; no whirlpool.
+        CLOSE_POSITION_DISC | CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_DISC => {
+            position(WatchAction::ClosePosition, None)
+        }
+        // increase/decrease_liquidity(liquidity_amount: u128, token_a limit, token_b limit):
+        // whirlpool at [0]; v1 has position at [3] and owner accounts at [5]/[6], v2 has
+        // position at [5] and the mints at [7]/[8].
+        INCREASE_LIQUIDITY_DISC => liquidity(
+            WatchAction::AddLiquidity,
+            pool(0, ix.mint_of(5), ix.mint_of(6)),
+            3,
+            AmountBound::Max,
+        ),
+        INCREASE_LIQUIDITY_V2_DISC => liquidity(
+            WatchAction::AddLiquidity,
+            pool(0, ix.get(7), ix.get(8)),
+            5,
+            AmountBound::Max,
+        ),
+        DECREASE_LIQUIDITY_DISC => liquidity(
+            WatchAction::RemoveLiquidity,
+            pool(0, ix.mint_of(5), ix.mint_of(6)),
+            3,
+            AmountBound::Min,
+        ),
+        DECREASE_LIQUIDITY_V2_DISC => liquidity(
+            WatchAction::RemoveLiquidity,
+            pool(0, ix.get(7), ix.get(8)),
+            5,
+            AmountBound::Min,
+        ),
+        _ => return None,
+    })
+}
+
+/// Fill whirlpool ids and mints that other instructions of the same transaction reveal.
+fn resolve(calls: &mut [WhirlpoolCall]) {
+    let mut pool_mints: BTreeMap<Pubkey, (Pubkey, Pubkey)> = BTreeMap::new();
+    let mut position_pools: BTreeMap<Pubkey, Pubkey> = BTreeMap::new();
+    let mut learn = |pool: &Whirlpool| {
+        if let (Some(id), Some(a), Some(b)) = (pool.id, pool.mint_a, pool.mint_b) {
+            pool_mints.insert(id, (a, b));
+        }
+    };
+    for call in calls.iter() {
+        match call {
+            WhirlpoolCall::Pool { pool, position, .. } => {
+                learn(pool);
+                if let (Some(position), Some(id)) = (position, pool.id) {
+                    position_pools.insert(*position, id);
+                }
+            }
+            WhirlpoolCall::Swap { hops, .. } => hops.iter().for_each(|hop| learn(&hop.pool)),
+        }
+    }
+    let fill = |pool: &mut Whirlpool| {
+        if let Some((a, b)) = pool.id.and_then(|id| pool_mints.get(&id)) {
+            pool.mint_a.get_or_insert(*a);
+            pool.mint_b.get_or_insert(*b);
+        }
+    };
+    for call in calls.iter_mut() {
+        match call {
+            WhirlpoolCall::Pool { pool, position, .. } => {
+                if pool.id.is_none() {
+                    pool.id = position.and_then(|p| position_pools.get(&p).copied());
+                }
+                fill(pool);
+            }
+            WhirlpoolCall::Swap { hops, .. } => hops.iter_mut().for_each(|hop| fill(&mut hop.pool)),
+        }
+    }
+}
+
+fn amount(mint: Option<Pubkey>, raw: RawAmount) -> Option<Amount> {
+    let (value, bound) = raw?;
+    Some(match mint {
+        Some(mint) => side_amount(&mint, value, bound),
+        None => Amount::token(value).with_bound(bound),
+    })
+}
+
+impl WhirlpoolCall {
+    fn to_mint_detail(&self) -> Option<MintDetail> {
+        match self {
+            Self::Pool {
+                action,
+                pool,
+                position,
+                amount_a,
+                amount_b,
+            } => {
+                let (id, mint_a, mint_b) = (pool.id?, pool.mint_a?, pool.mint_b?);
+                if is_system_id(&mint_a) || is_system_id(&mint_b) {
+                    return None;
+                }
+                let mut detail = PoolDetail::new(id, mint_a, mint_b);
+                detail.base_amount = amount(Some(mint_a), *amount_a);
+                detail.quote_amount = amount(Some(mint_b), *amount_b);
+                detail.position = *position;
+                Some(detail.to_mint_detail(Protocol::OrcaWhirlpool, *action))
+            }
+            Self::Swap {
+                hops,
+                amount_in,
+                amount_out,
+            } => {
+                let (first, last) = (hops.first()?, hops.last()?);
+                let input = if first.a_to_b {
+                    first.pool.mint_a
+                } else {
+                    first.pool.mint_b
+                };
+                let output = if last.a_to_b {
+                    last.pool.mint_b
+                } else {
+                    last.pool.mint_a
+                };
+                let mut swap = SwapDetail::new(first.pool.id?, input, output);
+                swap.amount_in = amount(input, *amount_in);
+                swap.amount_out = amount(output, *amount_out);
+                swap.route = hops[1..].iter().filter_map(|hop| hop.pool.id).collect();
+                swap.to_mint_detail(Protocol::OrcaWhirlpool)
+            }
+        }
+    }
+}
+
+fn mint_details(program_ids: &[Pubkey], tx: &VersionedTransaction) -> Vec<MintDetail> {
+    let keys = tx.message.static_account_keys();
+    let mut token_accounts = None;
+    let mut calls = Vec::new();
+    for ix in tx.message.instructions() {
+        let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+            continue;
+        };
+        if !program_ids.contains(program_id) {
+            continue;
+        }
+        let view = InstructionView::new(
+            keys,
+            ix,
+            token_accounts.get_or_insert_with(|| token_account_mints(tx)),
+        );
+        calls.extend(decode(&view));
+    }
+    resolve(&mut calls);
+    calls
+        .iter()
+        .filter_map(WhirlpoolCall::to_mint_detail)
+        .collect()
+}
+
+/// Orca Whirlpool finder: reports the token side of each whirlpool touched.
+pub struct OrcaWhirlpoolMintFinder {
+    program_ids: Vec<Pubkey>,
+}
+
+impl OrcaWhirlpoolMintFinder {
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self { program_ids }
+    }
+}
+
+impl Default for OrcaWhirlpoolMintFinder {
+    fn default() -> Self {
+        Self::new(vec![ORCA_WHIRLPOOL])
+    }
+}
+
+impl MintFinder for OrcaWhirlpoolMintFinder {
+    fn find_mints(&self, tx: &VersionedTransaction, _cfg: &ProgramWatchConfig) -> Vec<MintInfo> {
+        amm::mint_infos(mint_details(&self.program_ids, tx))
+    }
+}
+
+/// Orca Whirlpool detailer: pool, mints, amount/threshold limits and swap direction.
+pub struct OrcaWhirlpoolDetailer {
+    program_ids: Vec<Pubkey>,
+}
+
+impl OrcaWhirlpoolDetailer {
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self { program_ids }
+    }
+}
+
+impl Default for OrcaWhirlpoolDetailer {
+    fn default() -> Self {
+        Self::new(vec![ORCA_WHIRLPOOL])
+    }
+}
+
+impl MintDetailer for OrcaWhirlpoolDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        _cfg: &ProgramWatchConfig,
+        _mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        amm::dedup_details(mint_details(&self.program_ids, tx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{
+            test_support::{program_ix, tx_with},
+            WSOL,
+        },
+    };
+
+    const POOL: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const TOKEN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const USER: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const POSITION: Pubkey = Pubkey::new_from_array([4u8; 32]);
+    const POOL_TWO: Pubkey = Pubkey::new_from_array([5u8; 32]);
+    const USDC: Pubkey = Pubkey::new_from_array([6u8; 32]);
+
+    /// Whirlpool instruction with `accounts` placed at their indices and fillers elsewhere.
+    fn swap_args(amount: u64, threshold: u64, flags: &[bool], sqrt_limit_first: bool) -> Vec<u8> {
+        let mut data = amount.to_le_bytes().to_vec();
+        data.extend_from_slice(&threshold.to_le_bytes());
+        if sqrt_limit_first {
+            data.extend_from_slice(&0u128.to_le_bytes());
+        }
+        data.extend(flags.iter().map(|f| *f as u8));
+        data
+    }
+
+    fn liquidity_args(a: u64, b: u64) -> Vec<u8> {
+        let mut data = 1_000u128.to_le_bytes().to_vec();
+        data.extend_from_slice(&a.to_le_bytes());
+        data.extend_from_slice(&b.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn swap_v2_b_to_a_buys_token_side() {
+        // Pool is TOKEN/WSOL; b -> a with exact input is a buy paying exactly 2 SOL.
+        let swap = program_ix(
+            ORCA_WHIRLPOOL,
+            &SWAP_V2_DISC,
+            &swap_args(2_000_000_000, 77_000, &[true, false], true),
+            15,
+            &[(4, POOL), (5, TOKEN), (6, WSOL)],
+        );
+        let tx = tx_with(&USER, &[swap]);
+        let cfg = ProgramWatchConfig::new(vec![ORCA_WHIRLPOOL], Vec::new());
+
+        let events = collect_watch_events(11, &[&tx], &cfg, 0);
+        assert_eq!(events.len(), 1);
+        let detail = &events[0].details[0];
+        assert_eq!(detail.mint, TOKEN);
+        assert_eq!(detail.protocol, Some(Protocol::OrcaWhirlpool));
+        assert_eq!(detail.action, Some(WatchAction::Buy));
+        assert_eq!(detail.sol_amount, Some(Amount::lamports(2_000_000_000)));
+        assert_eq!(
+            detail.token_amount,
+            Some(Amount::token(77_000).with_bound(AmountBound::Min))
+        );
+        assert_eq!(detail.swap.as_ref().unwrap().pool, POOL);
+    }
+
+    #[test]
+    fn two_hop_swap_v2_reports_route_and_end_mints() {
+        // USDC -a_to_b-> WSOL -b_to_a-> TOKEN, exact output.
+        let swap = program_ix(
+            ORCA_WHIRLPOOL,
+            &TWO_HOP_SWAP_V2_DISC,
+            &swap_args(500, 9_000_000, &[false, true, false], false),
+            20,
+            &[(0, POOL), (1, POOL_TWO), (2, USDC), (3, WSOL), (4, TOKEN)],
+        );
+        let tx = tx_with(&USER, &[swap]);
+        let cfg = ProgramWatchConfig::new(vec![ORCA_WHIRLPOOL], Vec::new());
+
+        let details = OrcaWhirlpoolDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].mint, TOKEN);
+        assert_eq!(details[0].action, Some(WatchAction::Trade));
+        let swap = details[0].swap.as_ref().unwrap();
+        assert_eq!(
+            (swap.input_mint, swap.output_mint),
+            (Some(USDC), Some(TOKEN))
+        );
+        assert_eq!((swap.pool, swap.route.clone()), (POOL, vec![POOL_TWO]));
+        assert_eq!(
+            swap.amount_in,
+            Some(Amount::token(9_000_000).with_bound(AmountBound::Max))
+        );
+        assert_eq!(swap.amount_out, Some(Amount::token(500)));
+    }
+
+    #[test]
+    fn positions_resolve_pool_and_mints_from_liquidity_instructions() {
+        let cfg = ProgramWatchConfig::new(vec![ORCA_WHIRLPOOL], Vec::new());
+        let liquidity_accounts = [(0, POOL), (5, POSITION), (7, TOKEN), (8, WSOL)];
+
+        let open = program_ix(
+            ORCA_WHIRLPOOL,
+            &OPEN_POSITION_WITH_TOKEN_EXTENSIONS_DISC,
+            &[0; 9],
+            10,
+            &[(2, POSITION), (5, POOL)],
+        );
+        let increase = program_ix(
+            ORCA_WHIRLPOOL,
+            &INCREASE_LIQUIDITY_V2_DISC,
+            &liquidity_args(40_000, 1_500_000_000),
+            15,
+            &liquidity_accounts,
+        );
+        let details =
+            OrcaWhirlpoolDetailer::default().detail(&tx_with(&USER, &[open, increase]), &cfg, &[]);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].action, Some(WatchAction::OpenPosition));
+        assert_eq!(
+            details[0].sol_amount,
+            Some(Amount::lamports(1_500_000_000).with_bound(AmountBound::Max))
+        );
+        let pool = details[0].pool.as_ref().unwrap();
+        assert_eq!((pool.pool, pool.position), (POOL, Some(POSITION)));
+
+        // close_position names neither the pool nor the mints.
+        let decrease = program_ix(
+            ORCA_WHIRLPOOL,
+            &DECREASE_LIQUIDITY_V2_DISC,
+            &liquidity_args(1, 2),
+            15,
+            &liquidity_accounts,
+        );
+        let close = program_ix(
+            ORCA_WHIRLPOOL,
+            &CLOSE_POSITION_DISC,
+            &[],
+            6,
+            &[(2, POSITION)],
+        );
+        let details = OrcaWhirlpoolDetailer::default().detail(
+            &tx_with(&USER, &[decrease, close.clone()]),
+            &cfg,
+            &[],
+        );
+        assert_eq!(details[0].action, Some(WatchAction::ClosePosition));
+        assert_eq!(
+            details[0].token_amount,
+            Some(Amount::token(1).with_bound(AmountBound::Min))
+        );
+        assert!(OrcaWhirlpoolDetailer::default()
+            .detail(&tx_with(&USER, &[close]), &cfg, &[])
+            .is_empty());
+
+        let init = program_ix(
+            ORCA_WHIRLPOOL,
+            &INITIALIZE_POOL_V2_DISC,
+            &[0; 18],
+            14,
+            &[(1, WSOL), (2, TOKEN), (6, POOL)],
+        );
+        let mints = OrcaWhirlpoolMintFinder::default().find_mints(&tx_with(&USER, &[init]), &cfg);
+        assert_eq!(mints.len(), 1);
+        assert_eq!(
+            (mints[0].mint, mints[0].action),
+            (TOKEN, Some(WatchAction::CreatePool))
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! PumpSwap (pump.fun AMM) instructions: create_pool, buy, buy_exact_quote_in, sell,
+//! deposit and withdraw.
+//!
+//! Every instruction takes `pool` at accounts[0] and `base_mint`/`quote_mint` at
+//! accounts[3]/[4]. Graduated pools are usually token/WSOL, so `buy` means buying the token;
+//! for inverted pools (WSOL base) the action is flipped so it always reads from the token's
+//! side. Amounts are the exact values or limits carried in instruction data.
+
+use super::{
+    amm, insert_mint, is_system_id, read_u64_le, side_amount, AmountBound, MintDetail,
+    MintDetailer, MintFinder, MintInfo, PoolDetail, ProgramWatchConfig, Protocol, WatchAction,
+};
+use solana_sdk::{
+    message::compiled_instruction::CompiledInstruction, pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+use std::collections::BTreeMap;
+
+pub const PUMPSWAP_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+pub(super) const PUMPSWAP: Pubkey = Pubkey::from_str_const(PUMPSWAP_PROGRAM_ID);
+
+const CREATE_POOL_DISC: [u8; 8] = [0xe9, 0x92, 0xd1, 0x8e, 0xcf, 0x68, 0x40, 0xbc];
+const BUY_DISC: [u8; 8] = [0x66, 0x06, 0x3d, 0x12, 0x01, 0xda, 0xeb, 0xea];
+const BUY_EXACT_QUOTE_IN_DISC: [u8; 8] = [0xc6, 0x2e, 0x15, 0x52, 0xb4, 0xd9, 0xe8, 0x70];
+const SELL_DISC: [u8; 8] = [0x33, 0xe6, 0x85, 0xa4, 0x01, 0x7f, 0x83, 0xad];
+const DEPOSIT_DISC: [u8; 8] = [0xf2, 0x23, 0xc6, 0x89, 0x52, 0xe1, 0xf2, 0xb6];
+const WITHDRAW_DISC: [u8; 8] = [0xb7, 0x12, 0x46, 0x9c, 0x94, 0x6d, 0xa1, 0x22];
+
+const POOL_INDEX: usize = 0;
+const BASE_MINT_INDEX: usize = 3;
+const QUOTE_MINT_INDEX: usize = 4;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PumpSwapInstruction {
+    CreatePool,
+    Buy,
+    BuyExactQuoteIn,
+    Sell,
+    Deposit,
+    Withdraw,
+}
+
+impl PumpSwapInstruction {
+    fn from_data(data: &[u8]) -> Option<Self> {
+        let disc: [u8; 8] = data.get(..8)?.try_into().ok()?;
+        Some(match disc {
+            CREATE_POOL_DISC => Self::CreatePool,
+            BUY_DISC => Self::Buy,
+            BUY_EXACT_QUOTE_IN_DISC => Self::BuyExactQuoteIn,
+            SELL_DISC => Self::Sell,
+            DEPOSIT_DISC => Self::Deposit,
+            WITHDRAW_DISC => Self::Withdraw,
+            _ => return None,
+        })
+    }
+
+    /// Action in PumpSwap's own orientation (buy = receive base).
+    fn action(self) -> WatchAction {
+        match self {
+            Self::CreatePool => WatchAction::CreatePool,
+            Self::Buy | Self::BuyExactQuoteIn => WatchAction::Buy,
+            Self::Sell => WatchAction::Sell,
+            Self::Deposit => WatchAction::AddLiquidity,
+            Self::Withdraw => WatchAction::RemoveLiquidity,
+        }
+    }
+}
+
+/// A decoded PumpSwap instruction.
+struct PumpSwapCall {
+    kind: PumpSwapInstruction,
+    pool: PoolDetail,
+}
+
+impl PumpSwapCall {
+    /// Action from the token's side: buy/sell flip on WSOL-base pools.
+    fn action(&self) -> WatchAction {
+        match (self.kind.action(), self.pool.is_inverted()) {
+            (WatchAction::Buy, true) => WatchAction::Sell,
+            (WatchAction::Sell, true) => WatchAction::Buy,
+            (action, _) => action,
+        }
+    }
+}
+
+fn decode(keys: &[Pubkey], ix: &CompiledInstruction) -> Option<PumpSwapCall> {
+    let kind = PumpSwapInstruction::from_data(&ix.data)?;
+    let account = |index: usize| {
+        ix.accounts
+            .get(index)
+            .and_then(|i| keys.get(*i as usize))
+            .copied()
+    };
+    let base_mint = account(BASE_MINT_INDEX)?;
+    let quote_mint = account(QUOTE_MINT_INDEX)?;
+    if is_system_id(&base_mint) || is_system_id(&quote_mint) {
+        return None;
+    }
+    let mut pool = PoolDetail::new(account(POOL_INDEX)?, base_mint, quote_mint);
+    let base = |offset: usize, bound: AmountBound| {
+        read_u64_le(&ix.data, offset).map(|v| side_amount(&base_mint, v, bound))
+    };
+    let quote = |offset: usize, bound: AmountBound| {
+        read_u64_le(&ix.data, offset).map(|v| side_amount(&quote_mint, v, bound))
+    };
+    let lp = |offset: usize| read_u64_le(&ix.data, offset).map(super::Amount::token);
+    match kind {
+        // create_pool(index: u16, base_amount_in, quote_amount_in, ..)
+        PumpSwapInstruction::CreatePool => {
+            pool.base_amount = base(10, AmountBound::Exact);
+            pool.quote_amount = quote(18, AmountBound::Exact);
+        }
+        // buy(base_amount_out, max_quote_amount_in)
+        PumpSwapInstruction::Buy => {
+            pool.base_amount = base(8, AmountBound::Exact);
+            pool.quote_amount = quote(16, AmountBound::Max);
+        }
+        // buy_exact_quote_in(spendable_quote_in, min_base_amount_out)
+        PumpSwapInstruction::BuyExactQuoteIn => {
+            pool.quote_amount = quote(8, AmountBound::Exact);
+            pool.base_amount = base(16, AmountBound::Min);
+        }
+        // sell(base_amount_in, min_quote_amount_out)
+        PumpSwapInstruction::Sell => {
+            pool.base_amount = base(8, AmountBound::Exact);
+            pool.quote_amount = quote(16, AmountBound::Min);
+        }
+        // deposit(lp_token_amount_out, max_base_amount_in, max_quote_amount_in)
+        PumpSwapInstruction::Deposit => {
+            pool.lp_amount = lp(8);
+            pool.base_amount = base(16, AmountBound::Max);
+            pool.quote_amount = quote(24, AmountBound::Max);
+        }
+        // withdraw(lp_token_amount_in, min_base_amount_out, min_quote_amount_out)
+        PumpSwapInstruction::Withdraw => {
+            pool.lp_amount = lp(8);
+            pool.base_amount = base(16, AmountBound::Min);
+            pool.quote_amount = quote(24, AmountBound::Min);
+        }
+    }
+    Some(PumpSwapCall { kind, pool })
+}
+
+fn calls<'a>(
+    program_ids: &'a [Pubkey],
+    tx: &'a VersionedTransaction,
+) -> impl Iterator<Item = PumpSwapCall> + 'a {
+    let keys = tx.message.static_account_keys();
+    tx.message.instructions().iter().filter_map(move |ix| {
+        let program_id = keys.get(ix.program_id_index as usize)?;
+        if !program_ids.contains(program_id) {
+            return None;
+        }
+        decode(keys, ix)
+    })
+}
+
+/// PumpSwap finder: reports the non-SOL mint of each pool touched.
+pub struct PumpSwapMintFinder {
+    program_ids: Vec<Pubkey>,
+}
+
+impl PumpSwapMintFinder {
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self { program_ids }
+    }
+}
+
+impl Default for PumpSwapMintFinder {
+    fn default() -> Self {
+        Self::new(vec![PUMPSWAP])
+    }
+}
+
+impl MintFinder for PumpSwapMintFinder {
+    fn find_mints(&self, tx: &VersionedTransaction, _cfg: &ProgramWatchConfig) -> Vec<MintInfo> {
+        let mut mints = BTreeMap::new();
+        for call in calls(&self.program_ids, tx) {
+            insert_mint(
+                &mut mints,
+                MintInfo {
+                    mint: call.pool.token_mint(),
+                    protocol: Some(Protocol::PumpSwap),
+                    action: Some(call.action()),
+                },
+            );
+        }
+        mints.into_values().collect()
+    }
+}
+
+/// PumpSwap detailer: pool, base/quote mints and amounts for every PumpSwap instruction.
+pub struct PumpSwapDetailer {
+    program_ids: Vec<Pubkey>,
+}
+
+impl PumpSwapDetailer {
+    pub fn new(program_ids: Vec<Pubkey>) -> Self {
+        Self { program_ids }
+    }
+}
+
+impl Default for PumpSwapDetailer {
+    fn default() -> Self {
+        Self::new(vec![PUMPSWAP])
+    }
+}
+
+impl MintDetailer for PumpSwapDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        _cfg: &ProgramWatchConfig,
+        _mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        amm::dedup_details(
+            calls(&self.program_ids, tx)
+                .map(|call| call.pool.to_mint_detail(Protocol::PumpSwap, call.action()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{
+            test_support::{program_ix, tx_with, u64_args},
+            Amount, WSOL,
+        },
+    };
+    use solana_sdk::instruction::Instruction;
+
+    const POOL: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const TOKEN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const USER: Pubkey = Pubkey::new_from_array([3u8; 32]);
+
+    fn pumpswap_ix(disc: [u8; 8], args: &[u64], base: Pubkey, quote: Pubkey) -> Instruction {
+        let accounts = [(0, POOL), (1, USER), (3, base), (4, quote)];
+        program_ix(PUMPSWAP, &disc, &u64_args(args), 6, &accounts)
+    }
+
+    #[test]
+    fn buy_on_token_wsol_pool_reports_token_buy_with_sol_limit() {
+        let tx = tx_with(
+            &USER,
+            &[pumpswap_ix(BUY_DISC, &[5_000, 2_000_000_000], TOKEN, WSOL)],
+        );
+        let cfg = ProgramWatchConfig::new(vec![PUMPSWAP], Vec::new());
+
+        let events = collect_watch_events(9, &[&tx], &cfg, 0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].details.len(),
+            1,
+            "pump.fun detailer must not misread PumpSwap"
+        );
+        let detail = &events[0].details[0];
+        assert_eq!(detail.mint, TOKEN);
+        assert_eq!(detail.protocol, Some(Protocol::PumpSwap));
+        assert_eq!(detail.action, Some(WatchAction::Buy));
+        assert_eq!(detail.token_amount, Some(Amount::token(5_000)));
+        assert_eq!(
+            detail.sol_amount,
+            Some(Amount::lamports(2_000_000_000).with_bound(AmountBound::Max))
+        );
+        let pool = detail.pool.as_ref().expect("pool detail");
+        assert_eq!(
+            (pool.pool, pool.base_mint, pool.quote_mint),
+            (POOL, TOKEN, WSOL)
+        );
+    }
+
+    #[test]
+    fn inverted_pool_flips_sell_into_token_buy() {
+        // WSOL base: selling base (SOL) for quote means buying the token.
+        let tx = tx_with(&USER, &[pumpswap_ix(SELL_DISC, &[700, 42], WSOL, TOKEN)]);
+        let cfg = ProgramWatchConfig::new(vec![PUMPSWAP], Vec::new());
+
+        let details = PumpSwapDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details[0].mint, TOKEN);
+        assert_eq!(details[0].action, Some(WatchAction::Buy));
+        assert_eq!(details[0].sol_amount, Some(Amount::lamports(700)));
+        assert_eq!(
+            details[0].token_amount,
+            Some(Amount::token(42).with_bound(AmountBound::Min))
+        );
+    }
+
+    #[test]
+    fn create_pool_and_liquidity_instructions() {
+        let mut create = pumpswap_ix(CREATE_POOL_DISC, &[], TOKEN, WSOL);
+        create.data.extend_from_slice(&0u16.to_le_bytes());
+        create.data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        create
+            .data
+            .extend_from_slice(&85_000_000_000u64.to_le_bytes());
+        let deposit = pumpswap_ix(DEPOSIT_DISC, &[10, 20, 30], TOKEN, WSOL);
+        let tx = tx_with(&USER, &[create, deposit]);
+        let cfg = ProgramWatchConfig::new(vec![PUMPSWAP], Vec::new());
+
+        let details = PumpSwapDetailer::default().detail(&tx, &cfg, &[]);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].action, Some(WatchAction::CreatePool));
+        assert_eq!(
+            details[0].sol_amount,
+            Some(Amount::lamports(85_000_000_000))
+        );
+        assert_eq!(details[0].token_amount, Some(Amount::token(1_000_000)));
+
+        let withdraw = tx_with(
+            &USER,
+            &[pumpswap_ix(WITHDRAW_DISC, &[10, 20, 30], TOKEN, WSOL)],
+        );
+        let details = PumpSwapDetailer::default().detail(&withdraw, &cfg, &[]);
+        let pool = details[0].pool.as_ref().unwrap();
+        assert_eq!(details[0].action, Some(WatchAction::RemoveLiquidity));
+        assert_eq!(pool.lp_amount, Some(Amount::token(10)));
+        assert_eq!(
+            pool.quote_amount,
+            Some(Amount::lamports(30).with_bound(AmountBound::Min))
+        );
+
+        let finder = PumpSwapMintFinder::default().find_mints(&tx, &cfg);
+        assert_eq!(finder.len(), 1);
+        assert_eq!(finder[0].protocol, Some(Protocol::PumpSwap));
+    }
+}
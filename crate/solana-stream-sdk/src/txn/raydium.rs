@@ -0,0 +1,413 @@
+//! Raydium AMM v4, CPMM and CLMM instructions: pool creation, swaps and liquidity changes.
+//!
+//! CPMM instructions and the CLMM `*_v2` variants pass both mints as accounts. AMM v4 and the
+//! original CLMM `swap`/`increase_liquidity`/`decrease_liquidity` only take token accounts, so
+//! their mints are resolved with [`token_account_mints`](super::token_account_mints) (ATAs or WSOL accounts created in the
+//! same transaction). A swap with neither mint resolved, or a pool instruction missing one, is
+//! not reported. Amounts are the exact values or limits carried in instruction data.
+
+use super::{
+    amm::{anchor_disc, AmmCall, AmmDetailer, AmmMintFinder, AmmProgram, InstructionView},
+    read_u64_le, Amount, AmountBound, Protocol, WatchAction,
+};
+use solana_sdk::pubkey::Pubkey;
+
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const RAYDIUM_CPMM_PROGRAM_ID: &str = "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C";
+pub const RAYDIUM_CLMM_PROGRAM_ID: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+const RAYDIUM_AMM_V4: Pubkey = Pubkey::from_str_const(RAYDIUM_AMM_V4_PROGRAM_ID);
+const RAYDIUM_CPMM: Pubkey = Pubkey::from_str_const(RAYDIUM_CPMM_PROGRAM_ID);
+const RAYDIUM_CLMM: Pubkey = Pubkey::from_str_const(RAYDIUM_CLMM_PROGRAM_ID);
+
+// AMM v4 is not an Anchor program: the first data byte is the instruction tag.
+const AMM_V4_INITIALIZE2: u8 = 1;
+const AMM_V4_DEPOSIT: u8 = 3;
+const AMM_V4_WITHDRAW: u8 = 4;
+const AMM_V4_SWAP_BASE_IN: u8 = 9;
+const AMM_V4_SWAP_BASE_OUT: u8 = 11;
+const AMM_V4_SWAP_BASE_IN_V2: u8 = 16;
+const AMM_V4_SWAP_BASE_OUT_V2: u8 = 17;
+
+const CPMM_INITIALIZE_DISC: [u8; 8] = [0xaf, 0xaf, 0x6d, 0x1f, 0x0d, 0x98, 0x9b, 0xed];
+const CPMM_DEPOSIT_DISC: [u8; 8] = [0xf2, 0x23, 0xc6, 0x89, 0x52, 0xe1, 0xf2, 0xb6];
+const CPMM_WITHDRAW_DISC: [u8; 8] = [0xb7, 0x12, 0x46, 0x9c, 0x94, 0x6d, 0xa1, 0x22];
+const CPMM_SWAP_BASE_INPUT_DISC: [u8; 8] = [0x8f, 0xbe, 0x5a, 0xda, 0xc4, 0x1e, 0x33, 0xde];
+const CPMM_SWAP_BASE_OUTPUT_DISC: [u8; 8] = [0x37, 0xd9, 0x62, 0x56, 0xa3, 0x4a, 0xb4, 0xad];
+
+const CLMM_CREATE_POOL_DISC: [u8; 8] = [0xe9, 0x92, 0xd1, 0x8e, 0xcf, 0x68, 0x40, 0xbc];
+const CLMM_SWAP_DISC: [u8; 8] = [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+const CLMM_SWAP_V2_DISC: [u8; 8] = [0x2b, 0x04, 0xed, 0x0b, 0x1a, 0xc9, 0x1e, 0x62];
+const CLMM_OPEN_POSITION_V2_DISC: [u8; 8] = [0x4d, 0xb8, 0x4a, 0xd6, 0x70, 0x56, 0xf1, 0xc7];
+const CLMM_OPEN_POSITION_WITH_TOKEN22_NFT_DISC: [u8; 8] =
+    [0x4d, 0xff, 0xae, 0x52, 0x7d, 0x1d, 0xc9, 0x2e];
+const CLMM_INCREASE_LIQUIDITY_DISC: [u8; 8] = [0x2e, 0x9c, 0xf3, 0x76, 0x0d, 0xcd, 0xfb, 0xb2];
+const CLMM_INCREASE_LIQUIDITY_V2_DISC: [u8; 8] = [0x85, 0x1d, 0x59, 0xdf, 0x45, 0xee, 0xb0, 0x0a];
+const CLMM_DECREASE_LIQUIDITY_DISC: [u8; 8] = [0xa0, 0x26, 0xd0, 0x6f, 0x68, 0x5b, 0x2c, 0x01];
+const CLMM_DECREASE_LIQUIDITY_V2_DISC: [u8; 8] = [0x3a, 0x7f, 0xbc, 0x3e, 0x4f, 0x52, 0xc4, 0x60];
+
+/// Which Raydium program a watched program id speaks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum RaydiumProgram {
+    AmmV4,
+    Cpmm,
+    Clmm,
+}
+
+impl RaydiumProgram {
+    pub const ALL: [RaydiumProgram; 3] = [Self::AmmV4, Self::Cpmm, Self::Clmm];
+
+    /// Mainnet program id.
+    pub fn program_id(self) -> Pubkey {
+        match self {
+            Self::AmmV4 => RAYDIUM_AMM_V4,
+            Self::Cpmm => RAYDIUM_CPMM,
+            Self::Clmm => RAYDIUM_CLMM,
+        }
+    }
+
+    pub fn from_program_id(id: &Pubkey) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.program_id() == *id)
+    }
+
+    pub fn protocol(self) -> Protocol {
+        match self {
+            Self::AmmV4 => Protocol::RaydiumAmm,
+            Self::Cpmm => Protocol::RaydiumCpmm,
+            Self::Clmm => Protocol::RaydiumClmm,
+        }
+    }
+}
+
+impl AmmProgram for RaydiumProgram {
+    const PROGRAMS: &'static [Self] = &Self::ALL;
+
+    fn program_id(self) -> Pubkey {
+        RaydiumProgram::program_id(self)
+    }
+
+    fn protocol(self) -> Protocol {
+        RaydiumProgram::protocol(self)
+    }
+
+    fn decode(self, ix: &InstructionView<'_>) -> Option<AmmCall> {
+        match self {
+            Self::AmmV4 => decode_amm_v4(ix),
+            Self::Cpmm => decode_cpmm(ix),
+            Self::Clmm => decode_clmm(ix),
+        }
+    }
+}
+
+fn decode_amm_v4(ix: &InstructionView<'_>) -> Option<AmmCall> {
+    use AmountBound::{Exact, Max, Min};
+    match *ix.data().first()? {
+        // initialize2(nonce: u8, open_time, init_pc_amount, init_coin_amount); amm at [4],
+        // coin/pc mints at [8]/[9].
+        AMM_V4_INITIALIZE2 => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(4), ix.get(8), ix.get(9)),
+            Some((18, 10, Exact)),
+        ),
+        // deposit(max_coin_amount, max_pc_amount, base_side); user coin/pc accounts at [9]/[10].
+        AMM_V4_DEPOSIT => ix.pool(
+            WatchAction::AddLiquidity,
+            (ix.get(1), ix.mint_of(9), ix.mint_of(10)),
+            Some((1, 9, Max)),
+        ),
+        // withdraw(amount): LP tokens burned; user coin/pc accounts at [16]/[17].
+        AMM_V4_WITHDRAW => {
+            let mut call = ix.pool(
+                WatchAction::RemoveLiquidity,
+                (ix.get(1), ix.mint_of(16), ix.mint_of(17)),
+                None,
+            )?;
+            if let AmmCall::Pool { pool, .. } = &mut call {
+                pool.lp_amount = read_u64_le(ix.data(), 1).map(Amount::token);
+            }
+            Some(call)
+        }
+        // Swaps end with user_source, user_destination, user_owner in every account layout
+        // (17/18 accounts with the OpenBook market, 8 for the v2 variants).
+        tag @ (AMM_V4_SWAP_BASE_IN
+        | AMM_V4_SWAP_BASE_OUT
+        | AMM_V4_SWAP_BASE_IN_V2
+        | AMM_V4_SWAP_BASE_OUT_V2) => {
+            let source = ix.len().checked_sub(3)?;
+            let accounts = (ix.get(1), ix.mint_of(source), ix.mint_of(source + 1));
+            if matches!(tag, AMM_V4_SWAP_BASE_IN | AMM_V4_SWAP_BASE_IN_V2) {
+                // swap_base_in(amount_in, minimum_amount_out)
+                ix.swap(accounts, (1, Exact), (9, Min))
+            } else {
+                // swap_base_out(max_amount_in, amount_out)
+                ix.swap(accounts, (1, Max), (9, Exact))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn decode_cpmm(ix: &InstructionView<'_>) -> Option<AmmCall> {
+    use AmountBound::{Exact, Max, Min};
+    match anchor_disc(ix.data())? {
+        // initialize(init_amount_0, init_amount_1, open_time); pool at [3], mints at [4]/[5].
+        CPMM_INITIALIZE_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(3), ix.get(4), ix.get(5)),
+            Some((8, 16, Exact)),
+        ),
+        // deposit/withdraw(lp_token_amount, token_0 limit, token_1 limit); pool at [2],
+        // vault mints at [10]/[11].
+        disc @ (CPMM_DEPOSIT_DISC | CPMM_WITHDRAW_DISC) => {
+            let (action, bound) = if disc == CPMM_DEPOSIT_DISC {
+                (WatchAction::AddLiquidity, Max)
+            } else {
+                (WatchAction::RemoveLiquidity, Min)
+            };
+            let mut call = ix.pool(
+                action,
+                (ix.get(2), ix.get(10), ix.get(11)),
+                Some((16, 24, bound)),
+            )?;
+            if let AmmCall::Pool { pool, .. } = &mut call {
+                pool.lp_amount = read_u64_le(ix.data(), 8).map(Amount::token);
+            }
+            Some(call)
+        }
+        // swap_base_input(amount_in, minimum_amount_out); pool at [3], mints at [10]/[11].
+        CPMM_SWAP_BASE_INPUT_DISC => {
+            ix.swap((ix.get(3), ix.get(10), ix.get(11)), (8, Exact), (16, Min))
+        }
+        // swap_base_output(max_amount_in, amount_out)
+        CPMM_SWAP_BASE_OUTPUT_DISC => {
+            ix.swap((ix.get(3), ix.get(10), ix.get(11)), (8, Max), (16, Exact))
+        }
+        _ => None,
+    }
+}
+
+fn decode_clmm(ix: &InstructionView<'_>) -> Option<AmmCall> {
+    use AmountBound::{Exact, Max, Min};
+    match anchor_disc(ix.data())? {
+        // create_pool(sqrt_price_x64: u128, open_time); pool at [2], mints at [3]/[4]. No
+        // liquidity is added yet, so there are no amounts.
+        CLMM_CREATE_POOL_DISC => ix.pool(
+            WatchAction::CreatePool,
+            (ix.get(2), ix.get(3), ix.get(4)),
+            None,
+        ),
+        // swap(amount, other_amount_threshold, sqrt_price_limit_x64: u128, is_base_input); pool
+        // at [2]. v1 passes the user token accounts at [3]/[4], v2 adds the mints at [11]/[12].
+        disc @ (CLMM_SWAP_DISC | CLMM_SWAP_V2_DISC) => {
+            let accounts = if disc == CLMM_SWAP_V2_DISC {
+                (ix.get(2), ix.get(11), ix.get(12))
+            } else {
+                (ix.get(2), ix.mint_of(3), ix.mint_of(4))
+            };
+            if *ix.data().get(40)? != 0 {
+                ix.swap(accounts, (8, Exact), (16, Min))
+            } else {
+                ix.swap(accounts, (16, Max), (8, Exact))
+            }
+        }
+        // open_position_v2(tick_lower, tick_upper, tick_array_lower_start, tick_array_upper_start,
+        // liquidity: u128, amount_0_max, amount_1_max, ..); pool at [5], mints at [20]/[21].
+        CLMM_OPEN_POSITION_V2_DISC => ix.pool(
+            WatchAction::AddLiquidity,
+            (ix.get(5), ix.get(20), ix.get(21)),
+            Some((40, 48, Max)),
+        ),
+        // Same args; no metadata account, so pool at [4] and mints at [18]/[19].
+        CLMM_OPEN_POSITION_WITH_TOKEN22_NFT_DISC => ix.pool(
+            WatchAction::AddLiquidity,
+            (ix.get(4), ix.get(18), ix.get(19)),
+            Some((40, 48, Max)),
+        ),
+        // increase_liquidity(liquidity: u128, amount_0_max, amount_1_max); pool at [2], user
+        // token accounts at [7]/[8]; v2 adds the vault mints at [13]/[14].
+        CLMM_INCREASE_LIQUIDITY_DISC => ix.pool(
+            WatchAction::AddLiquidity,
+            (ix.get(2), ix.mint_of(7), ix.mint_of(8)),
+            Some((24, 32, Max)),
+        ),
+        CLMM_INCREASE_LIQUIDITY_V2_DISC => ix.pool(
+            WatchAction::AddLiquidity,
+            (ix.get(2), ix.get(13), ix.get(14)),
+            Some((24, 32, Max)),
+        ),
+        // decrease_liquidity(liquidity: u128, amount_0_min, amount_1_min); pool at [3],
+        // recipient token accounts at [9]/[10]; v2 adds the vault mints at [14]/[15].
+        CLMM_DECREASE_LIQUIDITY_DISC => ix.pool(
+            WatchAction::RemoveLiquidity,
+            (ix.get(3), ix.mint_of(9), ix.mint_of(10)),
+            Some((24, 32, Min)),
+        ),
+        CLMM_DECREASE_LIQUIDITY_V2_DISC => ix.pool(
+            WatchAction::RemoveLiquidity,
+            (ix.get(3), ix.get(14), ix.get(15)),
+            Some((24, 32, Min)),
+        ),
+        _ => None,
+    }
+}
+
+/// Raydium finder: reports the token side of each pool or swap touched.
+pub type RaydiumMintFinder = AmmMintFinder<RaydiumProgram>;
+
+/// Raydium detailer: pool, mints and amount limits for AMM v4, CPMM and CLMM instructions.
+pub type RaydiumDetailer = AmmDetailer<RaydiumProgram>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{MintDetail, MintDetailer, MintFinder, ProgramWatchConfig, WSOL},
+    };
+    use solana_sdk::transaction::VersionedTransaction;
+    use std::str::FromStr;
+
+    /// Wire-format transaction from `txn/fixtures` (see the README there).
+    fn fixture(hex_bytes: &str) -> VersionedTransaction {
+        let bytes = hex::decode(hex_bytes.trim()).expect("fixture hex");
+        bincode::deserialize(&bytes).expect("fixture transaction")
+    }
+
+    fn key(raw: &str) -> Pubkey {
+        Pubkey::from_str(raw).unwrap()
+    }
+
+    fn detail_of(tx: &VersionedTransaction) -> MintDetail {
+        let cfg = ProgramWatchConfig::new(vec![RAYDIUM_AMM_V4], Vec::new());
+        let mut details = RaydiumDetailer::default().detail(tx, &cfg, &[]);
+        assert_eq!(details.len(), 1, "{details:?}");
+        details.remove(0)
+    }
+
+    #[test]
+    fn amm_v4_swap_resolves_mints_from_created_token_accounts() {
+        let tx = fixture(include_str!("fixtures/raydium_amm_v4_swap_base_in.hex"));
+        let token = key("BuXpYrkD8fNdkti9dPY5iw3TjraShEV1zpWDjKs4i6pG");
+        let cfg = ProgramWatchConfig::new(vec![RAYDIUM_AMM_V4], Vec::new());
+
+        let events = collect_watch_events(7, &[&tx], &cfg, 0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].details.len(), 1, "{:?}", events[0].details);
+        let detail = &events[0].details[0];
+        assert_eq!(detail.mint, token);
+        assert_eq!(detail.protocol, Some(Protocol::RaydiumAmm));
+        assert_eq!(detail.action, Some(WatchAction::Buy));
+        assert_eq!(detail.lamports(), Some(1_500_000_000));
+        assert_eq!(
+            detail.token_amount,
+            Some(Amount::token(48_213_377_000).with_bound(AmountBound::Min))
+        );
+        let swap = detail.swap.as_ref().expect("swap detail");
+        assert_eq!(
+            swap.pool,
+            key("FxGWm6QtgEoV8zDHGLdRarU66SwNnvGrFqh6HP1k7WXe")
+        );
+        assert_eq!(
+            (swap.input_mint, swap.output_mint),
+            (Some(WSOL), Some(token))
+        );
+    }
+
+    #[test]
+    fn amm_v4_initialize2_reports_pool_creation() {
+        let tx = fixture(include_str!("fixtures/raydium_amm_v4_initialize2.hex"));
+        let detail = detail_of(&tx);
+        assert_eq!(detail.action, Some(WatchAction::CreatePool));
+        assert_eq!(
+            detail.mint,
+            key("6GXVfXzJA96ishaUffHuNNonUfDHB3po4BC9kGQjnCg")
+        );
+        assert_eq!(detail.sol_amount, Some(Amount::lamports(79_005_359_123)));
+        assert_eq!(
+            detail.token_amount,
+            Some(Amount::token(206_900_000_000_000))
+        );
+        let pool = detail.pool.expect("pool detail");
+        assert_eq!(
+            pool.pool,
+            key("FEeqTcXDCthcn1ndVNk8hrjZqpUafztaDGaZoQyXjMKZ")
+        );
+        assert_eq!(pool.quote_mint, WSOL);
+    }
+
+    #[test]
+    fn cpmm_swap_and_deposit() {
+        let tx = fixture(include_str!("fixtures/raydium_cpmm_swap_base_input.hex"));
+        let detail = detail_of(&tx);
+        assert_eq!(
+            detail.mint,
+            key("AU1UtkeCzeQtqd6iLtLK76a4CA5XB6FMNFLvNMqPayju")
+        );
+        assert_eq!(detail.protocol, Some(Protocol::RaydiumCpmm));
+        assert_eq!(detail.action, Some(WatchAction::Sell));
+        assert_eq!(detail.token_amount, Some(Amount::token(3_250_000_000_000)));
+        assert_eq!(
+            detail.sol_amount,
+            Some(Amount::lamports(912_000_000).with_bound(AmountBound::Min))
+        );
+
+        // WSOL is token_0 here, so the pool reads as inverted.
+        let tx = fixture(include_str!("fixtures/raydium_cpmm_deposit.hex"));
+        let detail = detail_of(&tx);
+        assert_eq!(detail.action, Some(WatchAction::AddLiquidity));
+        assert_eq!(
+            detail.sol_amount,
+            Some(Amount::lamports(2_000_000_000).with_bound(AmountBound::Max))
+        );
+        let pool = detail.pool.expect("pool detail");
+        assert_eq!(pool.base_mint, WSOL);
+        assert_eq!(pool.lp_amount, Some(Amount::token(44_721_359)));
+    }
+
+    #[test]
+    fn clmm_swap_v2_and_decrease_liquidity_v2() {
+        let tx = fixture(include_str!("fixtures/raydium_clmm_swap_v2.hex"));
+        let detail = detail_of(&tx);
+        assert_eq!(detail.protocol, Some(Protocol::RaydiumClmm));
+        // USDC -> token is neither a SOL buy nor sell.
+        assert_eq!(detail.action, Some(WatchAction::Trade));
+        assert_eq!(detail.token_amount, Some(Amount::token(5_000_000_000)));
+        let swap = detail.swap.expect("swap detail");
+        assert_eq!(
+            swap.input_mint,
+            Some(key("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"))
+        );
+        assert_eq!(
+            swap.amount_in,
+            Some(Amount::token(1_250_000_000).with_bound(AmountBound::Max))
+        );
+
+        let tx = fixture(include_str!(
+            "fixtures/raydium_clmm_decrease_liquidity_v2.hex"
+        ));
+        let detail = detail_of(&tx);
+        assert_eq!(detail.action, Some(WatchAction::RemoveLiquidity));
+        assert_eq!(
+            detail.mint,
+            key("GMuNdu9f3oRRbTNVANKTYj4mynGLJoqgcyDELd6VSqiv")
+        );
+        assert_eq!(
+            detail.sol_amount,
+            Some(Amount::lamports(3_000_000_000).with_bound(AmountBound::Min))
+        );
+    }
+
+    #[test]
+    fn unknown_program_ids_are_ignored_unless_mapped() {
+        let tx = fixture(include_str!("fixtures/raydium_cpmm_swap_base_input.hex"));
+        let cfg = ProgramWatchConfig::new(vec![RAYDIUM_CPMM], Vec::new());
+        assert!(RaydiumDetailer::new(vec![RAYDIUM_CLMM])
+            .detail(&tx, &cfg, &[])
+            .is_empty());
+        let finder =
+            RaydiumMintFinder::new(Vec::new()).with_program(RAYDIUM_CPMM, RaydiumProgram::Cpmm);
+        assert_eq!(
+            finder.find_mints(&tx, &cfg)[0].action,
+            Some(WatchAction::Sell)
+        );
+    }
+}
@@ -0,0 +1,837 @@
+//! SPL Token / Token-2022 instruction decoder.
+//!
+//! Decodes the base instructions that move or lock tokens (transfers, mint-to, burns, account
+//! closes, approvals, freezes) and authority changes, so token flows and mint/freeze authority
+//! revocations can be followed for a set of watched mints (see
+//! [`ProgramWatchConfig::with_token_mints`]). Token-2022 extension instructions are not decoded.
+//!
+//! Transfers, approvals and closes only pass token accounts; their mint is known when the
+//! account is created in the same transaction (see [`token_account_mints`]).
+
+use super::{
+    default_token_program_ids, token_account_mints_for, Amount, InstructionDecoder,
+    InstructionEvent, KeySet, MintDetail, MintDetailer, MintInfo, NamedAccount, ProgramHit,
+    ProgramWatchConfig, Protocol, TxMeta, WatchAction,
+};
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use std::collections::BTreeMap;
+
+const TRANSFER: u8 = 3;
+const APPROVE: u8 = 4;
+const SET_AUTHORITY: u8 = 6;
+const MINT_TO: u8 = 7;
+const BURN: u8 = 8;
+const CLOSE_ACCOUNT: u8 = 9;
+const FREEZE_ACCOUNT: u8 = 10;
+const THAW_ACCOUNT: u8 = 11;
+const TRANSFER_CHECKED: u8 = 12;
+const APPROVE_CHECKED: u8 = 13;
+const MINT_TO_CHECKED: u8 = 14;
+const BURN_CHECKED: u8 = 15;
+
+/// `SetAuthority` authority types; Token-2022 adds the extension authorities after
+/// `CloseAccount`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum AuthorityType {
+    MintTokens,
+    FreezeAccount,
+    AccountOwner,
+    CloseAccount,
+    TransferFeeConfig,
+    WithheldWithdraw,
+    CloseMint,
+    InterestRate,
+    PermanentDelegate,
+    ConfidentialTransferMint,
+    TransferHookProgramId,
+    ConfidentialTransferFeeConfig,
+    MetadataPointer,
+    GroupPointer,
+    GroupMemberPointer,
+    ScaledUiAmount,
+    Pause,
+}
+
+impl AuthorityType {
+    const ALL: [AuthorityType; 17] = [
+        Self::MintTokens,
+        Self::FreezeAccount,
+        Self::AccountOwner,
+        Self::CloseAccount,
+        Self::TransferFeeConfig,
+        Self::WithheldWithdraw,
+        Self::CloseMint,
+        Self::InterestRate,
+        Self::PermanentDelegate,
+        Self::ConfidentialTransferMint,
+        Self::TransferHookProgramId,
+        Self::ConfidentialTransferFeeConfig,
+        Self::MetadataPointer,
+        Self::GroupPointer,
+        Self::GroupMemberPointer,
+        Self::ScaledUiAmount,
+        Self::Pause,
+    ];
+
+    pub fn from_u8(tag: u8) -> Option<Self> {
+        Self::ALL.get(tag as usize).copied()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MintTokens => "mint_tokens",
+            Self::FreezeAccount => "freeze_account",
+            Self::AccountOwner => "account_owner",
+            Self::CloseAccount => "close_account",
+            Self::TransferFeeConfig => "transfer_fee_config",
+            Self::WithheldWithdraw => "withheld_withdraw",
+            Self::CloseMint => "close_mint",
+            Self::InterestRate => "interest_rate",
+            Self::PermanentDelegate => "permanent_delegate",
+            Self::ConfidentialTransferMint => "confidential_transfer_mint",
+            Self::TransferHookProgramId => "transfer_hook_program_id",
+            Self::ConfidentialTransferFeeConfig => "confidential_transfer_fee_config",
+            Self::MetadataPointer => "metadata_pointer",
+            Self::GroupPointer => "group_pointer",
+            Self::GroupMemberPointer => "group_member_pointer",
+            Self::ScaledUiAmount => "scaled_ui_amount",
+            Self::Pause => "pause",
+        }
+    }
+
+    /// True when the authority belongs to a token account rather than the mint.
+    pub fn is_account_authority(&self) -> bool {
+        matches!(self, Self::AccountOwner | Self::CloseAccount)
+    }
+}
+
+/// A decoded SPL Token / Token-2022 instruction. `decimals` is only carried by the `*Checked`
+/// variants.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TokenInstruction {
+    Transfer {
+        source: Pubkey,
+        /// Present for `TransferChecked`.
+        mint: Option<Pubkey>,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    MintTo {
+        mint: Pubkey,
+        account: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    Burn {
+        account: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    CloseAccount {
+        account: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+    },
+    /// `account` is the mint for mint authorities, a token account otherwise.
+    SetAuthority {
+        account: Pubkey,
+        current_authority: Pubkey,
+        authority_type: AuthorityType,
+        /// `None` revokes the authority.
+        new_authority: Option<Pubkey>,
+    },
+    FreezeAccount {
+        account: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+    },
+    ThawAccount {
+        account: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+    },
+    Approve {
+        source: Pubkey,
+        /// Present for `ApproveChecked`.
+        mint: Option<Pubkey>,
+        delegate: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+}
+
+impl TokenInstruction {
+    /// Decode from instruction data and the instruction's account keys, in order. `None` for
+    /// other instructions and for truncated data or account lists.
+    pub fn decode(data: &[u8], accounts: &[Pubkey]) -> Option<Self> {
+        let account = |index: usize| accounts.get(index).copied();
+        let amount = || super::read_u64_le(data, 1);
+        let decimals = || data.get(9).copied();
+        Some(match *data.first()? {
+            TRANSFER => Self::Transfer {
+                source: account(0)?,
+                mint: None,
+                destination: account(1)?,
+                authority: account(2)?,
+                amount: amount()?,
+                decimals: None,
+            },
+            TRANSFER_CHECKED => Self::Transfer {
+                source: account(0)?,
+                mint: Some(account(1)?),
+                destination: account(2)?,
+                authority: account(3)?,
+                amount: amount()?,
+                decimals: Some(decimals()?),
+            },
+            MINT_TO | MINT_TO_CHECKED => Self::MintTo {
+                mint: account(0)?,
+                account: account(1)?,
+                authority: account(2)?,
+                amount: amount()?,
+                decimals: match data[0] {
+                    MINT_TO_CHECKED => Some(decimals()?),
+                    _ => None,
+                },
+            },
+            BURN | BURN_CHECKED => Self::Burn {
+                account: account(0)?,
+                mint: account(1)?,
+                authority: account(2)?,
+                amount: amount()?,
+                decimals: match data[0] {
+                    BURN_CHECKED => Some(decimals()?),
+                    _ => None,
+                },
+            },
+            CLOSE_ACCOUNT => Self::CloseAccount {
+                account: account(0)?,
+                destination: account(1)?,
+                authority: account(2)?,
+            },
+            // authority_type: u8, new_authority: COption<Pubkey> (u8 tag + 32 bytes).
+            SET_AUTHORITY => Self::SetAuthority {
+                account: account(0)?,
+                current_authority: account(1)?,
+                authority_type: AuthorityType::from_u8(*data.get(1)?)?,
+                new_authority: match *data.get(2)? {
+                    0 => None,
+                    1 => Some(Pubkey::try_from(data.get(3..35)?).ok()?),
+                    _ => return None,
+                },
+            },
+            FREEZE_ACCOUNT => Self::FreezeAccount {
+                account: account(0)?,
+                mint: account(1)?,
+                authority: account(2)?,
+            },
+            THAW_ACCOUNT => Self::ThawAccount {
+                account: account(0)?,
+                mint: account(1)?,
+                authority: account(2)?,
+            },
+            APPROVE => Self::Approve {
+                source: account(0)?,
+                mint: None,
+                delegate: account(1)?,
+                owner: account(2)?,
+                amount: amount()?,
+                decimals: None,
+            },
+            APPROVE_CHECKED => Self::Approve {
+                source: account(0)?,
+                mint: Some(account(1)?),
+                delegate: account(2)?,
+                owner: account(3)?,
+                amount: amount()?,
+                decimals: Some(decimals()?),
+            },
+            _ => return None,
+        })
+    }
+
+    /// Instruction name as the token program spells it in snake_case.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Transfer { mint: None, .. } => "transfer",
+            Self::Transfer { .. } => "transfer_checked",
+            Self::MintTo { decimals: None, .. } => "mint_to",
+            Self::MintTo { .. } => "mint_to_checked",
+            Self::Burn { decimals: None, .. } => "burn",
+            Self::Burn { .. } => "burn_checked",
+            Self::CloseAccount { .. } => "close_account",
+            Self::SetAuthority { .. } => "set_authority",
+            Self::FreezeAccount { .. } => "freeze_account",
+            Self::ThawAccount { .. } => "thaw_account",
+            Self::Approve { mint: None, .. } => "approve",
+            Self::Approve { .. } => "approve_checked",
+        }
+    }
+
+    pub fn action(&self) -> WatchAction {
+        match self {
+            Self::Transfer { .. } => WatchAction::Transfer,
+            Self::MintTo { .. } => WatchAction::Mint,
+            Self::Burn { .. } => WatchAction::Burn,
+            Self::CloseAccount { .. } => WatchAction::CloseAccount,
+            Self::SetAuthority { .. } => WatchAction::SetAuthority,
+            Self::FreezeAccount { .. } => WatchAction::Freeze,
+            Self::ThawAccount { .. } => WatchAction::Thaw,
+            Self::Approve { .. } => WatchAction::Approve,
+        }
+    }
+
+    /// The mint named by the instruction itself, without looking at other instructions.
+    pub fn mint(&self) -> Option<Pubkey> {
+        match self {
+            Self::Transfer { mint, .. } | Self::Approve { mint, .. } => *mint,
+            Self::MintTo { mint, .. }
+            | Self::Burn { mint, .. }
+            | Self::FreezeAccount { mint, .. }
+            | Self::ThawAccount { mint, .. } => Some(*mint),
+            Self::SetAuthority {
+                account,
+                authority_type,
+                ..
+            } => (!authority_type.is_account_authority()).then_some(*account),
+            Self::CloseAccount { .. } => None,
+        }
+    }
+
+    /// The token account whose mint identifies the instruction when [`mint`](Self::mint) is
+    /// `None`.
+    fn token_account(&self) -> Option<Pubkey> {
+        match self {
+            Self::Transfer { source, .. } | Self::Approve { source, .. } => Some(*source),
+            Self::CloseAccount { account, .. } | Self::SetAuthority { account, .. } => {
+                Some(*account)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn amount(&self) -> Option<u64> {
+        match self {
+            Self::Transfer { amount, .. }
+            | Self::MintTo { amount, .. }
+            | Self::Burn { amount, .. }
+            | Self::Approve { amount, .. } => Some(*amount),
+            _ => None,
+        }
+    }
+
+    /// The signing authority (owner, delegate or mint/freeze authority).
+    pub fn authority(&self) -> Pubkey {
+        match self {
+            Self::Transfer { authority, .. }
+            | Self::MintTo { authority, .. }
+            | Self::Burn { authority, .. }
+            | Self::CloseAccount { authority, .. }
+            | Self::FreezeAccount { authority, .. }
+            | Self::ThawAccount { authority, .. } => *authority,
+            Self::SetAuthority {
+                current_authority, ..
+            } => *current_authority,
+            Self::Approve { owner, .. } => *owner,
+        }
+    }
+
+    /// True for `SetAuthority` removing a mint's mint or freeze authority.
+    pub fn is_authority_revocation(&self) -> bool {
+        matches!(
+            self,
+            Self::SetAuthority {
+                authority_type: AuthorityType::MintTokens | AuthorityType::FreezeAccount,
+                new_authority: None,
+                ..
+            }
+        )
+    }
+
+    /// `(name, pubkey, writable, signer)` per account; multisig signers are not listed.
+    fn named_accounts(&self) -> Vec<(&'static str, Pubkey, bool, bool)> {
+        match self {
+            Self::Transfer {
+                source,
+                mint,
+                destination,
+                authority,
+                ..
+            } => {
+                let mut accounts = vec![("source", *source, true, false)];
+                accounts.extend(mint.map(|mint| ("mint", mint, false, false)));
+                accounts.push(("destination", *destination, true, false));
+                accounts.push(("authority", *authority, false, true));
+                accounts
+            }
+            Self::MintTo {
+                mint,
+                account,
+                authority,
+                ..
+            } => vec![
+                ("mint", *mint, true, false),
+                ("account", *account, true, false),
+                ("authority", *authority, false, true),
+            ],
+            Self::Burn {
+                account,
+                mint,
+                authority,
+                ..
+            } => vec![
+                ("account", *account, true, false),
+                ("mint", *mint, true, false),
+                ("authority", *authority, false, true),
+            ],
+            Self::CloseAccount {
+                account,
+                destination,
+                authority,
+            } => vec![
+                ("account", *account, true, false),
+                ("destination", *destination, true, false),
+                ("authority", *authority, false, true),
+            ],
+            Self::SetAuthority {
+                account,
+                current_authority,
+                ..
+            } => vec![
+                ("account", *account, true, false),
+                ("current_authority", *current_authority, false, true),
+            ],
+            Self::FreezeAccount {
+                account,
+                mint,
+                authority,
+            }
+            | Self::ThawAccount {
+                account,
+                mint,
+                authority,
+            } => vec![
+                ("account", *account, true, false),
+                ("mint", *mint, false, false),
+                ("authority", *authority, false, true),
+            ],
+            Self::Approve {
+                source,
+                mint,
+                delegate,
+                owner,
+                ..
+            } => {
+                let mut accounts = vec![("source", *source, true, false)];
+                accounts.extend(mint.map(|mint| ("mint", mint, false, false)));
+                accounts.push(("delegate", *delegate, false, false));
+                accounts.push(("owner", *owner, false, true));
+                accounts
+            }
+        }
+    }
+
+    fn args(&self) -> Value {
+        match self {
+            Self::Transfer {
+                amount, decimals, ..
+            }
+            | Self::MintTo {
+                amount, decimals, ..
+            }
+            | Self::Burn {
+                amount, decimals, ..
+            }
+            | Self::Approve {
+                amount, decimals, ..
+            } => match decimals {
+                Some(decimals) => json!({ "amount": amount, "decimals": decimals }),
+                None => json!({ "amount": amount }),
+            },
+            Self::SetAuthority {
+                authority_type,
+                new_authority,
+                ..
+            } => json!({
+                "authority_type": authority_type.as_str(),
+                "new_authority": new_authority.map(|key| key.to_string()),
+            }),
+            _ => json!({}),
+        }
+    }
+
+    /// The instruction in the same shape as IDL-decoded instructions.
+    pub fn to_instruction_event(&self, program_id: Pubkey) -> InstructionEvent {
+        InstructionEvent {
+            program_id,
+            protocol: Protocol::SplToken,
+            instruction: self.name().to_string(),
+            args: self.args(),
+            accounts: self
+                .named_accounts()
+                .into_iter()
+                .map(|(name, pubkey, writable, signer)| NamedAccount {
+                    name: name.to_string(),
+                    pubkey,
+                    writable,
+                    signer,
+                })
+                .collect(),
+            error: None,
+        }
+    }
+}
+
+/// A top-level token instruction with its program and resolved mint.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodedTokenInstruction {
+    pub program_id: Pubkey,
+    /// From the instruction, else from a token account created in the transaction.
+    pub mint: Option<Pubkey>,
+    pub instruction: TokenInstruction,
+}
+
+/// Decode every top-level instruction of `tx` addressed to one of `token_program_ids`
+/// (usually [`ProgramWatchConfig::token_program_ids`]). Instructions referencing accounts from
+/// address lookup tables are skipped.
+pub fn token_instructions(
+    tx: &VersionedTransaction,
+    token_program_ids: &[Pubkey],
+) -> Vec<DecodedTokenInstruction> {
+    let keys = tx.message.static_account_keys();
+    let mut account_mints: Option<BTreeMap<Pubkey, Pubkey>> = None;
+    let mut out = Vec::new();
+    for ix in tx.message.instructions() {
+        let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+            continue;
+        };
+        if !token_program_ids.contains(program_id) {
+            continue;
+        }
+        let Some(accounts) = ix
+            .accounts
+            .iter()
+            .map(|i| keys.get(*i as usize).copied())
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        let Some(instruction) = TokenInstruction::decode(&ix.data, &accounts) else {
+            continue;
+        };
+        let mint = instruction.mint().or_else(|| {
+            let account = instruction.token_account()?;
+            account_mints
+                .get_or_insert_with(|| token_account_mints_for(tx, token_program_ids))
+                .get(&account)
+                .copied()
+        });
+        out.push(DecodedTokenInstruction {
+            program_id: *program_id,
+            mint,
+            instruction,
+        });
+    }
+    out
+}
+
+/// True when one of the decoded `instructions` touches one of `mints`.
+pub fn touches_mints(instructions: &[DecodedTokenInstruction], mints: &KeySet) -> bool {
+    !mints.is_empty()
+        && instructions
+            .iter()
+            .any(|ix| ix.mint.is_some_and(|mint| mints.contains(&mint)))
+}
+
+/// Emits the token instructions of watched mints as [`InstructionEvent`]s. Under a watch config
+/// it reuses [`ProgramHit::token_instructions`]; standalone [`decode`](InstructionDecoder::decode)
+/// calls decode with [`with_token_program_ids`](Self::with_token_program_ids) (Token and
+/// Token-2022 by default).
+pub struct SplTokenDecoder {
+    mints: Vec<Pubkey>,
+    token_program_ids: Vec<Pubkey>,
+}
+
+impl SplTokenDecoder {
+    pub fn new(mints: Vec<Pubkey>) -> Self {
+        Self {
+            mints,
+            token_program_ids: default_token_program_ids(),
+        }
+    }
+
+    pub fn with_token_program_ids(mut self, token_program_ids: Vec<Pubkey>) -> Self {
+        self.token_program_ids = token_program_ids;
+        self
+    }
+
+    fn events(&self, instructions: &[DecodedTokenInstruction]) -> Vec<InstructionEvent> {
+        instructions
+            .iter()
+            .filter(|ix| ix.mint.is_some_and(|mint| self.mints.contains(&mint)))
+            .map(|ix| ix.instruction.to_instruction_event(ix.program_id))
+            .collect()
+    }
+}
+
+impl InstructionDecoder for SplTokenDecoder {
+    fn decode(&self, tx: &VersionedTransaction) -> Vec<InstructionEvent> {
+        self.events(&token_instructions(tx, &self.token_program_ids))
+    }
+
+    fn decode_hit(&self, tx: &VersionedTransaction, hit: &ProgramHit) -> Vec<InstructionEvent> {
+        match &hit.token_instructions {
+            Some(instructions) => self.events(instructions),
+            None => self.decode(tx),
+        }
+    }
+}
+
+/// Token flow detailer: one detail per mint touched by a token instruction on a watched mint
+/// (`cfg.token_mints`) or signed by a tracked wallet (`cfg.wallets`). An authority change wins
+/// over other instructions on the same mint; otherwise the first instruction is reported, with
+/// its amount in base units. Part of the default detailers; does nothing without either list.
+#[derive(Default)]
+pub struct SplTokenDetailer;
+
+impl SplTokenDetailer {
+    fn details(
+        cfg: &ProgramWatchConfig,
+        instructions: &[DecodedTokenInstruction],
+    ) -> Vec<MintDetail> {
+        let index = cfg.index();
+        let mut out: BTreeMap<Pubkey, MintDetail> = BTreeMap::new();
+        for ix in instructions {
+            let Some(mint) = ix.mint.filter(|mint| {
+                index.token_mints.contains(mint)
+                    || index.wallets.contains(&ix.instruction.authority())
+            }) else {
+                continue;
+            };
+            let detail = MintDetail {
+                protocol: Some(Protocol::SplToken),
+                action: Some(ix.instruction.action()),
+                token_amount: ix.instruction.amount().map(Amount::token),
+                ..MintDetail::new(mint)
+            };
+            match out.get(&mint) {
+                Some(current) if current.action == Some(WatchAction::SetAuthority) => {}
+                Some(_) if detail.action != Some(WatchAction::SetAuthority) => {}
+                _ => {
+                    out.insert(mint, detail);
+                }
+            }
+        }
+        out.into_values().collect()
+    }
+}
+
+impl MintDetailer for SplTokenDetailer {
+    fn detail(
+        &self,
+        tx: &VersionedTransaction,
+        cfg: &ProgramWatchConfig,
+        _mints: &[MintInfo],
+    ) -> Vec<MintDetail> {
+        let index = cfg.index();
+        if index.token_mints.is_empty() && index.wallets.is_empty() {
+            return Vec::new();
+        }
+        Self::details(cfg, &token_instructions(tx, &cfg.token_program_ids))
+    }
+
+    fn detail_hit(
+        &self,
+        tx: &VersionedTransaction,
+        cfg: &ProgramWatchConfig,
+        hit: &ProgramHit,
+        _meta: Option<&TxMeta>,
+    ) -> Vec<MintDetail> {
+        match &hit.token_instructions {
+            Some(instructions) => Self::details(cfg, instructions),
+            None => self.detail(tx, cfg, &hit.mints),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shreds_udp::collect_watch_events, txn::test_support::tx_with};
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use std::str::FromStr;
+
+    const OWNER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const SOURCE: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const DESTINATION: Pubkey = Pubkey::new_from_array([4u8; 32]);
+
+    fn token_program() -> Pubkey {
+        Pubkey::from_str(super::super::TOKEN_PROGRAM_ID).unwrap()
+    }
+
+    fn token_ix(data: Vec<u8>, accounts: &[Pubkey]) -> Instruction {
+        Instruction::new_with_bytes(
+            token_program(),
+            &data,
+            accounts
+                .iter()
+                .map(|key| AccountMeta::new(*key, *key == OWNER))
+                .collect(),
+        )
+    }
+
+    fn amount_data(tag: u8, amount: u64, decimals: Option<u8>) -> Vec<u8> {
+        let mut data = vec![tag];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend(decimals);
+        data
+    }
+
+    #[test]
+    fn decodes_amounts_authorities_and_resolved_mints() {
+        let new_owner = Pubkey::new_from_array([9u8; 32]);
+        let mut set_owner = vec![SET_AUTHORITY, 2, 1];
+        set_owner.extend_from_slice(new_owner.as_ref());
+        let tx = tx_with(
+            &OWNER,
+            &[
+                // InitializeAccount3 makes SOURCE resolvable for the plain transfer.
+                token_ix(
+                    [vec![18], OWNER.to_bytes().to_vec()].concat(),
+                    &[SOURCE, MINT],
+                ),
+                token_ix(
+                    amount_data(TRANSFER, 500, None),
+                    &[SOURCE, DESTINATION, OWNER],
+                ),
+                token_ix(
+                    amount_data(TRANSFER_CHECKED, 7, Some(6)),
+                    &[SOURCE, MINT, DESTINATION, OWNER],
+                ),
+                token_ix(
+                    amount_data(BURN_CHECKED, 3, Some(6)),
+                    &[SOURCE, MINT, OWNER],
+                ),
+                token_ix(set_owner, &[SOURCE, OWNER]),
+                token_ix(vec![SET_AUTHORITY, 0, 0], &[MINT, OWNER]),
+                token_ix(vec![CLOSE_ACCOUNT], &[DESTINATION, OWNER, OWNER]),
+                // Truncated amount.
+                token_ix(vec![TRANSFER, 1, 2], &[SOURCE, DESTINATION, OWNER]),
+            ],
+        );
+        let decoded = token_instructions(&tx, &default_token_program_ids());
+        let summary: Vec<_> = decoded
+            .iter()
+            .map(|ix| (ix.instruction.name(), ix.mint, ix.instruction.amount()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("transfer", Some(MINT), Some(500)),
+                ("transfer_checked", Some(MINT), Some(7)),
+                ("burn_checked", Some(MINT), Some(3)),
+                ("set_authority", Some(MINT), None),
+                ("set_authority", Some(MINT), None),
+                ("close_account", None, None),
+            ]
+        );
+        assert!(!decoded[3].instruction.is_authority_revocation());
+        assert!(decoded[4].instruction.is_authority_revocation());
+
+        let event = decoded[1].instruction.to_instruction_event(token_program());
+        assert_eq!(event.args, json!({ "amount": 7, "decimals": 6 }));
+        assert_eq!(event.account("mint"), Some(&MINT));
+        assert!(event.accounts[3].signer);
+        let event = decoded[3].instruction.to_instruction_event(token_program());
+        assert_eq!(
+            event.args,
+            json!({ "authority_type": "account_owner", "new_authority": new_owner.to_string() })
+        );
+    }
+
+    #[test]
+    fn watched_mints_hit_and_report_revocations() {
+        let cfg = ProgramWatchConfig::new(Vec::new(), Vec::new()).with_token_mints(vec![MINT]);
+        let transfer = tx_with(
+            &OWNER,
+            &[token_ix(
+                amount_data(TRANSFER_CHECKED, 1_000, Some(9)),
+                &[SOURCE, MINT, DESTINATION, OWNER],
+            )],
+        );
+        let revoke = tx_with(
+            &OWNER,
+            &[
+                token_ix(amount_data(MINT_TO, 5, None), &[MINT, DESTINATION, OWNER]),
+                token_ix(vec![SET_AUTHORITY, 1, 0], &[MINT, OWNER]),
+            ],
+        );
+        let other_mint = Pubkey::new_from_array([8u8; 32]);
+        let unrelated = tx_with(
+            &OWNER,
+            &[token_ix(
+                amount_data(TRANSFER_CHECKED, 1, Some(9)),
+                &[SOURCE, other_mint, DESTINATION, OWNER],
+            )],
+        );
+
+        let events = collect_watch_events(5, &[&transfer, &revoke, &unrelated], &cfg, 0);
+        assert_eq!(events.len(), 2);
+        assert!(events[0].hit.mint_hit && !events[0].hit.program_hit);
+        let detail = &events[0].details[0];
+        assert_eq!(detail.action, Some(WatchAction::Transfer));
+        assert_eq!(detail.token_amount, Some(Amount::token(1_000)));
+        assert_eq!(events[0].instructions[0].instruction, "transfer_checked");
+
+        let detail = events[1]
+            .details
+            .iter()
+            .find(|d| d.mint == MINT)
+            .expect("mint detail");
+        assert_eq!(detail.action, Some(WatchAction::SetAuthority));
+        let names: Vec<_> = events[1]
+            .instructions
+            .iter()
+            .map(|ix| ix.instruction.as_str())
+            .collect();
+        assert_eq!(names, ["mint_to", "set_authority"]);
+        assert_eq!(
+            events[1].instructions[1].args["authority_type"],
+            "freeze_account"
+        );
+    }
+
+    #[test]
+    fn configured_token_program_ids_are_decoded_once_per_hit() {
+        let fork = Pubkey::new_from_array([9u8; 32]);
+        let mut ix = token_ix(
+            amount_data(TRANSFER_CHECKED, 7, Some(6)),
+            &[SOURCE, MINT, DESTINATION, OWNER],
+        );
+        ix.program_id = fork;
+        let tx = tx_with(&OWNER, &[ix]);
+
+        let default_cfg =
+            ProgramWatchConfig::new(Vec::new(), Vec::new()).with_token_mints(vec![MINT]);
+        assert!(collect_watch_events(5, &[&tx], &default_cfg, 0).is_empty());
+
+        let cfg = ProgramWatchConfig::new(Vec::new(), Vec::new())
+            .with_token_program_ids(vec![fork])
+            .with_token_mints(vec![MINT]);
+        let events = collect_watch_events(5, &[&tx], &cfg, 0);
+        assert_eq!(events.len(), 1);
+        let hit = &events[0].hit;
+        assert!(hit.mint_hit);
+        assert_eq!(hit.token_instructions.as_ref().map(Vec::len), Some(1));
+        assert_eq!(events[0].details[0].token_amount, Some(Amount::token(7)));
+        assert_eq!(events[0].instructions[0].program_id, fork);
+    }
+}
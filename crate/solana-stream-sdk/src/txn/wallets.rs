@@ -0,0 +1,177 @@
+//! Wallet watch: transactions signed (or paid for) by tracked wallets.
+//!
+//! Unlike `authorities`, which match a key anywhere in the transaction, wallets only match
+//! the signer set (or the fee payer alone), so a wallet that is merely passed as an account
+//! does not trigger. Lists of thousands of wallets can be loaded with [`load_wallets`].
+
+use super::KeySet;
+use crate::{Result, SolanaStreamError};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use std::{fs, path::Path, str::FromStr};
+
+/// Which keys of a transaction are compared against the tracked wallets.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletMatch {
+    /// Any required signer, including the fee payer.
+    #[default]
+    Signers,
+    /// Only the fee payer (first signer).
+    FeePayer,
+}
+
+/// Sort and dedup a wallet list.
+pub fn sorted_wallets(mut wallets: Vec<Pubkey>) -> Vec<Pubkey> {
+    wallets.sort_unstable();
+    wallets.dedup();
+    wallets
+}
+
+/// Tracked wallets that signed `tx` under `mode`, in signer order.
+pub fn matched_wallets(
+    tx: &VersionedTransaction,
+    wallets: &KeySet,
+    mode: WalletMatch,
+) -> Vec<Pubkey> {
+    if wallets.is_empty() {
+        return Vec::new();
+    }
+    let signers = usize::from(tx.message.header().num_required_signatures);
+    let count = match mode {
+        WalletMatch::Signers => signers,
+        WalletMatch::FeePayer => signers.min(1),
+    };
+    tx.message
+        .static_account_keys()
+        .iter()
+        .take(count)
+        .filter(|key| wallets.contains(key))
+        .copied()
+        .collect()
+}
+
+/// Read a wallet list: a JSON array of base58 strings, or text with one or more
+/// comma/whitespace-separated keys per line and `#` comments. Any invalid key is an error.
+pub fn load_wallets(path: impl AsRef<Path>) -> Result<Vec<Pubkey>> {
+    let path = path.as_ref();
+    let context =
+        |e: String| SolanaStreamError::Configuration(format!("{}: {}", path.display(), e));
+    let raw = fs::read_to_string(path).map_err(|e| context(e.to_string()))?;
+    parse_wallets(&raw).map_err(context)
+}
+
+fn parse_wallets(raw: &str) -> std::result::Result<Vec<Pubkey>, String> {
+    let parse = |(at, key): (String, &str)| {
+        Pubkey::from_str(key).map_err(|_| format!("{}: invalid pubkey {:?}", at, key))
+    };
+    let wallets = if raw.trim_start().starts_with('[') {
+        serde_json::from_str::<Vec<String>>(raw)
+            .map_err(|e| e.to_string())?
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (format!("[{}]", index), key.trim()))
+            .map(parse)
+            .collect::<std::result::Result<Vec<_>, _>>()?
+    } else {
+        raw.lines()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                let at = format!("line {}", index + 1);
+                line.split('#')
+                    .next()
+                    .unwrap_or_default()
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|key| !key.is_empty())
+                    .map(move |key| (at.clone(), key))
+            })
+            .map(parse)
+            .collect::<std::result::Result<Vec<_>, _>>()?
+    };
+    Ok(sorted_wallets(wallets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shreds_udp::collect_watch_events,
+        txn::{test_support::tx_with, ProgramWatchConfig},
+    };
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+
+    const PAYER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const COSIGNER: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const PASSIVE: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const PROGRAM: Pubkey = Pubkey::new_from_array([4u8; 32]);
+
+    fn tx() -> VersionedTransaction {
+        tx_with(
+            &PAYER,
+            &[Instruction::new_with_bytes(
+                PROGRAM,
+                &[],
+                vec![
+                    AccountMeta::new_readonly(COSIGNER, true),
+                    AccountMeta::new(PASSIVE, false),
+                ],
+            )],
+        )
+    }
+
+    #[test]
+    fn matches_signers_or_fee_payer_only() {
+        let wallets = KeySet::new(&[PASSIVE, COSIGNER, PAYER], Default::default());
+        assert_eq!(
+            matched_wallets(&tx(), &wallets, WalletMatch::Signers),
+            [PAYER, COSIGNER]
+        );
+        assert_eq!(
+            matched_wallets(&tx(), &wallets, WalletMatch::FeePayer),
+            [PAYER]
+        );
+
+        let cfg = ProgramWatchConfig::new(Vec::new(), Vec::new())
+            .with_wallets(vec![PASSIVE], WalletMatch::Signers);
+        assert!(collect_watch_events(1, &[&tx()], &cfg, 0).is_empty());
+        let cfg = cfg.with_wallets(vec![COSIGNER], WalletMatch::Signers);
+        let hit = crate::txn::detect_program_hit(&tx(), &cfg).expect("cosigner hit");
+        assert_eq!(hit.wallets, [COSIGNER]);
+        assert!(!hit.program_hit && !hit.authority_hit);
+
+        // Token instructions the wallet signs are reported on their mint.
+        let mint = Pubkey::new_from_array([9u8; 32]);
+        let mut data = vec![12];
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.push(6);
+        let token_program = Pubkey::from_str(crate::txn::TOKEN_PROGRAM_ID).unwrap();
+        let transfer = tx_with(
+            &PAYER,
+            &[Instruction::new_with_bytes(
+                token_program,
+                &data,
+                vec![
+                    AccountMeta::new(PASSIVE, false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new(PROGRAM, false),
+                    AccountMeta::new_readonly(COSIGNER, true),
+                ],
+            )],
+        );
+        let events = collect_watch_events(1, &[&transfer], &cfg, 0);
+        let detail = &events[0].details[0];
+        assert_eq!(detail.mint, mint);
+        assert_eq!(detail.action, Some(crate::txn::WatchAction::Transfer));
+        assert_eq!(detail.token_amount, Some(crate::txn::Amount::token(42)));
+    }
+
+    #[test]
+    fn parses_text_and_json_lists() {
+        let text = format!("# tracked\n{}, {}\n\n{}  # dup\n", PAYER, COSIGNER, PAYER);
+        assert_eq!(parse_wallets(&text).unwrap(), [PAYER, COSIGNER]);
+        let json = format!(r#"["{}", "{}"]"#, PASSIVE, PAYER);
+        assert_eq!(parse_wallets(&json).unwrap(), [PAYER, PASSIVE]);
+        let err = parse_wallets(&format!("{}\nnot-a-key\n", PAYER)).unwrap_err();
+        assert_eq!(err, r#"line 2: invalid pubkey "not-a-key""#);
+    }
+}
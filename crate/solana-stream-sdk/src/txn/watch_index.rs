@@ -0,0 +1,191 @@
+//! Hash-set index of the watch lists, so [`detect_program_hit`](super::detect_program_hit)
+//! costs the same per transaction whether ten or a hundred thousand keys are watched.
+//!
+//! Each list becomes a [`KeySet`]: a `HashSet` optionally fronted by a probabilistic prefilter.
+//! With [`Prefilter::Cuckoo`] a Yellowstone [`CuckooFilter`] (the type the SDK re-exports as
+//! `GeyserCuckooFilter`) answers most misses before the set is consulted; hits are always
+//! confirmed by the set, so the prefilter never adds false positives.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashSet,
+    hash::{BuildHasherDefault, Hasher},
+};
+use yellowstone_grpc_proto::cuckoo::CuckooFilter;
+
+/// Probabilistic filter checked before the hash set.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Prefilter {
+    /// Hash set only.
+    #[default]
+    None,
+    /// Yellowstone cuckoo filter (~3 bytes per key) in front of the set.
+    Cuckoo,
+}
+
+/// Fx-style hash over every 8-byte word of the key: much cheaper than SipHash, and still
+/// spreads keys that only differ in a few bytes (vanity or sequential keys).
+#[derive(Debug, Default)]
+pub struct PubkeyHasher(u64);
+
+const SEED: u64 = 0x517c_c1b7_2722_0a95;
+
+impl Hasher for PubkeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            let word = u64::from_le_bytes(word.try_into().unwrap_or_default());
+            self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(SEED);
+        }
+        for byte in words.remainder() {
+            self.0 = (self.0.rotate_left(5) ^ u64::from(*byte)).wrapping_mul(SEED);
+        }
+    }
+}
+
+/// A watch list indexed for O(1) membership tests.
+#[derive(Debug, Default)]
+pub struct KeySet {
+    keys: HashSet<Pubkey, BuildHasherDefault<PubkeyHasher>>,
+    prefilter: Option<CuckooFilter<Pubkey>>,
+}
+
+impl KeySet {
+    /// Index `keys`. A cuckoo prefilter that cannot be built (or fills up) is dropped and
+    /// the set is used alone.
+    pub fn new<'a>(keys: impl IntoIterator<Item = &'a Pubkey>, prefilter: Prefilter) -> Self {
+        let keys: HashSet<Pubkey, BuildHasherDefault<PubkeyHasher>> =
+            keys.into_iter().copied().collect();
+        let prefilter = match prefilter {
+            Prefilter::Cuckoo if !keys.is_empty() => CuckooFilter::with_capacity(keys.len())
+                .ok()
+                .and_then(|mut filter| {
+                    keys.iter()
+                        .try_for_each(|key| filter.insert(key))
+                        .ok()
+                        .map(|()| filter)
+                }),
+            _ => None,
+        };
+        Self { keys, prefilter }
+    }
+
+    pub fn contains(&self, key: &Pubkey) -> bool {
+        if self.keys.is_empty() {
+            return false;
+        }
+        if let Some(filter) = &self.prefilter {
+            if !filter.contains(key) {
+                return false;
+            }
+        }
+        self.keys.contains(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn has_prefilter(&self) -> bool {
+        self.prefilter.is_some()
+    }
+}
+
+/// Indexed copies of the [`ProgramWatchConfig`](super::ProgramWatchConfig) lists, rebuilt by
+/// its builders and setters.
+#[derive(Default)]
+pub struct WatchIndex {
+    pub program_ids: KeySet,
+    pub authorities: KeySet,
+    pub token_mints: KeySet,
+    pub wallets: KeySet,
+    pub prefilter: Prefilter,
+}
+
+impl WatchIndex {
+    pub fn new(
+        program_ids: &[Pubkey],
+        authorities: &[Pubkey],
+        token_mints: &[Pubkey],
+        wallets: &[Pubkey],
+        prefilter: Prefilter,
+    ) -> Self {
+        Self {
+            program_ids: KeySet::new(program_ids, prefilter),
+            authorities: KeySet::new(authorities, prefilter),
+            token_mints: KeySet::new(token_mints, prefilter),
+            wallets: KeySet::new(wallets, prefilter),
+            prefilter,
+        }
+    }
+
+    /// True when nothing is watched, so no transaction can hit.
+    pub fn is_empty(&self) -> bool {
+        self.program_ids.is_empty()
+            && self.authorities.is_empty()
+            && self.token_mints.is_empty()
+            && self.wallets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txn::{detect_program_hit, test_support::tx_with, ProgramWatchConfig};
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+
+    fn key(n: u32) -> Pubkey {
+        let mut bytes = [7u8; 32];
+        bytes[..4].copy_from_slice(&n.to_le_bytes());
+        bytes[28..].copy_from_slice(&n.to_be_bytes());
+        Pubkey::new_from_array(bytes)
+    }
+
+    #[test]
+    fn key_sets_agree_with_and_without_prefilter() {
+        let watched: Vec<Pubkey> = (0..5_000).map(key).collect();
+        for prefilter in [Prefilter::None, Prefilter::Cuckoo] {
+            let set = KeySet::new(&watched, prefilter);
+            assert_eq!(set.has_prefilter(), prefilter == Prefilter::Cuckoo);
+            assert_eq!(set.len(), 5_000);
+            assert!(watched.iter().all(|k| set.contains(k)));
+            assert!(!(5_000..10_000).map(key).any(|k| set.contains(&k)));
+        }
+        assert!(!KeySet::new(&[], Prefilter::Cuckoo).contains(&key(1)));
+    }
+
+    #[test]
+    fn config_index_follows_builders() {
+        let payer = key(1);
+        let program = key(2);
+        let tx = tx_with(
+            &payer,
+            &[Instruction::new_with_bytes(
+                program,
+                &[],
+                vec![AccountMeta::new(key(3), false)],
+            )],
+        );
+        let cfg = ProgramWatchConfig::new((10..20_000).map(key).collect(), Vec::new())
+            .with_prefilter(Prefilter::Cuckoo);
+        assert!(cfg.index().program_ids.has_prefilter());
+        assert!(detect_program_hit(&tx, &cfg).is_none());
+
+        let mut cfg = cfg.with_wallets(vec![payer], Default::default());
+        assert_eq!(detect_program_hit(&tx, &cfg).unwrap().wallets, [payer]);
+
+        // Setters rebuild the index.
+        cfg.set_authorities(vec![key(3)]);
+        assert!(detect_program_hit(&tx, &cfg).unwrap().authority_hit);
+    }
+}
@@ -0,0 +1,927 @@
+//! Named filter expressions for watch events.
+//!
+//! Rules are declared in `settings.jsonc` under `"rules"` and compiled once at load time;
+//! sinks opt into them by name (`"rules": ["whale_buys"]`). An expression is evaluated
+//! against each [`MintDetail`] of an event, with the event-level fields available too:
+//!
+//! ```text
+//! program == "pump" && action == "buy" && sol_amount >= 5 SOL && signer in @whales
+//! ```
+//!
+//! - Operators: `==`, `!=`, `<`, `<=`, `>`, `>=`, `in`, `&&`, `||`, `!` and parentheses.
+//! - Values: `"strings"`, integers, amounts with a unit (`0.5 SOL`, `1000 lamports`),
+//!   `true`/`false`, inline lists (`["buy", "sell"]`) and named key lists (`@whales`, declared
+//!   under `"lists"`).
+//! - Fields: see [`FIELDS`]. Multi-valued fields (`signer`, `wallet`, `instruction`) match
+//!   when any value does. Unknown values (e.g. a missing `sol_amount`) never satisfy `==` or
+//!   an ordering comparison, so `!=` holds for them.
+
+use std::{collections::BTreeMap, fmt, path::PathBuf, str::FromStr, sync::Arc};
+
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    shreds_udp::WatchEvent,
+    txn::{load_wallets, KeySet, MintDetail, Prefilter, WatchAction},
+    Result, SolanaStreamError,
+};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Field names accepted in rule expressions.
+pub const FIELDS: &[&str] = &[
+    "slot",
+    "signature",
+    "signer",
+    "fee_payer",
+    "wallet",
+    "program_hit",
+    "authority_hit",
+    "mint_hit",
+    "failed",
+    "jito_tip",
+    "priority_fee",
+    "cu_price",
+    "cu_limit",
+    "mint",
+    "program",
+    "action",
+    "sol_amount",
+    "token_amount",
+    "name",
+    "symbol",
+    "instruction",
+];
+
+/// Key list referenced from rules as `@name`: inline base58 keys, or the path of a wallet list
+/// file (same format as `watch_wallets_file`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RuleList {
+    Keys(Vec<String>),
+    File(PathBuf),
+}
+
+impl RuleList {
+    pub fn load(&self) -> Result<Vec<Pubkey>> {
+        match self {
+            RuleList::Keys(keys) => keys
+                .iter()
+                .map(|key| {
+                    Pubkey::from_str(key.trim()).map_err(|_| {
+                        SolanaStreamError::Configuration(format!("invalid pubkey {:?}", key))
+                    })
+                })
+                .collect(),
+            RuleList::File(path) => load_wallets(path),
+        }
+    }
+}
+
+/// Every rule of a settings file, compiled against its lists.
+#[derive(Debug, Clone, Default)]
+pub struct WatchRules {
+    rules: BTreeMap<String, WatchRule>,
+}
+
+impl WatchRules {
+    /// Load the lists and compile every rule; fails on the first invalid list or expression.
+    pub fn compile(
+        rules: &BTreeMap<String, String>,
+        lists: &BTreeMap<String, RuleList>,
+    ) -> Result<Self> {
+        let mut sets = BTreeMap::new();
+        for (name, list) in lists {
+            let keys = list.load().map_err(|e| {
+                SolanaStreamError::Configuration(format!("list @{}: {}", name, rule_error(e)))
+            })?;
+            sets.insert(name.clone(), Arc::new(KeySet::new(&keys, Prefilter::None)));
+        }
+        let rules = rules
+            .iter()
+            .map(|(name, source)| Ok((name.clone(), WatchRule::compile(name, source, &sets)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WatchRule> {
+        self.rules.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// A compiled rule expression.
+#[derive(Debug, Clone)]
+pub struct WatchRule {
+    name: String,
+    source: String,
+    expr: Arc<Expr>,
+}
+
+impl WatchRule {
+    /// Compile `source`; `@list` references resolve against `lists`.
+    pub fn compile(
+        name: &str,
+        source: &str,
+        lists: &BTreeMap<String, Arc<KeySet>>,
+    ) -> Result<Self> {
+        let error = |column: usize, message: String| {
+            SolanaStreamError::Configuration(format!(
+                "rule {}: {} at column {}",
+                name, message, column
+            ))
+        };
+        let tokens = tokenize(source).map_err(|(column, message)| error(column, message))?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: source.chars().count() + 1,
+            lists,
+        };
+        let expr = parser
+            .parse()
+            .map_err(|(column, message)| error(column, message))?;
+        Ok(Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            expr: Arc::new(expr),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate against one detail of `event`, or the event alone when it has no details.
+    pub fn matches(&self, event: &WatchEvent, detail: Option<&MintDetail>) -> bool {
+        self.expr.eval(event, detail)
+    }
+
+    /// Return the event with non-matching details removed, or `None` if nothing matched.
+    /// Events without details (decoded instructions only) are matched as a whole.
+    pub fn apply(&self, event: &WatchEvent) -> Option<WatchEvent> {
+        if event.details.is_empty() {
+            return self.matches(event, None).then(|| event.clone());
+        }
+        let details: Vec<_> = event
+            .details
+            .iter()
+            .filter(|d| self.matches(event, Some(d)))
+            .cloned()
+            .collect();
+        if details.is_empty() {
+            return None;
+        }
+        Some(WatchEvent {
+            details,
+            ..event.clone()
+        })
+    }
+}
+
+/// Strip the `Configuration error: ` prefix when nesting an error message.
+fn rule_error(error: SolanaStreamError) -> String {
+    match error {
+        SolanaStreamError::Configuration(message) => message,
+        other => other.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cmp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Cmp {
+    fn holds(self, left: u64, right: u64) -> bool {
+        match self {
+            Cmp::Eq => left == right,
+            Cmp::Ne => left != right,
+            Cmp::Lt => left < right,
+            Cmp::Le => left <= right,
+            Cmp::Gt => left > right,
+            Cmp::Ge => left >= right,
+        }
+    }
+}
+
+impl fmt::Display for Cmp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Cmp::Eq => "==",
+            Cmp::Ne => "!=",
+            Cmp::Lt => "<",
+            Cmp::Le => "<=",
+            Cmp::Gt => ">",
+            Cmp::Ge => ">=",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(String),
+    List(String),
+    Cmp(Cmp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{}`", ident),
+            Token::Str(s) => write!(f, "{:?}", s),
+            Token::Num(n) => write!(f, "`{}`", n),
+            Token::List(name) => write!(f, "`@{}`", name),
+            Token::Cmp(cmp) => write!(f, "`{}`", cmp),
+            Token::And => f.write_str("`&&`"),
+            Token::Or => f.write_str("`||`"),
+            Token::Not => f.write_str("`!`"),
+            Token::LParen => f.write_str("`(`"),
+            Token::RParen => f.write_str("`)`"),
+            Token::LBracket => f.write_str("`[`"),
+            Token::RBracket => f.write_str("`]`"),
+            Token::Comma => f.write_str("`,`"),
+        }
+    }
+}
+
+/// Error position (1-based column) and message.
+type ParseError = (usize, String);
+
+fn tokenize(source: &str) -> std::result::Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let word = |start: usize, ok: fn(char) -> bool| {
+        let end = chars[start..]
+            .iter()
+            .position(|c| !ok(*c))
+            .map_or(chars.len(), |n| start + n);
+        (end, chars[start..end].iter().collect::<String>())
+    };
+    while i < chars.len() {
+        let column = i + 1;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (len, token) = match (c, next) {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('&', Some('&')) => (2, Token::And),
+            ('|', Some('|')) => (2, Token::Or),
+            ('=', Some('=')) => (2, Token::Cmp(Cmp::Eq)),
+            ('!', Some('=')) => (2, Token::Cmp(Cmp::Ne)),
+            ('<', Some('=')) => (2, Token::Cmp(Cmp::Le)),
+            ('>', Some('=')) => (2, Token::Cmp(Cmp::Ge)),
+            ('<', _) => (1, Token::Cmp(Cmp::Lt)),
+            ('>', _) => (1, Token::Cmp(Cmp::Gt)),
+            ('!', _) => (1, Token::Not),
+            ('(', _) => (1, Token::LParen),
+            (')', _) => (1, Token::RParen),
+            ('[', _) => (1, Token::LBracket),
+            (']', _) => (1, Token::RBracket),
+            (',', _) => (1, Token::Comma),
+            ('"', _) => {
+                let mut value = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return Err((column, "unterminated string".to_string())),
+                        Some('"') => break,
+                        Some('\\') if j + 1 < chars.len() => {
+                            value.push(chars[j + 1]);
+                            j += 2;
+                        }
+                        Some(other) => {
+                            value.push(*other);
+                            j += 1;
+                        }
+                    }
+                }
+                (j + 1 - i, Token::Str(value))
+            }
+            ('@', _) => {
+                let (end, name) = word(i + 1, |c| c.is_alphanumeric() || c == '_' || c == '-');
+                if name.is_empty() {
+                    return Err((column, "expected a list name after `@`".to_string()));
+                }
+                (end - i, Token::List(name))
+            }
+            _ if c.is_ascii_digit() => {
+                let (end, number) = word(i, |c| c.is_ascii_digit() || c == '.' || c == '_');
+                (end - i, Token::Num(number))
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let (end, ident) = word(i, |c| c.is_alphanumeric() || c == '_');
+                (end - i, Token::Ident(ident))
+            }
+            _ => return Err((column, format!("unexpected character {:?}", c))),
+        };
+        tokens.push((column, token));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Slot,
+    Signature,
+    Signer,
+    FeePayer,
+    Wallet,
+    ProgramHit,
+    AuthorityHit,
+    MintHit,
+    Failed,
+    JitoTip,
+    PriorityFee,
+    CuPrice,
+    CuLimit,
+    Mint,
+    Program,
+    Action,
+    SolAmount,
+    TokenAmount,
+    Name,
+    Symbol,
+    Instruction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Flag,
+    Number { lamports: bool },
+    Text,
+    Key,
+    Action,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "slot" => Field::Slot,
+            "signature" => Field::Signature,
+            "signer" => Field::Signer,
+            "fee_payer" => Field::FeePayer,
+            "wallet" => Field::Wallet,
+            "program_hit" => Field::ProgramHit,
+            "authority_hit" => Field::AuthorityHit,
+            "mint_hit" => Field::MintHit,
+            "failed" => Field::Failed,
+            "jito_tip" => Field::JitoTip,
+            "priority_fee" => Field::PriorityFee,
+            "cu_price" => Field::CuPrice,
+            "cu_limit" => Field::CuLimit,
+            "mint" => Field::Mint,
+            "program" | "protocol" => Field::Program,
+            "action" => Field::Action,
+            "sol_amount" => Field::SolAmount,
+            "token_amount" => Field::TokenAmount,
+            "name" => Field::Name,
+            "symbol" => Field::Symbol,
+            "instruction" => Field::Instruction,
+            _ => return None,
+        })
+    }
+
+    fn kind(self) -> Kind {
+        match self {
+            Field::ProgramHit | Field::AuthorityHit | Field::MintHit | Field::Failed => Kind::Flag,
+            Field::JitoTip | Field::PriorityFee | Field::SolAmount => {
+                Kind::Number { lamports: true }
+            }
+            Field::Slot | Field::CuPrice | Field::CuLimit | Field::TokenAmount => {
+                Kind::Number { lamports: false }
+            }
+            Field::Signature
+            | Field::Program
+            | Field::Name
+            | Field::Symbol
+            | Field::Instruction => Kind::Text,
+            Field::Signer | Field::FeePayer | Field::Wallet | Field::Mint => Kind::Key,
+            Field::Action => Kind::Action,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Keys {
+    Inline(Vec<Pubkey>),
+    List(Arc<KeySet>),
+}
+
+impl Keys {
+    fn contains(&self, key: &Pubkey) -> bool {
+        match self {
+            Keys::Inline(keys) => keys.contains(key),
+            Keys::List(set) => set.contains(key),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Flag(Field),
+    Number(Field, Cmp, u64),
+    Text(Field, Vec<String>),
+    Key(Field, Keys),
+    Action(Vec<WatchAction>),
+}
+
+impl Expr {
+    fn eval(&self, event: &WatchEvent, detail: Option<&MintDetail>) -> bool {
+        match self {
+            Expr::And(left, right) => left.eval(event, detail) && right.eval(event, detail),
+            Expr::Or(left, right) => left.eval(event, detail) || right.eval(event, detail),
+            Expr::Not(inner) => !inner.eval(event, detail),
+            Expr::Flag(field) => match field {
+                Field::ProgramHit => event.hit.program_hit,
+                Field::AuthorityHit => event.hit.authority_hit,
+                Field::MintHit => event.hit.mint_hit,
+                Field::Failed => event.outcome.as_ref().is_some_and(|o| o.failed),
+                _ => false,
+            },
+            Expr::Number(field, cmp, want) => {
+                number(*field, event, detail).is_some_and(|have| cmp.holds(have, *want))
+            }
+            Expr::Text(field, want) => {
+                any_text(*field, event, detail, |have| want.iter().any(|w| w == have))
+            }
+            Expr::Key(field, want) => {
+                let hit = &event.hit;
+                match field {
+                    Field::Signer => hit.signers.iter().any(|key| want.contains(key)),
+                    Field::FeePayer => hit.signers.first().is_some_and(|key| want.contains(key)),
+                    Field::Wallet => hit.wallets.iter().any(|key| want.contains(key)),
+                    Field::Mint => detail.is_some_and(|d| want.contains(&d.mint)),
+                    _ => false,
+                }
+            }
+            Expr::Action(want) => detail
+                .and_then(|d| d.action)
+                .is_some_and(|action| want.contains(&action)),
+        }
+    }
+}
+
+fn number(field: Field, event: &WatchEvent, detail: Option<&MintDetail>) -> Option<u64> {
+    match field {
+        Field::Slot => Some(event.slot),
+        Field::JitoTip => Some(event.jito_tip_lamports),
+        Field::PriorityFee => Some(event.compute_budget.priority_fee_lamports),
+        Field::CuPrice => event.compute_budget.unit_price,
+        Field::CuLimit => event.compute_budget.unit_limit.map(u64::from),
+        Field::SolAmount => detail.and_then(MintDetail::lamports),
+        Field::TokenAmount => detail.and_then(|d| d.token_amount).map(|a| a.value),
+        _ => None,
+    }
+}
+
+fn any_text(
+    field: Field,
+    event: &WatchEvent,
+    detail: Option<&MintDetail>,
+    mut pred: impl FnMut(&str) -> bool,
+) -> bool {
+    match field {
+        Field::Signature => pred(&event.hit.signature.to_string()),
+        // Instruction-only events fall back to the protocols of their decoded instructions.
+        Field::Program => match detail {
+            Some(d) => d.protocol.as_ref().is_some_and(|p| pred(p.as_str())),
+            None => event
+                .instructions
+                .iter()
+                .any(|ix| pred(ix.protocol.as_str())),
+        },
+        Field::Name => detail
+            .and_then(|d| d.metadata.name.as_deref())
+            .is_some_and(pred),
+        Field::Symbol => detail
+            .and_then(|d| d.metadata.symbol.as_deref())
+            .is_some_and(pred),
+        Field::Instruction => event.instructions.iter().any(|ix| pred(&ix.instruction)),
+        _ => false,
+    }
+}
+
+/// A literal on the right-hand side of a comparison.
+enum Value {
+    Bool(bool),
+    Number(u64),
+    Text(String),
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Column reported for errors at the end of the input.
+    end: usize,
+    lists: &'a BTreeMap<String, Arc<KeySet>>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self) -> std::result::Result<Expr, ParseError> {
+        let expr = self.or()?;
+        match self.tokens.get(self.pos) {
+            None => Ok(expr),
+            Some((column, token)) => Err((*column, format!("unexpected {}", token))),
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |(column, _)| *column)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn next(&mut self, expected: &str) -> std::result::Result<(usize, Token), ParseError> {
+        match self.tokens.get(self.pos).cloned() {
+            Some(token) => {
+                self.pos += 1;
+                Ok(token)
+            }
+            None => Err((
+                self.end,
+                format!("expected {}, found end of rule", expected),
+            )),
+        }
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> std::result::Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> std::result::Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> std::result::Result<Expr, ParseError> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let expr = self.or()?;
+            let column = self.column();
+            return match self.next("`)`")? {
+                (_, Token::RParen) => Ok(expr),
+                (_, token) => Err((column, format!("expected `)`, found {}", token))),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> std::result::Result<Expr, ParseError> {
+        let (column, name) = match self.next("a field")? {
+            (column, Token::Ident(name)) => (column, name),
+            (column, token) => return Err((column, format!("expected a field, found {}", token))),
+        };
+        let field = Field::parse(&name).ok_or_else(|| {
+            (
+                column,
+                format!(
+                    "unknown field `{}` (expected one of: {})",
+                    name,
+                    FIELDS.join(", ")
+                ),
+            )
+        })?;
+        let kind = field.kind();
+        match self.peek().cloned() {
+            Some(Token::Cmp(cmp)) => {
+                self.pos += 1;
+                let column = self.column();
+                let value = self.value(kind)?;
+                self.compare(field, &name, cmp, value, column)
+            }
+            Some(Token::Ident(word)) if word == "in" => {
+                self.pos += 1;
+                self.membership(field, &name)
+            }
+            _ if kind == Kind::Flag => Ok(Expr::Flag(field)),
+            _ => Err((
+                self.column(),
+                format!("expected a comparison or `in` after `{}`", name),
+            )),
+        }
+    }
+
+    fn value(&mut self, kind: Kind) -> std::result::Result<Value, ParseError> {
+        match self.next("a value")? {
+            (_, Token::Str(text)) => Ok(Value::Text(text)),
+            (_, Token::Ident(word)) if word == "true" || word == "false" => {
+                Ok(Value::Bool(word == "true"))
+            }
+            (column, Token::Num(number)) => {
+                let unit = match self.peek() {
+                    Some(Token::Ident(unit))
+                        if matches!(unit.as_str(), "SOL" | "sol" | "lamports") =>
+                    {
+                        let unit = unit.clone();
+                        self.pos += 1;
+                        Some(unit)
+                    }
+                    _ => None,
+                };
+                if unit.is_some() && kind != (Kind::Number { lamports: true }) {
+                    return Err((column, "units only apply to lamport amounts".to_string()));
+                }
+                parse_number(&number, unit.as_deref())
+                    .map(Value::Number)
+                    .map_err(|message| (column, message))
+            }
+            (column, token) => Err((column, format!("expected a value, found {}", token))),
+        }
+    }
+
+    fn compare(
+        &self,
+        field: Field,
+        name: &str,
+        cmp: Cmp,
+        value: Value,
+        column: usize,
+    ) -> std::result::Result<Expr, ParseError> {
+        let kind = field.kind();
+        if !matches!(kind, Kind::Number { .. }) && !matches!(cmp, Cmp::Eq | Cmp::Ne) {
+            return Err((column, format!("`{}` only applies to numeric fields", cmp)));
+        }
+        let expr = match (kind, value) {
+            (Kind::Number { .. }, Value::Number(n)) => return Ok(Expr::Number(field, cmp, n)),
+            (Kind::Flag, Value::Bool(b)) => {
+                let flag = Expr::Flag(field);
+                return Ok(if b == (cmp == Cmp::Eq) {
+                    flag
+                } else {
+                    Expr::Not(Box::new(flag))
+                });
+            }
+            (Kind::Text, Value::Text(text)) => Expr::Text(field, vec![text]),
+            (Kind::Key, Value::Text(text)) => {
+                Expr::Key(field, Keys::Inline(vec![parse_key(&text, column)?]))
+            }
+            (Kind::Action, Value::Text(text)) => Expr::Action(vec![parse_action(&text, column)?]),
+            (kind, _) => return Err((column, format!("`{}` expects {}", name, kind_name(kind)))),
+        };
+        Ok(if cmp == Cmp::Ne {
+            Expr::Not(Box::new(expr))
+        } else {
+            expr
+        })
+    }
+
+    fn membership(&mut self, field: Field, name: &str) -> std::result::Result<Expr, ParseError> {
+        let kind = field.kind();
+        match self.next("`@list` or `[...]`")? {
+            (column, Token::List(list)) => {
+                if kind != Kind::Key {
+                    return Err((column, format!("`@{}` only applies to key fields", list)));
+                }
+                let set = self
+                    .lists
+                    .get(&list)
+                    .ok_or_else(|| (column, format!("unknown list `@{}`", list)))?;
+                Ok(Expr::Key(field, Keys::List(set.clone())))
+            }
+            (_, Token::LBracket) => {
+                let mut values = Vec::new();
+                loop {
+                    let column = self.column();
+                    match self.next("a value")? {
+                        (_, Token::Str(text)) => values.push((column, text)),
+                        (column, token) => {
+                            return Err((column, format!("expected a string, found {}", token)))
+                        }
+                    }
+                    let column = self.column();
+                    match self.next("`,` or `]`")? {
+                        (_, Token::Comma) => {}
+                        (_, Token::RBracket) => break,
+                        (_, token) => {
+                            return Err((column, format!("expected `,` or `]`, found {}", token)))
+                        }
+                    }
+                }
+                match kind {
+                    Kind::Text => Ok(Expr::Text(
+                        field,
+                        values.into_iter().map(|(_, text)| text).collect(),
+                    )),
+                    Kind::Key => values
+                        .iter()
+                        .map(|(column, text)| parse_key(text, *column))
+                        .collect::<std::result::Result<_, _>>()
+                        .map(|keys| Expr::Key(field, Keys::Inline(keys))),
+                    Kind::Action => values
+                        .iter()
+                        .map(|(column, text)| parse_action(text, *column))
+                        .collect::<std::result::Result<_, _>>()
+                        .map(Expr::Action),
+                    _ => Err((self.column(), format!("`in` does not apply to `{}`", name))),
+                }
+            }
+            (column, token) => Err((
+                column,
+                format!("expected `@list` or `[...]`, found {}", token),
+            )),
+        }
+    }
+}
+
+fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Flag => "`true` or `false`",
+        Kind::Number { lamports: true } => "an amount (e.g. `5 SOL` or `1000 lamports`)",
+        Kind::Number { lamports: false } => "a number",
+        Kind::Text => "a string",
+        Kind::Key => "a base58 pubkey string",
+        Kind::Action => "an action string",
+    }
+}
+
+fn parse_key(text: &str, column: usize) -> std::result::Result<Pubkey, ParseError> {
+    Pubkey::from_str(text).map_err(|_| (column, format!("invalid pubkey {:?}", text)))
+}
+
+fn parse_action(text: &str, column: usize) -> std::result::Result<WatchAction, ParseError> {
+    serde_json::from_value(serde_json::Value::String(text.to_string()))
+        .map_err(|_| (column, format!("unknown action {:?}", text)))
+}
+
+/// Integer, or a decimal amount of SOL (up to 9 fractional digits) converted to lamports.
+fn parse_number(number: &str, unit: Option<&str>) -> std::result::Result<u64, String> {
+    let digits = number.replace('_', "");
+    let invalid = || format!("invalid number `{}`", number);
+    if !matches!(unit, Some("SOL" | "sol")) {
+        return digits.parse().map_err(|_| invalid());
+    }
+    let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+    if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: u64 = format!("{:0<9}", fraction).parse().map_err(|_| invalid())?;
+    whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|lamports| lamports.checked_add(fraction))
+        .ok_or_else(|| format!("`{} SOL` is too large", number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txn::{Amount, ProgramHit, Protocol};
+    use solana_sdk::signature::Signature;
+
+    const WHALE: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const OTHER: Pubkey = Pubkey::new_from_array([2u8; 32]);
+
+    fn detail(mint: u8, action: WatchAction, lamports: Option<u64>) -> MintDetail {
+        MintDetail {
+            protocol: Some(Protocol::Pump),
+            action: Some(action),
+            sol_amount: lamports.map(Amount::lamports),
+            ..MintDetail::new(Pubkey::new_from_array([mint; 32]))
+        }
+    }
+
+    fn event(signer: Pubkey, details: Vec<MintDetail>) -> WatchEvent {
+        WatchEvent {
+            slot: 9,
+            hit: ProgramHit {
+                signature: Signature::default(),
+                program_hit: true,
+                authority_hit: false,
+                mint_hit: false,
+                signers: vec![signer],
+                wallets: Vec::new(),
+                mints: Vec::new(),
+                token_instructions: None,
+            },
+            details,
+            compute_budget: Default::default(),
+            jito_tip_lamports: 0,
+            instructions: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    fn rules(source: &str) -> Result<WatchRules> {
+        let rules = BTreeMap::from([("whale_buys".to_string(), source.to_string())]);
+        let lists = BTreeMap::from([(
+            "whales".to_string(),
+            RuleList::Keys(vec![WHALE.to_string()]),
+        )]);
+        WatchRules::compile(&rules, &lists)
+    }
+
+    #[test]
+    fn rule_keeps_matching_details() {
+        let rules = rules(
+            r#"program == "pump" && action in ["buy", "create"] && sol_amount >= 0.5 SOL
+               && (signer in @whales || jito_tip > 1_000) && !mint_hit"#,
+        )
+        .expect("compile");
+        let rule = rules.get("whale_buys").expect("rule");
+        let details = vec![
+            detail(3, WatchAction::Buy, Some(400_000_000)),
+            detail(4, WatchAction::Buy, Some(500_000_000)),
+            detail(5, WatchAction::Sell, Some(9_000_000_000)),
+            detail(6, WatchAction::Create, None),
+        ];
+
+        let kept = rule
+            .apply(&event(WHALE, details.clone()))
+            .expect("whale buy");
+        assert_eq!(kept.details.len(), 1);
+        assert_eq!(kept.details[0].mint, Pubkey::new_from_array([4; 32]));
+        assert!(rule.apply(&event(OTHER, details.clone())).is_none());
+
+        let mut tipped = event(OTHER, details);
+        tipped.jito_tip_lamports = 5_000;
+        assert!(rule.apply(&tipped).is_some());
+    }
+
+    #[test]
+    fn invalid_rules_report_position() {
+        let error = |source: &str| rules(source).unwrap_err().to_string();
+        assert_eq!(
+            error(r#"action == "buy" && sol >= 5 SOL"#),
+            format!(
+                "Configuration error: rule whale_buys: unknown field `sol` (expected one of: {}) at column 20",
+                FIELDS.join(", ")
+            )
+        );
+        assert_eq!(
+            error(r#"action == "bye""#),
+            r#"Configuration error: rule whale_buys: unknown action "bye" at column 11"#
+        );
+        assert_eq!(
+            error("signer in @sharks"),
+            "Configuration error: rule whale_buys: unknown list `@sharks` at column 11"
+        );
+        assert_eq!(
+            error("slot >= 5 SOL"),
+            "Configuration error: rule whale_buys: units only apply to lamport amounts at column 9"
+        );
+        assert_eq!(
+            error("(program_hit"),
+            "Configuration error: rule whale_buys: expected `)`, found end of rule at column 13"
+        );
+    }
+}
@@ -0,0 +1,875 @@
+//! Pluggable sinks for structured watch events.
+//!
+//! A sink receives every [`WatchEvent`] produced by `collect_watch_events` after its own
+//! filter has been applied. Built-in sinks cover stdout JSON lines, a rotating local file,
+//! an HTTP webhook (retry + HMAC-SHA256 signing) and UDP datagrams; implement [`WatchSink`]
+//! to add your own.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{LineWriter, Write},
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use hmac::{Hmac, Mac};
+use log::{error, warn};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::Sha256;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+
+use crate::{
+    shreds_udp::WatchEvent,
+    txn::WatchAction,
+    watch_rule::{WatchRule, WatchRules},
+    Result, SolanaStreamError,
+};
+
+pub const DEFAULT_FILE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+pub const DEFAULT_FILE_MAX_FILES: usize = 5;
+pub const DEFAULT_FILE_QUEUE: usize = 1024;
+pub const DEFAULT_WEBHOOK_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DEFAULT_WEBHOOK_QUEUE: usize = 1024;
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-watch-signature";
+
+/// Keys every `sinks[]` entry accepts: the name, the type tag and the [`WatchSinkFilter`].
+const SINK_COMMON_FIELDS: &[&str] = &[
+    "name",
+    "type",
+    "min_lamports",
+    "actions",
+    "mints",
+    "program_hit_only",
+    "rules",
+];
+
+const WEBHOOK_RETRY_BASE: Duration = Duration::from_millis(200);
+const MAX_UDP_DATAGRAM: usize = 65_507;
+
+/// Destination for structured watch events.
+///
+/// `emit` is called from the receive loop, so implementations should hand slow work
+/// (network or disk I/O) off to a background task instead of blocking.
+pub trait WatchSink: Send + Sync {
+    fn name(&self) -> &str;
+    fn emit(&self, event: &WatchEvent);
+}
+
+/// Per-sink event filter. An empty filter forwards every event unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WatchSinkFilter {
+    /// Drop details whose SOL amount is known and below this lamport threshold (0 = no filter).
+    #[serde(default)]
+    pub min_lamports: u64,
+    /// Only forward details with one of these actions (e.g. `["create", "buy"]`).
+    #[serde(default)]
+    pub actions: Vec<WatchAction>,
+    /// Only forward details for these mints (base58), parsed by
+    /// [`WatchSinkFilter::parse_mints`] when the sink is registered.
+    #[serde(default)]
+    pub mints: Vec<String>,
+    /// Only forward events that matched a watched program.
+    #[serde(default)]
+    pub program_hit_only: bool,
+    /// Only forward details matching at least one of these named rules (see [`WatchRules`]).
+    #[serde(default)]
+    pub rules: Vec<String>,
+    /// `rules`, compiled by [`WatchSinkFilter::with_rules`].
+    #[serde(skip)]
+    compiled_rules: Vec<WatchRule>,
+    /// `mints`, parsed by [`WatchSinkFilter::parse_mints`].
+    #[serde(skip)]
+    parsed_mints: Vec<Pubkey>,
+}
+
+impl WatchSinkFilter {
+    /// Parse `mints`; an invalid pubkey is a configuration error.
+    pub fn parse_mints(mut self) -> Result<Self> {
+        self.parsed_mints = self
+            .mints
+            .iter()
+            .map(|mint| {
+                Pubkey::from_str(mint).map_err(|_| {
+                    SolanaStreamError::Configuration(format!(
+                        "sink mints: invalid pubkey {:?}",
+                        mint
+                    ))
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Resolve `rules` against the compiled rules of the settings file.
+    pub fn with_rules(mut self, rules: &WatchRules) -> Result<Self> {
+        self.compiled_rules = self
+            .rules
+            .iter()
+            .map(|name| {
+                rules.get(name).cloned().ok_or_else(|| {
+                    SolanaStreamError::Configuration(format!(
+                        "sink rules: unknown rule {:?} (declare it under \"rules\")",
+                        name
+                    ))
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Return the event with non-matching details removed, or `None` if nothing is left.
+    pub fn apply(&self, event: &WatchEvent) -> Option<WatchEvent> {
+        if self.program_hit_only && !event.hit.program_hit {
+            return None;
+        }
+        let rules = &self.compiled_rules;
+        if event.details.is_empty()
+            && !rules.is_empty()
+            && !rules.iter().any(|rule| rule.matches(event, None))
+        {
+            return None;
+        }
+        let mints = &self.parsed_mints;
+        let details: Vec<_> = event
+            .details
+            .iter()
+            .filter(|d| {
+                self.min_lamports == 0
+                    || d.lamports().map(|l| l >= self.min_lamports).unwrap_or(true)
+            })
+            .filter(|d| {
+                self.actions.is_empty()
+                    || d.action.map(|a| self.actions.contains(&a)).unwrap_or(false)
+            })
+            .filter(|d| mints.is_empty() || mints.contains(&d.mint))
+            .filter(|d| rules.is_empty() || rules.iter().any(|rule| rule.matches(event, Some(d))))
+            .cloned()
+            .collect();
+        // Instruction-only events pass unless the filter targets details.
+        let detail_filter = self.min_lamports != 0
+            || !self.actions.is_empty()
+            || !mints.is_empty()
+            || (!rules.is_empty() && !event.details.is_empty());
+        if details.is_empty() && (detail_filter || event.instructions.is_empty()) {
+            return None;
+        }
+        Some(WatchEvent {
+            slot: event.slot,
+            hit: event.hit.clone(),
+            details,
+            compute_budget: event.compute_budget,
+            jito_tip_lamports: event.jito_tip_lamports,
+            instructions: event.instructions.clone(),
+            outcome: event.outcome.clone(),
+        })
+    }
+}
+
+/// Sink declaration as it appears in `settings.jsonc` under `"sinks"`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchSinkConfig {
+    /// Optional display name; defaults to the sink type.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub kind: WatchSinkKind,
+    #[serde(flatten)]
+    pub filter: WatchSinkFilter,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WatchSinkKind {
+    /// One JSON object per line on stdout.
+    Stdout,
+    /// JSON lines appended to `path`, rotated to `path.1..path.N` once `max_bytes` is reached.
+    File {
+        path: String,
+        #[serde(default)]
+        max_bytes: Option<u64>,
+        #[serde(default)]
+        max_files: Option<usize>,
+    },
+    /// HTTP POST of each event; signed with HMAC-SHA256 when a secret is configured.
+    Webhook {
+        url: String,
+        #[serde(default)]
+        secret: Option<String>,
+        /// Read the HMAC secret from this env var instead of the settings file.
+        #[serde(default)]
+        secret_env: Option<String>,
+        #[serde(default)]
+        max_retries: Option<u32>,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    /// One JSON datagram per event sent to `target` (`ip:port`).
+    Udp { target: String },
+}
+
+impl WatchSinkKind {
+    /// Keys specific to the sink type tagged `type_name`, or `None` for an unknown type.
+    fn fields(type_name: &str) -> Option<&'static [&'static str]> {
+        Some(match type_name {
+            "stdout" => &[],
+            "file" => &["path", "max_bytes", "max_files"],
+            "webhook" => &["url", "secret", "secret_env", "max_retries", "timeout_ms"],
+            "udp" => &["target"],
+            _ => return None,
+        })
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            WatchSinkKind::Stdout => "stdout",
+            WatchSinkKind::File { .. } => "file",
+            WatchSinkKind::Webhook { .. } => "webhook",
+            WatchSinkKind::Udp { .. } => "udp",
+        }
+    }
+}
+
+impl WatchSinkConfig {
+    /// Keys of a raw `sinks[]` entry that neither its sink type nor the filter reads.
+    ///
+    /// The type and filter are flattened into one object, so serde cannot reject these itself.
+    /// Entries that are not objects or have no known `type` yield nothing; deserializing them
+    /// reports the problem.
+    pub fn unknown_fields(raw: &Value) -> Vec<String> {
+        let Some(map) = raw.as_object() else {
+            return Vec::new();
+        };
+        let Some(kind_fields) = map
+            .get("type")
+            .and_then(Value::as_str)
+            .and_then(WatchSinkKind::fields)
+        else {
+            return Vec::new();
+        };
+        map.keys()
+            .filter(|key| {
+                !SINK_COMMON_FIELDS.contains(&key.as_str()) && !kind_fields.contains(&key.as_str())
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Instantiate the configured sink. Webhook sinks spawn a background task, so this must
+    /// run inside a tokio runtime.
+    pub fn build(&self) -> Result<Arc<dyn WatchSink>> {
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| self.kind.type_name().to_string());
+        let sink: Arc<dyn WatchSink> = match &self.kind {
+            WatchSinkKind::Stdout => Arc::new(StdoutSink::new(name)),
+            WatchSinkKind::File {
+                path,
+                max_bytes,
+                max_files,
+            } => Arc::new(FileSink::open(
+                name,
+                path,
+                max_bytes.unwrap_or(DEFAULT_FILE_MAX_BYTES),
+                max_files.unwrap_or(DEFAULT_FILE_MAX_FILES),
+            )?),
+            WatchSinkKind::Webhook {
+                url,
+                secret,
+                secret_env,
+                max_retries,
+                timeout_ms,
+            } => {
+                let secret = match secret_env {
+                    Some(var) => Some(std::env::var(var).map_err(|_| {
+                        SolanaStreamError::Configuration(format!(
+                            "webhook sink {name}: env {var} is not set"
+                        ))
+                    })?),
+                    None => secret.clone(),
+                };
+                Arc::new(WebhookSink::spawn(
+                    name,
+                    url,
+                    secret,
+                    max_retries.unwrap_or(DEFAULT_WEBHOOK_MAX_RETRIES),
+                    timeout_ms
+                        .map(Duration::from_millis)
+                        .unwrap_or(DEFAULT_WEBHOOK_TIMEOUT),
+                )?)
+            }
+            WatchSinkKind::Udp { target } => Arc::new(UdpSink::connect(name, target)?),
+        };
+        Ok(sink)
+    }
+
+    /// Problems that would make [`WatchSinkConfig::build`] fail or silently drop filter
+    /// entries; used by strict config validation.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for mint in &self.filter.mints {
+            if Pubkey::from_str(mint).is_err() {
+                issues.push(format!("mints: invalid pubkey {:?}", mint));
+            }
+        }
+        match &self.kind {
+            WatchSinkKind::Stdout => {}
+            WatchSinkKind::File {
+                path, max_files, ..
+            } => {
+                if path.trim().is_empty() {
+                    issues.push("path: must not be empty".to_string());
+                }
+                if *max_files == Some(0) {
+                    issues.push("max_files: must be at least 1".to_string());
+                }
+            }
+            WatchSinkKind::Webhook {
+                url, secret_env, ..
+            } => {
+                match url::Url::parse(url) {
+                    Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                    Ok(parsed) => issues.push(format!(
+                        "url: unsupported scheme {:?} (expected http or https)",
+                        parsed.scheme()
+                    )),
+                    Err(e) => issues.push(format!("url: {}", e)),
+                }
+                if let Some(var) = secret_env {
+                    if std::env::var(var).is_err() {
+                        issues.push(format!("secret_env: env {} is not set", var));
+                    }
+                }
+            }
+            WatchSinkKind::Udp { target } => {
+                if target.to_socket_addrs().is_err() {
+                    issues.push(format!("target: cannot resolve {:?}", target));
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// A set of sinks, each paired with its own filter.
+#[derive(Clone, Default)]
+pub struct WatchSinks {
+    sinks: Vec<(WatchSinkFilter, Arc<dyn WatchSink>)>,
+}
+
+impl WatchSinks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build every sink declared in the config; fails on the first invalid declaration.
+    pub fn from_configs(configs: &[WatchSinkConfig]) -> Result<Self> {
+        Self::from_configs_with_rules(configs, &WatchRules::default())
+    }
+
+    /// Like [`WatchSinks::from_configs`], resolving each sink's `rules` against `rules`.
+    pub fn from_configs_with_rules(
+        configs: &[WatchSinkConfig],
+        rules: &WatchRules,
+    ) -> Result<Self> {
+        let mut sinks = Self::new();
+        for cfg in configs {
+            sinks.push(cfg.filter.clone().with_rules(rules)?, cfg.build()?)?;
+        }
+        Ok(sinks)
+    }
+
+    /// Register a custom sink; fails if the filter lists an invalid mint.
+    pub fn push(&mut self, filter: WatchSinkFilter, sink: Arc<dyn WatchSink>) -> Result<()> {
+        self.sinks.push((filter.parse_mints()?, sink));
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// Send each event to every sink whose filter keeps at least one detail.
+    pub fn dispatch(&self, events: &[WatchEvent]) {
+        for event in events {
+            for (filter, sink) in &self.sinks {
+                if let Some(filtered) = filter.apply(event) {
+                    sink.emit(&filtered);
+                }
+            }
+        }
+    }
+}
+
+/// JSON representation shared by the built-in sinks (the serde form of [`WatchEvent`]).
+pub fn watch_event_json(event: &WatchEvent) -> Value {
+    serde_json::to_value(event).unwrap_or(Value::Null)
+}
+
+/// Hex-encoded HMAC-SHA256 of `body`, as sent in the `x-watch-signature` header.
+pub fn sign_webhook_body(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+pub struct StdoutSink {
+    name: String,
+}
+
+impl StdoutSink {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl WatchSink for StdoutSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn emit(&self, event: &WatchEvent) {
+        println!("{}", watch_event_json(event));
+    }
+}
+
+/// Size-rotated JSON lines file. Events are queued and written, with rotation, by a
+/// dedicated writer thread, so a slow disk never stalls the receive loop; the queue drops
+/// events (with a warning) when full. Dropping the sink flushes what is queued.
+pub struct FileSink {
+    name: String,
+    tx: Option<mpsc::Sender<String>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+/// State owned by the [`FileSink`] writer thread.
+struct FileWriter {
+    name: String,
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    writer: LineWriter<File>,
+    written: u64,
+}
+
+impl FileSink {
+    /// Open (or append to) `path` and start the writer thread. Open errors are returned here
+    /// rather than logged later.
+    pub fn open(
+        name: impl Into<String>,
+        path: impl Into<PathBuf>,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> Result<Self> {
+        let name = name.into();
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut state = FileWriter {
+            name: name.clone(),
+            written: file.metadata()?.len(),
+            path,
+            max_bytes: max_bytes.max(1),
+            max_files,
+            writer: LineWriter::new(file),
+        };
+        let (tx, mut rx) = mpsc::channel::<String>(DEFAULT_FILE_QUEUE);
+        let writer = thread::Builder::new()
+            .name(format!("file-sink-{name}"))
+            .spawn(move || {
+                while let Some(line) = rx.blocking_recv() {
+                    state.write(&line);
+                }
+                if let Err(e) = state.writer.flush() {
+                    error!("file sink {}: flush failed: {}", state.name, e);
+                }
+            })?;
+        Ok(Self {
+            name,
+            tx: Some(tx),
+            writer: Some(writer),
+        })
+    }
+}
+
+impl FileWriter {
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut raw = self.path.clone().into_os_string();
+        raw.push(format!(".{n}"));
+        PathBuf::from(raw)
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.writer.flush()?;
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..self.max_files).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.writer = LineWriter::new(file);
+        self.written = 0;
+        Ok(())
+    }
+
+    fn write(&mut self, line: &str) {
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            if let Err(e) = self.rotate() {
+                error!(
+                    "file sink {}: rotate {} failed: {}",
+                    self.name,
+                    self.path.display(),
+                    e
+                );
+            }
+        }
+        match self.writer.write_all(line.as_bytes()) {
+            Ok(()) => self.written += line.len() as u64,
+            Err(e) => error!("file sink {}: write failed: {}", self.name, e),
+        }
+    }
+}
+
+impl WatchSink for FileSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn emit(&self, event: &WatchEvent) {
+        let line = format!("{}\n", watch_event_json(event));
+        let sent = self.tx.as_ref().is_some_and(|tx| tx.try_send(line).is_ok());
+        if !sent {
+            warn!(
+                "file sink {}: queue full or closed, dropping event",
+                self.name
+            );
+        }
+    }
+}
+
+impl Drop for FileSink {
+    fn drop(&mut self) {
+        // Closing the queue lets the writer drain it, flush and exit.
+        self.tx.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// HTTP webhook sink. Events are queued and POSTed by a background task with
+/// exponential backoff; the queue drops events (with a warning) when full.
+pub struct WebhookSink {
+    name: String,
+    tx: mpsc::Sender<Vec<u8>>,
+}
+
+impl WebhookSink {
+    pub fn spawn(
+        name: impl Into<String>,
+        url: &str,
+        secret: Option<String>,
+        max_retries: u32,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let name = name.into();
+        let url = url::Url::parse(url)?;
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| SolanaStreamError::Configuration(format!("webhook sink {name}: {e}")))?;
+        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(DEFAULT_WEBHOOK_QUEUE);
+        let task_name = name.clone();
+        tokio::spawn(async move {
+            while let Some(body) = rx.recv().await {
+                let signature = secret
+                    .as_ref()
+                    .map(|s| format!("sha256={}", sign_webhook_body(s.as_bytes(), &body)));
+                let mut attempt = 0;
+                loop {
+                    let mut req = client
+                        .post(url.clone())
+                        .header(reqwest::header::CONTENT_TYPE, "application/json")
+                        .body(body.clone());
+                    if let Some(sig) = &signature {
+                        req = req.header(WEBHOOK_SIGNATURE_HEADER, sig);
+                    }
+                    let err = match req.send().await {
+                        Ok(resp) if resp.status().is_success() => break,
+                        Ok(resp) => format!("status {}", resp.status()),
+                        Err(e) => e.to_string(),
+                    };
+                    if attempt >= max_retries {
+                        error!(
+                            "webhook sink {}: giving up after {} attempts: {}",
+                            task_name,
+                            attempt + 1,
+                            err
+                        );
+                        break;
+                    }
+                    tokio::time::sleep(WEBHOOK_RETRY_BASE * 2u32.saturating_pow(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        });
+        Ok(Self { name, tx })
+    }
+}
+
+impl WatchSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn emit(&self, event: &WatchEvent) {
+        let body = watch_event_json(event).to_string().into_bytes();
+        if self.tx.try_send(body).is_err() {
+            warn!(
+                "webhook sink {}: queue full or closed, dropping event",
+                self.name
+            );
+        }
+    }
+}
+
+/// Sends each event as a single JSON datagram.
+pub struct UdpSink {
+    name: String,
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl UdpSink {
+    pub fn connect(name: impl Into<String>, target: &str) -> Result<Self> {
+        let name = name.into();
+        let target = target.to_socket_addrs()?.next().ok_or_else(|| {
+            SolanaStreamError::Configuration(format!("udp sink {name}: cannot resolve {target}"))
+        })?;
+        let bind = if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            name,
+            socket,
+            target,
+        })
+    }
+}
+
+impl WatchSink for UdpSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn emit(&self, event: &WatchEvent) {
+        let body = watch_event_json(event).to_string();
+        if body.len() > MAX_UDP_DATAGRAM {
+            warn!(
+                "udp sink {}: event for {} exceeds datagram size ({} bytes), dropping",
+                self.name,
+                event.hit.signature,
+                body.len()
+            );
+            return;
+        }
+        if let Err(e) = self.socket.send_to(body.as_bytes(), self.target) {
+            warn!(
+                "udp sink {}: send to {} failed: {}",
+                self.name, self.target, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txn::{Amount, MintDetail, ProgramHit};
+    use solana_sdk::signature::Signature;
+
+    fn detail(mint: Pubkey, action: WatchAction, sol_amount: Option<u64>) -> MintDetail {
+        MintDetail {
+            action: Some(action),
+            sol_amount: sol_amount.map(Amount::lamports),
+            ..MintDetail::new(mint)
+        }
+    }
+
+    fn event(details: Vec<MintDetail>) -> WatchEvent {
+        WatchEvent {
+            slot: 7,
+            hit: ProgramHit {
+                signature: Signature::default(),
+                program_hit: true,
+                authority_hit: false,
+                mint_hit: false,
+                signers: Vec::new(),
+                wallets: Vec::new(),
+                mints: Vec::new(),
+                token_instructions: None,
+            },
+            details,
+            compute_budget: Default::default(),
+            jito_tip_lamports: 0,
+            instructions: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    #[test]
+    fn filter_applies_threshold_actions_and_mints() {
+        let small = Pubkey::new_from_array([1u8; 32]);
+        let large = Pubkey::new_from_array([2u8; 32]);
+        let create = Pubkey::new_from_array([3u8; 32]);
+        let ev = event(vec![
+            detail(small, WatchAction::Buy, Some(10)),
+            detail(large, WatchAction::Buy, Some(500)),
+            detail(create, WatchAction::Create, None),
+        ]);
+
+        let threshold = WatchSinkFilter {
+            min_lamports: 100,
+            ..Default::default()
+        };
+        let kept = threshold.apply(&ev).expect("details left");
+        assert_eq!(
+            kept.details.iter().map(|d| d.mint).collect::<Vec<_>>(),
+            vec![large, create]
+        );
+
+        let creates_only = WatchSinkFilter {
+            actions: vec![WatchAction::Create],
+            ..Default::default()
+        };
+        let kept = creates_only.apply(&ev).expect("create left");
+        assert_eq!(kept.details.len(), 1);
+        assert_eq!(kept.details[0].mint, create);
+
+        let other_mint = WatchSinkFilter {
+            mints: vec![Pubkey::new_from_array([9u8; 32]).to_string()],
+            ..Default::default()
+        };
+        assert!(other_mint.parse_mints().unwrap().apply(&ev).is_none());
+
+        let bad_mint = WatchSinkFilter {
+            mints: vec!["not-a-mint".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            WatchSinks::new().push(bad_mint, Arc::new(StdoutSink::new("stdout"))),
+            Err(SolanaStreamError::Configuration(e)) if e.contains("not-a-mint")
+        ));
+    }
+
+    #[test]
+    fn parses_sink_configs_from_jsonc() {
+        let raw = r#"[
+            // stdout with a threshold
+            { "type": "stdout", "min_lamports": 5 },
+            { "type": "webhook", "name": "alerts", "url": "https://example.com/h", "actions": ["create"] },
+            { "type": "udp", "target": "127.0.0.1:9900" }
+        ]"#;
+        let cfgs: Vec<WatchSinkConfig> = serde_jsonc::from_str(raw).expect("parse sinks");
+        assert_eq!(cfgs.len(), 3);
+        assert!(matches!(cfgs[0].kind, WatchSinkKind::Stdout));
+        assert_eq!(cfgs[0].filter.min_lamports, 5);
+        assert_eq!(cfgs[1].name.as_deref(), Some("alerts"));
+        assert_eq!(cfgs[1].filter.actions, vec![WatchAction::Create]);
+        assert!(matches!(cfgs[2].kind, WatchSinkKind::Udp { .. }));
+
+        // Typos and keys of another sink type slip through the flattened config.
+        let raw: Value = serde_jsonc::from_str(
+            r#"[{ "type": "stdout", "url": "https://example.com/h", "action": ["buy"], "mints": [] }]"#,
+        )
+        .unwrap();
+        assert!(serde_json::from_value::<Vec<WatchSinkConfig>>(raw.clone()).is_ok());
+        assert_eq!(
+            WatchSinkConfig::unknown_fields(&raw[0]),
+            vec!["action".to_string(), "url".to_string()]
+        );
+    }
+
+    #[test]
+    fn file_sink_rotates_by_size_and_keeps_max_files() {
+        let path =
+            std::env::temp_dir().join(format!("watch-sink-rotate-{}.jsonl", std::process::id()));
+        let rotated = |n: usize| {
+            let mut raw = path.clone().into_os_string();
+            raw.push(format!(".{n}"));
+            PathBuf::from(raw)
+        };
+        for p in [path.clone(), rotated(1), rotated(2), rotated(3)] {
+            let _ = fs::remove_file(p);
+        }
+        let sink = FileSink::open("rotate", &path, 1, 2).expect("open file sink");
+        for n in 0..4 {
+            let mut ev = event(Vec::new());
+            ev.slot = n;
+            sink.emit(&ev);
+        }
+        // Dropping the sink drains the queue and joins the writer thread.
+        drop(sink);
+
+        let slots = [&path, &rotated(1), &rotated(2)].map(|p| {
+            let body = fs::read_to_string(p).expect("read rotated file");
+            let lines: Vec<&str> = body.lines().collect();
+            assert_eq!(lines.len(), 1, "{}", p.display());
+            serde_json::from_str::<Value>(lines[0]).unwrap()["slot"].as_u64()
+        });
+        assert_eq!(slots, [Some(3), Some(2), Some(1)]);
+        assert!(!rotated(3).exists());
+
+        for p in [path.clone(), rotated(1), rotated(2)] {
+            let _ = fs::remove_file(p);
+        }
+    }
+
+    #[test]
+    fn udp_sink_sends_one_json_datagram_per_event() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").expect("bind receiver");
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let target = receiver.local_addr().unwrap().to_string();
+        let sink = UdpSink::connect("udp", &target).expect("connect udp sink");
+
+        sink.emit(&event(Vec::new()));
+
+        let mut buf = [0u8; MAX_UDP_DATAGRAM];
+        let len = receiver.recv(&mut buf).expect("datagram");
+        let body: Value = serde_json::from_slice(&buf[..len]).expect("json datagram");
+        assert_eq!(body["slot"], 7);
+    }
+
+    #[test]
+    fn webhook_signature_matches_rfc4231() {
+        assert_eq!(
+            sign_webhook_body(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
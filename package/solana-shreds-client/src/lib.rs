@@ -7,11 +7,10 @@ use napi_derive::napi;
 use serde::Deserialize;
 use serde_json::json;
 use solana_stream_sdk::{
-    CommitmentLevel as SDKCommitmentLevel, ShredstreamClient, SubscribeEntriesRequest,
-    SubscribeRequestFilterAccounts, SubscribeRequestFilterSlots,
-    SubscribeRequestFilterTransactions,
+    CommitmentLevel as SDKCommitmentLevel, EntriesRequestBuilder, ShredstreamClient,
+    SubscribeEntriesRequest,
 };
-use std::{collections::HashMap, sync::Once};
+use std::sync::Once;
 
 fn install_rustls_provider() {
     static INIT: Once = Once::new();
@@ -41,51 +40,6 @@ impl From<CommitmentLevel> for SDKCommitmentLevel {
     }
 }
 
-#[derive(Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct SimpleFilterAccounts {
-    #[serde(default)]
-    pub account: Vec<String>,
-    #[serde(default)]
-    pub owner: Vec<String>,
-    #[serde(default)]
-    pub filters: Vec<String>,
-    #[serde(default)]
-    pub nonempty_txn_signature: Option<bool>,
-}
-
-#[derive(Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct SimpleFilterTransactions {
-    #[serde(default)]
-    pub account_include: Vec<String>,
-    #[serde(default)]
-    pub account_exclude: Vec<String>,
-    #[serde(default)]
-    pub account_required: Vec<String>,
-}
-
-#[derive(Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct SimpleFilterSlots {
-    #[serde(default)]
-    pub filter_by_commitment: Option<bool>,
-    #[serde(default)]
-    pub interslot_updates: Option<bool>,
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SimpleEntriesRequest {
-    #[serde(default)]
-    pub accounts: Option<HashMap<String, SimpleFilterAccounts>>,
-    #[serde(default)]
-    pub transactions: Option<HashMap<String, SimpleFilterTransactions>>,
-    #[serde(default)]
-    pub slots: Option<HashMap<String, SimpleFilterSlots>>,
-    pub commitment: Option<CommitmentLevel>,
-}
-
 #[napi]
 pub struct ShredsClient {
     endpoint: String,
@@ -107,64 +61,11 @@ impl ShredsClient {
         install_rustls_provider();
         let endpoint = self.endpoint.clone();
 
-        let request: SimpleEntriesRequest = serde_json::from_str(&request_json)
-            .map_err(|e| Error::from_reason(format!("JSON parse error: {}", e)))?;
-
-        let sdk_request = SubscribeEntriesRequest {
-            accounts: request
-                .accounts
-                .unwrap_or_default()
-                .into_iter()
-                .map(|(key, value)| {
-                    (
-                        key,
-                        SubscribeRequestFilterAccounts {
-                            account: value.account,
-                            owner: value.owner,
-                            filters: vec![],
-                            nonempty_txn_signature: Some(
-                                value.nonempty_txn_signature.unwrap_or_default(),
-                            ),
-                        },
-                    )
-                })
-                .collect(),
-
-            transactions: request
-                .transactions
-                .unwrap_or_default()
-                .into_iter()
-                .map(|(key, value)| {
-                    (
-                        key,
-                        SubscribeRequestFilterTransactions {
-                            account_include: value.account_include,
-                            account_exclude: value.account_exclude,
-                            account_required: value.account_required,
-                        },
-                    )
-                })
-                .collect(),
-
-            slots: request
-                .slots
-                .unwrap_or_default()
-                .into_iter()
-                .map(|(key, value)| {
-                    (
-                        key,
-                        SubscribeRequestFilterSlots {
-                            filter_by_commitment: value.filter_by_commitment,
-                            interslot_updates: value.interslot_updates,
-                        },
-                    )
-                })
-                .collect(),
-
-            commitment: request
-                .commitment
-                .map(|c| SDKCommitmentLevel::from(c) as i32),
-        };
+        // Same JSON/JSONC format as the SDK's `EntriesRequestBuilder`, validated before
+        // connecting.
+        let sdk_request = EntriesRequestBuilder::from_jsonc(&request_json)
+            .and_then(|request| request.build())
+            .map_err(|e| Error::from_reason(format!("Invalid entries request: {}", e)))?;
 
         napi::tokio::spawn(async move {
             if let Err(e) = run_stream(endpoint, sdk_request, on_receive_callback).await {
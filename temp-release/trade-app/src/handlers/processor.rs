@@ -56,9 +56,18 @@ async fn handle_update(
             if let Some(tx) = &tx_info.transaction {
                 // Parse account keys from the transaction message.
                 if let Some(msg) = &tx.message {
+                    // Runtime order: static keys, then the addresses loaded from lookup
+                    // tables (writable, then readonly). Instruction and token balance indexes
+                    // address this list.
+                    let loaded = tx_info.meta.iter().flat_map(|meta| {
+                        meta.loaded_writable_addresses
+                            .iter()
+                            .chain(&meta.loaded_readonly_addresses)
+                    });
                     let account_keys: Vec<Pubkey> = msg
                         .account_keys
                         .iter()
+                        .chain(loaded)
                         .filter_map(|k| {
                             if k.len() == 32 {
                                 Some(Pubkey::new_from_array(k.as_slice().try_into().unwrap()))
@@ -81,12 +90,16 @@ async fn handle_update(
                             continue;
                         }
 
-                        // Resolve the instruction's account keys.
-                        let ix_account_keys: Vec<Pubkey> = ix
+                        // Resolve the instruction's account keys; skip instructions whose
+                        // lookup-table accounts are unknown rather than shifting positions.
+                        let Some(ix_account_keys) = ix
                             .accounts
                             .iter()
-                            .filter_map(|&idx| account_keys.get(idx as usize).copied())
-                            .collect();
+                            .map(|&idx| account_keys.get(idx as usize).copied())
+                            .collect::<Option<Vec<Pubkey>>>()
+                        else {
+                            continue;
+                        };
 
                         // ── create_pool detection ──
                         if let Some(detected) =